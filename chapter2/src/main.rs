@@ -1,36 +1,177 @@
-mod elf;
-
 fn main() {
-    let loader = match elf::ElfLoader::try_new("c_src/elfsamp") {
+    let file_path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "c_src/elfsamp".to_string());
+    let loader = match elf::ElfLoader::try_new(&file_path) {
         Ok(loader) => loader,
         Err(error) => panic!("There was a problem opening the file: {:?}", error),
     };
+    if loader.is_archive() {
+        if let Err(error) = print_archive(&loader) {
+            println!("There was a problem reading the archive: {}", error);
+        }
+        return;
+    }
     if !loader.is_elf() {
         println!("Not ELF file!");
         return;
     }
-    let identification = elf::ElfIdentification::new(&loader.mapped_file);
+    if let Err(error) = print_elf(&loader) {
+        println!("There was a problem reading the file: {}", error);
+    }
+}
+
+fn print_elf(loader: &elf::ElfLoader) -> Result<(), elf::ElfError> {
+    let file = loader.elf_file()?;
+    for issue in file.validate().iter() {
+        println!("Warning: {}", issue);
+    }
+    let identification = file.get_identification();
     println!("{}", identification);
 
-    let header = loader.get_elf_header();
+    let header = file.get_elf_header();
     println!("{}", header);
 
-    let program_headers = loader.get_program_headers();
+    let program_headers = file.get_program_headers()?;
     for program_header in program_headers.iter() {
-        println!("{}", program_header);
+        println!("{}", program_header.display(header.machine()));
     }
 
-    let section_headers = loader.get_section_headers();
+    let section_headers = file.get_section_headers()?;
     for section_header in section_headers.iter() {
-        println!("{}", section_header);
+        println!("{}", section_header.display(header.machine()));
     }
-    let names = loader.get_section_names();
+    let names = file.get_section_names()?;
     for name in names.iter() {
         println!("{}", name);
     }
 
-    let table = loader.get_symbol_table();
-    for entry in table.iter() {
+    if !program_headers.is_empty() && !names.is_empty() {
+        print_segment_mapping(&file, &names)?;
+    }
+
+    match file.get_symbols() {
+        Ok(table) => {
+            for entry in table.iter() {
+                println!("{}", entry);
+            }
+        }
+        Err(elf::ElfError::MissingSection { .. }) => {}
+        Err(error) => return Err(error),
+    }
+
+    for need in file.get_version_needs().or_else(ignore_missing)?.iter() {
+        println!("{}", need);
+    }
+    for definition in file
+        .get_version_definitions()
+        .or_else(ignore_missing)?
+        .iter()
+    {
+        println!("{}", definition);
+    }
+
+    for note in file.get_notes()?.iter() {
+        println!("{}", note);
+    }
+
+    if header.elf_type() == elf::ElfType::Core {
+        print_core(&file)?;
+    }
+
+    match file.get_dynamic() {
+        Ok(dynamic) => println!("{}", dynamic),
+        Err(elf::ElfError::MissingSection { .. }) => {}
+        Err(error) => return Err(error),
+    }
+
+    for section in file.get_relocation_sections()?.iter() {
+        println!(
+            "---{} -> {}---",
+            section.name,
+            section.target_name.unwrap_or("")
+        );
+        for relocation in section.relocations() {
+            println!("{}", relocation?);
+        }
+    }
+    Ok(())
+}
+
+fn print_segment_mapping(file: &elf::ElfFile, names: &[&str]) -> Result<(), elf::ElfError> {
+    let mapping = file.get_segment_mapping()?;
+    let join = |indexes: &[usize]| -> String {
+        indexes
+            .iter()
+            .map(|index| names[*index])
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    println!("---Section to Segment mapping---");
+    for (i, sections) in mapping.segments.iter().enumerate() {
+        println!("{:02}     {}", i, join(sections));
+    }
+    println!("None   {}", join(&mapping.unmapped));
+    for (section, segment) in mapping.straddling.iter() {
+        println!(
+            "Warning: {} straddles the boundary of segment {:02}",
+            names[*section], segment
+        );
+    }
+    for section in mapping.uncovered.iter() {
+        println!("Warning: {} is allocated but not loaded", names[*section]);
+    }
+    Ok(())
+}
+
+fn print_archive(loader: &elf::ElfLoader) -> Result<(), elf::ElfError> {
+    let archive = loader.archive()?;
+    println!("---ElfArchive---");
+    for symbol in archive.symbols().iter() {
+        let member = archive.get_member_at(symbol.member_offset);
+        println!(
+            "{} in {}",
+            symbol.name,
+            member.map_or("?", |member| member.name)
+        );
+    }
+    for member in archive.members().iter() {
+        println!("---{} ({} bytes)---", member.name, member.size);
+        if member.is_elf() {
+            println!("{}", member.elf_file()?.get_elf_header());
+        }
+    }
+    Ok(())
+}
+
+fn ignore_missing<T>(error: elf::ElfError) -> Result<Vec<T>, elf::ElfError> {
+    match error {
+        elf::ElfError::MissingSection { .. } => Ok(Vec::new()),
+        error => Err(error),
+    }
+}
+
+fn print_core(file: &elf::ElfFile) -> Result<(), elf::ElfError> {
+    let core = file.get_core()?;
+    if let Some(process) = &core.process {
+        println!("{}", process);
+    }
+    for thread in core.threads.iter() {
+        println!("{}", thread.status.display(core.machine()));
+        if let Some(signal) = &thread.signal {
+            println!("Siginfo   = {}", signal);
+        }
+    }
+    println!("---Auxv---");
+    for entry in core.auxv.iter() {
         println!("{}", entry);
     }
+    println!("---Mapped Files---");
+    for mapped_file in core.mapped_files.iter() {
+        println!("{}", mapped_file);
+    }
+    if let Some(address) = core.get_auxv_value(elf::AuxvType::Execfn) {
+        println!("Execfn    = {}", core.read_string(address)?);
+    }
+    Ok(())
 }
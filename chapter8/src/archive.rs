@@ -0,0 +1,325 @@
+use crate::error::LinkError;
+use crate::input::ObjectFile;
+use crate::link::Input;
+use elf::{ArchiveMember, ElfArchive, ElfError, ElfLoader, SectionIndex, SymbolBinding};
+use std::collections::HashSet;
+
+/// An archive on the command line and the members already pulled from it.
+struct Archive<'a> {
+    path: &'a str,
+    archive: ElfArchive<'a>,
+    /// Header offsets of the extracted members.
+    extracted: HashSet<u64>,
+}
+
+/// An input in command line order, parsed but not yet added to the link.
+enum Item<'a> {
+    Object(ObjectFile<'a>),
+    Archive(Archive<'a>),
+}
+
+/// Objects of the link, with the global names they define and need so far.
+struct Objects<'a> {
+    objects: Vec<ObjectFile<'a>>,
+    defined: HashSet<&'a str>,
+    /// Names with a non weak reference and no definition yet. Weak references never
+    /// extract a member.
+    undefined: HashSet<&'a str>,
+}
+
+impl<'a> Objects<'a> {
+    fn add(&mut self, object: ObjectFile<'a>) {
+        for symbol in object.symbols.iter().skip(1) {
+            if symbol.binding == SymbolBinding::Local {
+                continue;
+            }
+            if symbol.section_index != SectionIndex::Undefined {
+                self.defined.insert(symbol.name);
+                self.undefined.remove(symbol.name);
+            } else if symbol.binding != SymbolBinding::Weak && !self.defined.contains(symbol.name) {
+                self.undefined.insert(symbol.name);
+            }
+        }
+        self.objects.push(object);
+    }
+
+    fn extract(
+        &mut self,
+        archive: &mut Archive<'a>,
+        member: ArchiveMember<'a>,
+    ) -> Result<(), LinkError> {
+        archive.extracted.insert(member.offset);
+        let path = format!("{}({})", archive.path, member.name);
+        let data = member.data().ok_or_else(|| {
+            LinkError::elf(
+                &path,
+                ElfError::ThinArchiveMember {
+                    offset: member.offset,
+                },
+            )
+        })?;
+        let object = ObjectFile::new(path, data)?;
+        self.add(object);
+        Ok(())
+    }
+
+    /// Extracts every member of `archive` that defines a currently undefined name, until
+    /// none is left. Returns whether any member was extracted.
+    fn search(&mut self, archive: &mut Archive<'a>) -> Result<bool, LinkError> {
+        let mut extracted_any = false;
+        loop {
+            let mut extracted = false;
+            for index in 0..archive.archive.symbols().len() {
+                let symbol = archive.archive.symbols()[index];
+                if !self.undefined.contains(symbol.name)
+                    || archive.extracted.contains(&symbol.member_offset)
+                {
+                    continue;
+                }
+                let member = match archive.archive.get_member_at(symbol.member_offset) {
+                    Some(member) => *member,
+                    None => continue,
+                };
+                self.extract(archive, member)?;
+                extracted = true;
+            }
+            if !extracted {
+                return Ok(extracted_any);
+            }
+            extracted_any = true;
+        }
+    }
+
+    /// Extracts every elf member of `archive`, as with `--whole-archive`.
+    /// Members of thin archives are not read, and are reported by `extract`.
+    fn extract_all(&mut self, archive: &mut Archive<'a>) -> Result<(), LinkError> {
+        for index in 0..archive.archive.members().len() {
+            let member = archive.archive.members()[index];
+            if member.is_elf() || member.data().is_none() {
+                self.extract(archive, member)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Object files of the link: the objects of `inputs` and the archive members they need,
+/// in the order the linker sees them.
+///
+/// An archive member is extracted only when it defines a name that is undefined at the
+/// point the archive is reached. Archives of a `--start-group` group are searched again
+/// until a whole pass extracts nothing, so they can depend on each other.
+pub fn load_objects<'a>(
+    inputs: &'a [Input],
+    loaders: &'a [ElfLoader],
+) -> Result<Vec<ObjectFile<'a>>, LinkError> {
+    let mut items = Vec::<Item<'a>>::new();
+    for (input, loader) in inputs.iter().zip(loaders.iter()) {
+        if !loader.is_archive() {
+            let object = ObjectFile::new(input.path.clone(), &loader.mapped_file)?;
+            items.push(Item::Object(object));
+            continue;
+        }
+        let archive = loader
+            .archive()
+            .map_err(|error| LinkError::elf(&input.path, error))?;
+        if !input.whole_archive && archive.symbols().is_empty() && !archive.members().is_empty() {
+            return Err(LinkError::MissingArchiveIndex {
+                path: input.path.clone(),
+            });
+        }
+        items.push(Item::Archive(Archive {
+            path: &input.path,
+            archive,
+            extracted: HashSet::new(),
+        }));
+    }
+
+    let mut objects = Objects {
+        objects: Vec::new(),
+        defined: HashSet::new(),
+        undefined: HashSet::new(),
+    };
+    let mut group = Vec::<Archive<'a>>::new();
+    for (position, (input, item)) in inputs.iter().zip(items).enumerate() {
+        match item {
+            Item::Object(object) => objects.add(object),
+            Item::Archive(mut archive) => {
+                if input.whole_archive {
+                    objects.extract_all(&mut archive)?;
+                } else {
+                    objects.search(&mut archive)?;
+                }
+                if input.group.is_some() {
+                    group.push(archive);
+                }
+            }
+        }
+        let group_ends = match inputs.get(position + 1) {
+            Some(next) => next.group != input.group,
+            None => true,
+        };
+        if input.group.is_some() && group_ends {
+            while search_group(&mut objects, &mut group)? {}
+            group.clear();
+        }
+    }
+    Ok(objects.objects)
+}
+
+/// Searches each archive of a group once. Returns whether any member was extracted.
+fn search_group<'a>(
+    objects: &mut Objects<'a>,
+    group: &mut [Archive<'a>],
+) -> Result<bool, LinkError> {
+    let mut extracted = false;
+    for archive in group.iter_mut() {
+        extracted |= objects.search(archive)?;
+    }
+    Ok(extracted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbols::SymbolTable;
+    use crate::testing::{archive, temp_dir, TestObject};
+    use std::fs;
+    use std::path::Path;
+
+    /// Object defining `defines` and referencing `needs`.
+    fn object(defines: &[&'static str], needs: &[&'static str]) -> Vec<u8> {
+        let mut object = TestObject::new();
+        let text = object.text(&[0xc3; 4]);
+        for name in defines.iter() {
+            object.define(name, SymbolBinding::Global, text, 0);
+        }
+        for name in needs.iter() {
+            object.reference(name, SymbolBinding::Global);
+        }
+        object.build()
+    }
+
+    /// `main.o` needs `a`. In `liba.a`, `a.o` defines `a` and needs `b`, and `unused.o` is
+    /// never needed. `libb.a` has `b.o`, which defines `b`.
+    fn write_inputs(directory: &Path) {
+        let a = object(&["a"], &["b"]);
+        let unused = object(&["unused"], &[]);
+        let b = object(&["b"], &[]);
+        let files = [
+            ("main.o", object(&["_start"], &["a"])),
+            (
+                "liba.a",
+                archive(&[("a.o", &a, &["a"]), ("unused.o", &unused, &["unused"])]),
+            ),
+            ("libb.a", archive(&[("b.o", &b, &["b"])])),
+            (
+                "libab.a",
+                archive(&[("b.o", &b, &["b"]), ("a.o", &a, &["a"])]),
+            ),
+            ("missing.o", object(&[], &["missing"])),
+        ];
+        for (name, data) in files.iter() {
+            fs::write(directory.join(name), data).unwrap();
+        }
+    }
+
+    /// Inputs named by `args`, where `(` and `)` bracket a group and `+` turns on
+    /// `--whole-archive` for the next input.
+    fn inputs(directory: &Path, args: &[&str]) -> Vec<Input> {
+        let mut inputs = Vec::new();
+        let mut group = None;
+        let mut whole_archive = false;
+        for arg in args.iter() {
+            match *arg {
+                "(" => group = Some(0),
+                ")" => group = None,
+                "+" => whole_archive = true,
+                name => {
+                    inputs.push(Input {
+                        path: directory.join(name).to_string_lossy().into_owned(),
+                        whole_archive,
+                        group,
+                    });
+                    whole_archive = false;
+                }
+            }
+        }
+        inputs
+    }
+
+    /// Names of the objects linked for `args`, and the error of resolving their symbols.
+    fn link(test: &str, args: &[&str]) -> (Vec<String>, Option<LinkError>) {
+        let directory = temp_dir(test);
+        write_inputs(&directory);
+        let inputs = inputs(&directory, args);
+        let loaders = inputs
+            .iter()
+            .map(|input| ElfLoader::try_new(&input.path).unwrap())
+            .collect::<Vec<_>>();
+        let objects = load_objects(&inputs, &loaders).unwrap();
+        let names = objects
+            .iter()
+            .map(|object| {
+                let path = Path::new(&object.path);
+                path.file_name().unwrap().to_string_lossy().into_owned()
+            })
+            .collect();
+        let error = SymbolTable::new(&objects, None).err();
+        fs::remove_dir_all(&directory).unwrap();
+        (names, error)
+    }
+
+    #[test]
+    fn extracts_members_until_nothing_is_missing() {
+        let (names, error) = link("fixpoint", &["main.o", "libab.a"]);
+        // `b.o` comes first in the archive, but is only needed once `a.o` is in.
+        assert_eq!(names, ["main.o", "libab.a(a.o)", "libab.a(b.o)"]);
+        assert!(error.is_none());
+    }
+
+    #[test]
+    fn searches_archives_in_order() {
+        let (names, error) = link("order", &["main.o", "liba.a", "libb.a"]);
+        assert_eq!(names, ["main.o", "liba.a(a.o)", "libb.a(b.o)"]);
+        assert!(error.is_none());
+
+        // `b` is only needed after `libb.a` was searched.
+        let (names, error) = link("reversed", &["main.o", "libb.a", "liba.a"]);
+        assert_eq!(names, ["main.o", "liba.a(a.o)"]);
+        match error {
+            Some(LinkError::UndefinedSymbol { name, path }) => {
+                assert_eq!(name, "b");
+                assert!(path.ends_with("liba.a(a.o)"));
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn searches_groups_until_nothing_is_extracted() {
+        let (names, error) = link("group", &["main.o", "(", "libb.a", "liba.a", ")"]);
+        assert_eq!(names, ["main.o", "liba.a(a.o)", "libb.a(b.o)"]);
+        assert!(error.is_none());
+    }
+
+    #[test]
+    fn whole_archive_extracts_every_member() {
+        let (names, error) = link("whole", &["main.o", "+", "liba.a", "libb.a"]);
+        assert_eq!(
+            names,
+            ["main.o", "liba.a(a.o)", "liba.a(unused.o)", "libb.a(b.o)"]
+        );
+        assert!(error.is_none());
+    }
+
+    #[test]
+    fn leaves_names_no_member_defines_undefined() {
+        let (names, error) = link("missing", &["missing.o", "liba.a", "libb.a"]);
+        assert_eq!(names, ["missing.o"]);
+        match error {
+            Some(LinkError::UndefinedSymbol { name, .. }) => assert_eq!(name, "missing"),
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use elf::{ElfError, RelocationType};
+use std::fmt;
+use std::io;
+
+/// Errors that stop the link.
+///
+/// Every variant names the input file it was found in, so it can be reported as is.
+#[derive(Debug)]
+pub enum LinkError {
+    /// No input file was given.
+    NoInput,
+    /// A file could not be opened, read or written.
+    Io { path: String, error: io::Error },
+    /// An input is not a well formed elf file.
+    Elf { path: String, error: ElfError },
+    /// An input is an elf file, but not an x86-64 relocatable object.
+    UnsupportedInput { path: String, reason: &'static str },
+    /// An archive has members but no symbol index to find them by.
+    MissingArchiveIndex { path: String },
+    /// An input section needs support the linker does not have, such as TLS.
+    UnsupportedSection { path: String, section: String },
+    /// A global symbol has two strong definitions, given by file and section.
+    DuplicateSymbol {
+        name: String,
+        first: String,
+        first_section: String,
+        second: String,
+        second_section: String,
+    },
+    /// A symbol is referenced but defined nowhere.
+    UndefinedSymbol { name: String, path: String },
+    /// A symbol is defined in a section that is not part of the output.
+    DiscardedSymbol { name: String, path: String },
+    UnsupportedRelocation {
+        path: String,
+        section: String,
+        offset: u64,
+        relocation_type: RelocationType,
+    },
+    /// The value computed for a relocation does not fit in its field.
+    RelocationOverflow {
+        path: String,
+        section: String,
+        offset: u64,
+        relocation_type: RelocationType,
+        value: i64,
+    },
+    /// The entry point symbol is not defined.
+    MissingEntry { name: String },
+    /// An output section ends past the largest 64-bit address.
+    AddressOverflow { section: String },
+    /// The output has more sections than a symbol can refer to without `SHN_XINDEX`.
+    TooManySections { count: usize },
+    /// The linker script could not be parsed.
+    ScriptSyntax {
+        path: String,
+        line: usize,
+        message: String,
+    },
+    /// The linker script asks for a placement that cannot be done.
+    Script { path: String, message: String },
+}
+
+impl LinkError {
+    pub fn elf(path: &str, error: ElfError) -> LinkError {
+        LinkError::Elf {
+            path: path.to_string(),
+            error,
+        }
+    }
+}
+
+impl fmt::Display for LinkError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinkError::NoInput => write!(f, "no input files"),
+            LinkError::Io { path, error } => write!(f, "{}: {}", path, error),
+            LinkError::Elf { path, error } => write!(f, "{}: {}", path, error),
+            LinkError::UnsupportedInput { path, reason } => write!(f, "{}: {}", path, reason),
+            LinkError::MissingArchiveIndex { path } => {
+                write!(f, "{}: archive has no index; run ranlib to add one", path)
+            }
+            LinkError::UnsupportedSection { path, section } => {
+                write!(f, "{}: section {} is not supported", path, section)
+            }
+            LinkError::DuplicateSymbol {
+                name,
+                first,
+                first_section,
+                second,
+                second_section,
+            } => write!(
+                f,
+                "multiple definition of `{}`: first defined in {} ({}), again in {} ({})",
+                name, first, first_section, second, second_section
+            ),
+            LinkError::UndefinedSymbol { name, path } => {
+                write!(f, "{}: undefined reference to `{}`", path, name)
+            }
+            LinkError::DiscardedSymbol { name, path } => write!(
+                f,
+                "{}: `{}` is defined in a section that is not loaded",
+                path, name
+            ),
+            LinkError::UnsupportedRelocation {
+                path,
+                section,
+                offset,
+                relocation_type,
+            } => write!(
+                f,
+                "{}: relocation {} at {}+{:#x} is not supported",
+                path, relocation_type, section, offset
+            ),
+            LinkError::RelocationOverflow {
+                path,
+                section,
+                offset,
+                relocation_type,
+                value,
+            } => write!(
+                f,
+                "{}: relocation {} at {}+{:#x} overflows with value {:#x}",
+                path, relocation_type, section, offset, value
+            ),
+            LinkError::MissingEntry { name } => write!(f, "entry symbol `{}` is not defined", name),
+            LinkError::AddressOverflow { section } => {
+                write!(f, "section {} does not fit in the address space", section)
+            }
+            LinkError::TooManySections { count } => write!(
+                f,
+                "{} output sections are more than a symbol section index can hold",
+                count
+            ),
+            LinkError::ScriptSyntax {
+                path,
+                line,
+                message,
+            } => write!(f, "{}:{}: {}", path, line, message),
+            LinkError::Script { path, message } => write!(f, "{}: {}", path, message),
+        }
+    }
+}
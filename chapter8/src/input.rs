@@ -0,0 +1,96 @@
+use crate::error::LinkError;
+use elf::{
+    Class, ElfError, ElfFile, ElfSectionHeader, ElfSymbol, ElfType, Endian, Machine,
+    RelocationSection, SectionFlags, SectionType,
+};
+
+/// A relocatable object given to the linker, parsed once up front.
+pub struct ObjectFile<'a> {
+    /// Name used in diagnostics.
+    pub path: String,
+    pub file: ElfFile<'a>,
+    pub section_names: Vec<&'a str>,
+    pub symbols: Vec<ElfSymbol<'a>>,
+    pub relocations: Vec<RelocationSection<'a>>,
+}
+
+impl<'a> ObjectFile<'a> {
+    /// Parses `data` as an x86-64 `ET_REL` object, the only kind of input the linker accepts.
+    pub fn new(path: String, data: &'a [u8]) -> Result<ObjectFile<'a>, LinkError> {
+        let file = ElfFile::new(data).map_err(|error| LinkError::elf(&path, error))?;
+        let encoding = file.get_encoding();
+        let header = file.get_elf_header();
+        let reason = if encoding.class != Class::Elf64 || encoding.endian != Endian::Little {
+            Some("not a little endian ELF64 file")
+        } else if header.elf_type() != ElfType::Rel {
+            Some("not a relocatable object")
+        } else if header.machine() != Machine::X86_64 {
+            Some("not an x86-64 object")
+        } else {
+            None
+        };
+        if let Some(reason) = reason {
+            return Err(LinkError::UnsupportedInput { path, reason });
+        }
+
+        let (section_names, symbols, relocations) =
+            read_tables(&file).map_err(|error| LinkError::elf(&path, error))?;
+        Ok(ObjectFile {
+            path,
+            file,
+            section_names,
+            symbols,
+            relocations,
+        })
+    }
+
+    pub fn section_header(&self, index: usize) -> Result<&ElfSectionHeader, LinkError> {
+        self.file
+            .get_section_header(index)
+            .map_err(|error| LinkError::elf(&self.path, error))
+    }
+
+    pub fn section_data(&self, header: &ElfSectionHeader) -> Result<&'a [u8], LinkError> {
+        self.file
+            .get_section_data(header)
+            .map_err(|error| LinkError::elf(&self.path, error))
+    }
+
+    pub fn section_name(&self, index: usize) -> &'a str {
+        self.section_names.get(index).copied().unwrap_or("")
+    }
+
+    /// Whether the section is copied into the executable.
+    ///
+    /// Only allocated contents are loaded; notes, groups and excluded sections are dropped.
+    pub fn is_loaded(&self, header: &ElfSectionHeader) -> bool {
+        let flags = header.flags();
+        flags.contains(SectionFlags::ALLOC)
+            && !flags.contains(SectionFlags::EXCLUDE)
+            && matches!(
+                header.section_type(Machine::X86_64),
+                SectionType::Progbits
+                    | SectionType::Nobits
+                    | SectionType::InitArray
+                    | SectionType::FiniArray
+                    | SectionType::PreinitArray
+                    | SectionType::X86_64Unwind
+            )
+    }
+}
+
+type Tables<'a> = (Vec<&'a str>, Vec<ElfSymbol<'a>>, Vec<RelocationSection<'a>>);
+
+/// Section names, `.symtab` and relocation sections. An object without symbols has no `.symtab`.
+fn read_tables<'a>(file: &ElfFile<'a>) -> Result<Tables<'a>, ElfError> {
+    let symbols = match file.get_symbol_table() {
+        Ok(symbols) => symbols,
+        Err(ElfError::MissingSection { .. }) => Vec::new(),
+        Err(error) => return Err(error),
+    };
+    Ok((
+        file.get_section_names()?,
+        symbols,
+        file.get_relocation_sections()?,
+    ))
+}
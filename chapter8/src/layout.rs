@@ -0,0 +1,1203 @@
+use crate::error::LinkError;
+use crate::input::ObjectFile;
+use crate::script::{Assignment, Command, Expr, OutputCommand, OutputDescription, Script};
+use crate::symbols::{Resolution, SymbolTable};
+use elf::{
+    ElfProgramHeader, ElfSectionHeader, ProgramFlags, ProgramType, SectionFlags, SectionType,
+    ELF64_HEADER_SIZE, ELF64_PROGRAM_HEADER_SIZE, ELF_IDENTIFICATION_SIZE,
+};
+use std::collections::HashMap;
+
+/// Address of the first byte of the file in memory, the usual base of x86-64 executables.
+pub const BASE_ADDRESS: u64 = 0x40_0000;
+pub const PAGE_SIZE: u64 = 0x1000;
+
+/// Names of the output sections that collect `name.*` input sections, as in the GNU ld default script.
+const OUTPUT_SECTION_NAMES: [&str; 9] = [
+    ".text",
+    ".rodata",
+    ".eh_frame",
+    ".preinit_array",
+    ".init_array",
+    ".fini_array",
+    ".data.rel.ro",
+    ".data",
+    ".bss",
+];
+
+/// An input section placed in an output section.
+#[derive(Debug, Clone, Copy)]
+pub struct InputSection {
+    pub file: usize,
+    pub index: usize,
+    /// Offset from the start of the output section.
+    pub offset: u64,
+}
+
+/// Input sections with the same output name concatenated into one section of the executable.
+#[derive(Debug, Clone)]
+pub struct OutputSection<'a> {
+    pub name: &'a str,
+    pub sh_type: u32,
+    pub sh_flags: u64,
+    pub address: u64,
+    /// Address the section is loaded at, which differs from `address` when a linker
+    /// script moves it with `AT`.
+    pub load_address: u64,
+    pub size: u64,
+    pub align: u64,
+    pub inputs: Vec<InputSection>,
+}
+
+impl<'a> OutputSection<'a> {
+    pub fn is_nobits(&self) -> bool {
+        self.sh_type == u32::from(SectionType::Nobits)
+    }
+
+    /// Merges the permissions and the type of an input section into the section.
+    fn add_flags(&mut self, header: &ElfSectionHeader) {
+        self.sh_flags |= header.sh_flags
+            & (SectionFlags::WRITE.0 | SectionFlags::ALLOC.0 | SectionFlags::EXECINSTR.0);
+        // Contents in the file win over NOBITS, which is then written out as zeros.
+        if self.is_nobits() {
+            self.sh_type = header.sh_type;
+        }
+    }
+
+    fn segment_kind(&self) -> SegmentKind {
+        SegmentKind::of(self.sh_flags)
+    }
+}
+
+/// `PT_LOAD` segments in the order they are laid out. The first one also maps the headers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SegmentKind {
+    ReadOnly,
+    Executable,
+    Writable,
+}
+
+impl SegmentKind {
+    fn of(sh_flags: u64) -> SegmentKind {
+        let flags = SectionFlags(sh_flags);
+        if flags.contains(SectionFlags::EXECINSTR) {
+            SegmentKind::Executable
+        } else if flags.contains(SectionFlags::WRITE) {
+            SegmentKind::Writable
+        } else {
+            SegmentKind::ReadOnly
+        }
+    }
+
+    fn flags(&self) -> ProgramFlags {
+        match self {
+            SegmentKind::ReadOnly => ProgramFlags::R,
+            SegmentKind::Executable => ProgramFlags(ProgramFlags::R.0 | ProgramFlags::X.0),
+            SegmentKind::Writable => ProgramFlags(ProgramFlags::R.0 | ProgramFlags::W.0),
+        }
+    }
+}
+
+/// Output sections with their addresses, and the segments that load them.
+pub struct Layout<'a> {
+    pub sections: Vec<OutputSection<'a>>,
+    pub segments: Vec<ElfProgramHeader>,
+    /// Output section and offset in it of every input section, by file and section index.
+    placements: Vec<Vec<Option<(usize, u64)>>>,
+    /// Output section and offset in it of every common symbol, by name.
+    commons: HashMap<&'a str, (usize, u64)>,
+    /// Values of the symbols assigned by the linker script.
+    script_symbols: HashMap<&'a str, u64>,
+}
+
+impl<'a> Layout<'a> {
+    /// Merges the loaded sections of `objects` by name and assigns addresses from `BASE_ADDRESS`.
+    /// Common symbols of `symbols` are allocated at the end of `.bss`.
+    ///
+    /// Read only data comes first, right after the headers, then code, then writable data
+    /// with `.bss` last. Each group starts on a new page so it can have its own permissions.
+    pub fn new(
+        objects: &[ObjectFile<'a>],
+        symbols: &SymbolTable<'a>,
+    ) -> Result<Layout<'a>, LinkError> {
+        let mut sections = Vec::<OutputSection<'a>>::new();
+        for (file, index, header) in loaded_sections(objects)? {
+            let name = output_section_name(objects[file].section_name(index));
+            let position = find_or_add_section(&mut sections, name, header.sh_type);
+            let section = &mut sections[position];
+            let align = header.sh_addralign.max(1);
+            let offset = align_up(section.size, align).ok_or_else(|| overflow(name))?;
+            section.size = offset
+                .checked_add(header.sh_size)
+                .ok_or_else(|| overflow(name))?;
+            section.align = section.align.max(align);
+            section.add_flags(&header);
+            section.inputs.push(InputSection {
+                file,
+                index,
+                offset,
+            });
+        }
+        let mut commons = Vec::new();
+        for symbol in symbols.iter() {
+            if let Resolution::Common { size, align, .. } = symbol.resolution {
+                let position =
+                    find_or_add_section(&mut sections, ".bss", u32::from(SectionType::Nobits));
+                let section = &mut sections[position];
+                let offset = align_up(section.size, align).ok_or_else(|| overflow(".bss"))?;
+                section.size = offset.checked_add(size).ok_or_else(|| overflow(".bss"))?;
+                section.align = section.align.max(align);
+                section.sh_flags |= SectionFlags::WRITE.0 | SectionFlags::ALLOC.0;
+                commons.push((symbol.name, offset));
+            }
+        }
+        sections.sort_by_key(|section| (section.segment_kind(), section.is_nobits()));
+
+        let mut layout = Layout::empty(objects, sections);
+        layout.assign_addresses()?;
+        layout.record_placements();
+        if let Some(bss) = layout
+            .sections
+            .iter()
+            .position(|section| section.name == ".bss")
+        {
+            for (name, offset) in commons {
+                layout.commons.insert(name, (bss, offset));
+            }
+        }
+        Ok(layout)
+    }
+
+    fn assign_addresses(&mut self) -> Result<(), LinkError> {
+        let kinds = [
+            SegmentKind::ReadOnly,
+            SegmentKind::Executable,
+            SegmentKind::Writable,
+        ];
+        let load_count = kinds
+            .iter()
+            .filter(|kind| {
+                **kind == SegmentKind::ReadOnly
+                    || self
+                        .sections
+                        .iter()
+                        .any(|section| section.segment_kind() == **kind)
+            })
+            .count();
+        // One more for PT_GNU_STACK.
+        let headers_size = ELF_IDENTIFICATION_SIZE
+            + ELF64_HEADER_SIZE
+            + (load_count + 1) * ELF64_PROGRAM_HEADER_SIZE;
+
+        let mut address = BASE_ADDRESS + headers_size as u64;
+        for kind in kinds.iter() {
+            let start = match kind {
+                SegmentKind::ReadOnly => BASE_ADDRESS,
+                _ => match self
+                    .sections
+                    .iter()
+                    .find(|section| section.segment_kind() == *kind)
+                {
+                    Some(first) => {
+                        align_up(address, PAGE_SIZE).ok_or_else(|| overflow(first.name))?
+                    }
+                    None => continue,
+                },
+            };
+            address = address.max(start);
+            for section in self
+                .sections
+                .iter_mut()
+                .filter(|section| section.segment_kind() == *kind)
+            {
+                section.address = align_up(address, section.align)
+                    .and_then(|address| address.checked_add(section.size).map(|_| address))
+                    .ok_or_else(|| overflow(section.name))?;
+                section.load_address = section.address;
+                address = section.address + section.size;
+            }
+            // File offsets and sizes are filled in by the writer.
+            self.segments.push(ElfProgramHeader {
+                p_type: u32::from(ProgramType::Load),
+                p_flags: kind.flags().0,
+                p_offset: 0,
+                p_vaddr: start,
+                p_paddr: start,
+                p_filesz: 0,
+                p_memsz: address - start,
+                p_align: PAGE_SIZE,
+            });
+        }
+        self.segments.push(gnu_stack());
+        Ok(())
+    }
+
+    /// Places the sections of `objects` as the `SECTIONS` command of `script` describes.
+    ///
+    /// Input sections go to the first output section with a pattern that matches them, in
+    /// input order for each pattern, and sections matched by `/DISCARD/` are dropped. The
+    /// others are orphans: they join the output section with their usual name, or get a new
+    /// one after the sections of the script. Each run of sections with the same permissions
+    /// and load offset that are no more than a page apart becomes a `PT_LOAD` segment.
+    pub fn with_script(
+        objects: &[ObjectFile<'a>],
+        symbols: &SymbolTable<'a>,
+        script: &'a Script,
+    ) -> Result<Layout<'a>, LinkError> {
+        let inputs = loaded_sections(objects)?;
+        let mut taken = objects
+            .iter()
+            .map(|object| vec![false; object.section_names.len()])
+            .collect::<Vec<_>>();
+        let mut commons = symbols
+            .iter()
+            .filter_map(|symbol| match symbol.resolution {
+                Resolution::Common { size, align, .. } => {
+                    Some(Piece::Common(symbol.name, size, align))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut pieces = Vec::<Vec<Piece<'a>>>::new();
+        for command in script.commands.iter() {
+            let mut output_pieces = Vec::new();
+            if let Command::Output(output) = command {
+                for content in output.contents.iter() {
+                    let description = match content {
+                        OutputCommand::Assignment(assignment) => {
+                            output_pieces.push(Piece::Assignment(assignment));
+                            continue;
+                        }
+                        OutputCommand::Input(description) => description,
+                    };
+                    for (file, index, header) in inputs.iter() {
+                        let object = &objects[*file];
+                        if !taken[*file][*index]
+                            && description.matches(&object.path, object.section_name(*index))
+                        {
+                            taken[*file][*index] = true;
+                            output_pieces.push(Piece::Input(*file, *index, *header));
+                        }
+                    }
+                    if description.matches_common() {
+                        output_pieces.append(&mut commons);
+                    }
+                }
+            }
+            pieces.push(output_pieces);
+        }
+
+        let mut orphans = Vec::<(&'a str, Vec<Piece<'a>>)>::new();
+        let orphan_inputs = inputs
+            .iter()
+            .filter(|(file, index, _)| !taken[*file][*index])
+            .map(|(file, index, header)| {
+                let name = output_section_name(objects[*file].section_name(*index));
+                (name, Piece::Input(*file, *index, *header))
+            });
+        let orphan_commons = commons.into_iter().map(|common| (".bss", common));
+        for (name, piece) in orphan_inputs.chain(orphan_commons) {
+            let output = script.commands.iter().position(|command| match command {
+                Command::Output(output) => output.name == name && !output.is_discard(),
+                Command::Assignment(_) => false,
+            });
+            match output {
+                Some(position) => pieces[position].push(piece),
+                None => match orphans.iter_mut().find(|(orphan, _)| *orphan == name) {
+                    Some((_, orphan_pieces)) => orphan_pieces.push(piece),
+                    None => orphans.push((name, vec![piece])),
+                },
+            }
+        }
+
+        // As in GNU ld, orphans follow the last section of the script with the same
+        // permissions, so that each kind of contents stays together.
+        let kinds = script
+            .commands
+            .iter()
+            .zip(pieces.iter())
+            .map(|(command, pieces)| match command {
+                Command::Output(output) if !output.is_discard() => pieces_kind(pieces),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let mut following = script
+            .commands
+            .iter()
+            .map(|_| Vec::new())
+            .collect::<Vec<_>>();
+        let mut trailing = Vec::new();
+        for (name, pieces) in orphans {
+            let kind = pieces_kind(&pieces);
+            match kinds
+                .iter()
+                .rposition(|other| other.is_some() && *other == kind)
+            {
+                Some(position) => following[position].push((name, pieces)),
+                None => trailing.push((name, pieces)),
+            }
+        }
+
+        // `SIZEOF_HEADERS` needs the number of program headers, which is only known once the
+        // sections are placed. The script runs again with room for more headers until the
+        // segments fit, which ends since there is at most one segment per section.
+        let mut header_count = 2;
+        loop {
+            let mut run = ScriptRun {
+                script,
+                symbols,
+                headers_size: (ELF_IDENTIFICATION_SIZE
+                    + ELF64_HEADER_SIZE
+                    + header_count * ELF64_PROGRAM_HEADER_SIZE)
+                    as u64,
+                location: 0,
+                regions: script.regions.iter().map(|region| region.origin).collect(),
+                sections: Vec::new(),
+                commons: HashMap::new(),
+                values: HashMap::new(),
+                previous_load: None,
+                deferred: Vec::new(),
+            };
+            run.run(pieces.clone(), following.clone(), trailing.clone())?;
+            let segments = script_segments(&run.sections);
+            if segments.len() > header_count {
+                header_count = segments.len();
+                continue;
+            }
+            let mut layout = Layout::empty(objects, run.sections);
+            layout.commons = run.commons;
+            layout.script_symbols = run.values;
+            layout.record_placements();
+            layout.segments = segments;
+            return Ok(layout);
+        }
+    }
+
+    fn empty(objects: &[ObjectFile<'a>], sections: Vec<OutputSection<'a>>) -> Layout<'a> {
+        Layout {
+            sections,
+            segments: Vec::new(),
+            placements: objects
+                .iter()
+                .map(|object| vec![None; object.section_names.len()])
+                .collect(),
+            commons: HashMap::new(),
+            script_symbols: HashMap::new(),
+        }
+    }
+
+    fn record_placements(&mut self) {
+        for (position, section) in self.sections.iter().enumerate() {
+            for input in section.inputs.iter() {
+                self.placements[input.file][input.index] = Some((position, input.offset));
+            }
+        }
+    }
+
+    /// Output section and offset in it of section `index` of `objects[file]`,
+    /// `None` when the section is not loaded.
+    pub fn placement(&self, file: usize, index: usize) -> Option<(usize, u64)> {
+        self.placements.get(file)?.get(index).copied().flatten()
+    }
+
+    pub fn section_address(&self, file: usize, index: usize) -> Option<u64> {
+        self.placement(file, index)
+            .map(|(position, offset)| self.sections[position].address + offset)
+    }
+
+    /// Output section and offset in it of the common symbol `name`.
+    pub fn common_placement(&self, name: &str) -> Option<(usize, u64)> {
+        self.commons.get(name).copied()
+    }
+
+    pub fn common_address(&self, name: &str) -> Option<u64> {
+        self.common_placement(name)
+            .map(|(position, offset)| self.sections[position].address + offset)
+    }
+
+    /// Value the linker script assigned to `name`.
+    pub fn script_symbol(&self, name: &str) -> Option<u64> {
+        self.script_symbols.get(name).copied()
+    }
+}
+
+/// Part of an output section described by a linker script.
+#[derive(Clone)]
+enum Piece<'a> {
+    /// File, section index and header of an input section.
+    Input(usize, usize, ElfSectionHeader),
+    /// Name, size and alignment of a common symbol.
+    Common(&'a str, u64, u64),
+    Assignment(&'a Assignment),
+}
+
+/// Segment kind of the contents of `pieces`, `None` when there are none.
+fn pieces_kind(pieces: &[Piece]) -> Option<SegmentKind> {
+    let mut flags = None;
+    for piece in pieces {
+        let piece_flags = match piece {
+            Piece::Input(_, _, header) => header.sh_flags,
+            Piece::Common(..) => SectionFlags::WRITE.0,
+            Piece::Assignment(_) => continue,
+        };
+        flags = Some(flags.unwrap_or(0) | piece_flags);
+    }
+    flags.map(SegmentKind::of)
+}
+
+/// State of a linker script while its commands are run in order.
+struct ScriptRun<'a, 's> {
+    script: &'a Script,
+    symbols: &'s SymbolTable<'a>,
+    /// Value of `SIZEOF_HEADERS`.
+    headers_size: u64,
+    /// The location counter `.`.
+    location: u64,
+    /// Next free address of each memory region.
+    regions: Vec<u64>,
+    sections: Vec<OutputSection<'a>>,
+    commons: HashMap<&'a str, (usize, u64)>,
+    values: HashMap<&'a str, u64>,
+    /// Memory region of the last output section and the distance from its address to its
+    /// load address.
+    previous_load: Option<(Option<usize>, u64)>,
+    /// Symbol assignments that could not be evaluated yet, with the location counter of
+    /// the point they appear at.
+    deferred: Vec<(&'a Assignment, u64)>,
+}
+
+impl<'a, 's> ScriptRun<'a, 's> {
+    fn error(&self, message: String) -> LinkError {
+        LinkError::Script {
+            path: self.script.path.clone(),
+            message,
+        }
+    }
+
+    /// Runs the commands of the script with the `pieces` of each, placing the orphans that
+    /// `following` puts after each command and the `trailing` ones at the end.
+    fn run(
+        &mut self,
+        pieces: Vec<Vec<Piece<'a>>>,
+        following: Vec<Vec<(&'a str, Vec<Piece<'a>>)>>,
+        trailing: Vec<(&'a str, Vec<Piece<'a>>)>,
+    ) -> Result<(), LinkError> {
+        let script = self.script;
+        for ((command, pieces), orphans) in script.commands.iter().zip(pieces).zip(following) {
+            match command {
+                Command::Assignment(assignment) => self.assign(assignment, None)?,
+                Command::Output(output) if output.is_discard() => {}
+                Command::Output(output) => self.place(&output.name, Some(output), pieces)?,
+            }
+            for (name, pieces) in orphans {
+                self.place(name, None, pieces)?;
+            }
+        }
+        for (name, pieces) in trailing {
+            self.place(name, None, pieces)?;
+        }
+        for (assignment, location) in std::mem::take(&mut self.deferred) {
+            self.location = location;
+            let value = self.evaluate(&assignment.expression)?;
+            self.values.insert(&assignment.symbol, value);
+        }
+        Ok(())
+    }
+
+    /// `value` aligned to `align`, which must not wrap around for `what`.
+    fn align(&self, value: u64, align: u64, what: &str) -> Result<u64, LinkError> {
+        align_up(value, align).ok_or_else(|| {
+            self.error(format!(
+                "aligning {:#x} to {:#x} for {} overflows the address space",
+                value, align, what
+            ))
+        })
+    }
+
+    /// Moves the location counter `size` bytes forward through the output section `name`.
+    fn advance(&mut self, size: u64, name: &str) -> Result<(), LinkError> {
+        self.location = self.location.checked_add(size).ok_or_else(|| {
+            self.error(format!(
+                "section `{}` at {:#x} with size {:#x} overflows the address space",
+                name, self.location, size
+            ))
+        })?;
+        Ok(())
+    }
+
+    fn region(&self, name: &str) -> Result<usize, LinkError> {
+        self.script
+            .regions
+            .iter()
+            .position(|region| region.name == name)
+            .ok_or_else(|| self.error(format!("unknown memory region `{}`", name)))
+    }
+
+    fn section(&self, name: &str) -> Result<&OutputSection<'a>, LinkError> {
+        self.sections
+            .iter()
+            .find(|section| section.name == name)
+            .ok_or_else(|| self.error(format!("section `{}` is not placed yet", name)))
+    }
+
+    fn evaluate(&self, expression: &Expr) -> Result<u64, LinkError> {
+        Ok(match expression {
+            Expr::Number(value) => *value,
+            Expr::Location => self.location,
+            Expr::Symbol(name) => match self.values.get(name.as_str()) {
+                Some(value) => *value,
+                None => {
+                    return Err(self.error(format!(
+                        "symbol `{}` is not assigned before it is used",
+                        name
+                    )))
+                }
+            },
+            Expr::Negate(value) => self.evaluate(value)?.wrapping_neg(),
+            Expr::Not(value) => !self.evaluate(value)?,
+            Expr::Binary(op, left, right) => op
+                .apply(self.evaluate(left)?, self.evaluate(right)?)
+                .ok_or_else(|| self.error("division by zero".to_string()))?,
+            Expr::Align(value, align) => {
+                let value = match value {
+                    Some(value) => self.evaluate(value)?,
+                    None => self.location,
+                };
+                self.align(value, self.evaluate(align)?, "`ALIGN`")?
+            }
+            Expr::Origin(name) => self.script.regions[self.region(name)?].origin,
+            Expr::Length(name) => self.script.regions[self.region(name)?].length,
+            Expr::Addr(name) => self.section(name)?.address,
+            Expr::LoadAddr(name) => self.section(name)?.load_address,
+            Expr::SizeOf(name) => self.section(name)?.size,
+            Expr::SizeOfHeaders => self.headers_size,
+        })
+    }
+
+    /// Runs an assignment. Inside an output section that starts at `section_start`, `.` only
+    /// moves forward, and a plain number assigned to it is an offset from the start.
+    fn assign(
+        &mut self,
+        assignment: &'a Assignment,
+        section_start: Option<u64>,
+    ) -> Result<(), LinkError> {
+        let name = assignment.symbol.as_str();
+        if assignment.provide
+            && self.symbols.get(name).map(|symbol| symbol.resolution) != Some(Resolution::Script)
+        {
+            return Ok(());
+        }
+        if name != "." {
+            // A symbol may use sections placed later, such as `LOADADDR(.data)` before
+            // `.data`. It is evaluated again once everything is placed.
+            match self.evaluate(&assignment.expression) {
+                Ok(value) => {
+                    self.values.insert(name, value);
+                }
+                Err(_) => self.deferred.push((assignment, self.location)),
+            }
+            return Ok(());
+        }
+        let value = self.evaluate(&assignment.expression)?;
+        match section_start {
+            Some(start) => {
+                let value = match assignment.expression.constant() {
+                    Some(_) => start.wrapping_add(value),
+                    None => value,
+                };
+                if value < self.location {
+                    return Err(self.error(format!(
+                        "cannot move the location counter backwards from {:#x} to {:#x}",
+                        self.location, value
+                    )));
+                }
+                self.location = value;
+            }
+            None => self.location = value,
+        }
+        Ok(())
+    }
+
+    /// Places an output section made of `pieces` at the location counter, or where `output`
+    /// says. Sections left empty are dropped.
+    fn place(
+        &mut self,
+        name: &'a str,
+        output: Option<&'a OutputDescription>,
+        pieces: Vec<Piece<'a>>,
+    ) -> Result<(), LinkError> {
+        let mut align = pieces
+            .iter()
+            .map(|piece| match piece {
+                Piece::Input(_, _, header) => header.sh_addralign.max(1),
+                Piece::Common(_, _, align) => *align,
+                Piece::Assignment(_) => 1,
+            })
+            .max()
+            .unwrap_or(1);
+        if let Some(expression) = output.and_then(|output| output.align.as_ref()) {
+            align = align.max(self.evaluate(expression)?);
+        }
+        let region = match output.and_then(|output| output.region.as_ref()) {
+            Some(region) => Some(self.region(region)?),
+            None => None,
+        };
+        let address = match output.and_then(|output| output.address.as_ref()) {
+            Some(expression) => self.evaluate(expression)?,
+            None => {
+                let start = match region {
+                    Some(region) => self.regions[region],
+                    None => self.location,
+                };
+                self.align(start, align, &format!("section `{}`", name))?
+            }
+        };
+
+        let position = self.sections.len();
+        let mut section = OutputSection {
+            name,
+            sh_type: u32::from(SectionType::Nobits),
+            sh_flags: SectionFlags::ALLOC.0,
+            address,
+            load_address: address,
+            size: 0,
+            align,
+            inputs: Vec::new(),
+        };
+        let mut has_commons = false;
+        self.location = address;
+        for piece in pieces {
+            match piece {
+                Piece::Input(file, index, header) => {
+                    self.location = self.align(
+                        self.location,
+                        header.sh_addralign.max(1),
+                        &format!("section `{}`", name),
+                    )?;
+                    section.inputs.push(InputSection {
+                        file,
+                        index,
+                        offset: self.location - address,
+                    });
+                    section.add_flags(&header);
+                    self.advance(header.sh_size, name)?;
+                }
+                Piece::Common(name, size, align) => {
+                    self.location = self.align(self.location, align, &format!("`{}`", name))?;
+                    self.commons
+                        .insert(name, (position, self.location - address));
+                    section.sh_flags |= SectionFlags::WRITE.0;
+                    has_commons = true;
+                    self.advance(size, section.name)?;
+                }
+                Piece::Assignment(assignment) => self.assign(assignment, Some(address))?,
+            }
+        }
+        section.size = self.location - address;
+        if section.inputs.is_empty() {
+            section.sh_flags |= SectionFlags::WRITE.0;
+        }
+        if output.is_some_and(|output| output.no_load) {
+            section.sh_type = u32::from(SectionType::Nobits);
+        }
+
+        if let Some(region) = region {
+            self.regions[region] = self.fit(region, name, address, section.size)?;
+        }
+        let load_region = match output.and_then(|output| output.load_region.as_ref()) {
+            Some(load_region) => Some(self.region(load_region)?),
+            None => None,
+        };
+        section.load_address = match output.and_then(|output| output.load_address.as_ref()) {
+            Some(expression) => self.evaluate(expression)?,
+            None => match load_region {
+                Some(load_region) => self.align(
+                    self.regions[load_region],
+                    align,
+                    &format!("the load address of `{}`", name),
+                )?,
+                // Like the section before it in the same region, such as `.bss` after
+                // `.data`, the section keeps the same distance to its load address.
+                None => match self.previous_load {
+                    Some((previous_region, delta))
+                        if previous_region == region
+                            && output.is_some_and(|output| output.address.is_none()) =>
+                    {
+                        address.wrapping_add(delta)
+                    }
+                    _ => address,
+                },
+            },
+        };
+        if let Some(load_region) = load_region {
+            let size = if section.is_nobits() { 0 } else { section.size };
+            self.regions[load_region] = self.fit(load_region, name, section.load_address, size)?;
+        }
+        self.previous_load = Some((region, section.load_address.wrapping_sub(address)));
+
+        if section.inputs.is_empty() && !has_commons && section.size == 0 {
+            return Ok(());
+        }
+        self.sections.push(section);
+        Ok(())
+    }
+
+    /// End of `size` bytes at `address` in `region`, which they must fit in.
+    fn fit(&self, region: usize, section: &str, address: u64, size: u64) -> Result<u64, LinkError> {
+        let region = &self.script.regions[region];
+        let end = address.saturating_add(size);
+        if address < region.origin || end > region.origin.saturating_add(region.length) {
+            return Err(self.error(format!(
+                "section `{}` at {:#x} with size {:#x} does not fit in memory region `{}`",
+                section, address, size, region.name
+            )));
+        }
+        Ok(end)
+    }
+}
+
+/// `PT_LOAD` segments for sections placed by a linker script, in section order.
+///
+/// A segment ends where the permissions or the distance between the addresses and the
+/// load addresses change, where the next section is more than a page away or before the
+/// end, and after `SHT_NOBITS` contents, since they have no bytes in the file.
+fn script_segments(sections: &[OutputSection]) -> Vec<ElfProgramHeader> {
+    let mut segments = Vec::<ElfProgramHeader>::new();
+    let mut nobits = false;
+    for section in sections.iter().filter(|section| section.size > 0) {
+        let flags = section.segment_kind().flags().0;
+        let delta = section.address.wrapping_sub(section.load_address);
+        let end = section.address + section.size;
+        if let Some(segment) = segments.last_mut() {
+            let segment_end = segment.p_vaddr + segment.p_memsz;
+            if segment.p_flags == flags
+                && segment.p_vaddr.wrapping_sub(segment.p_paddr) == delta
+                && section.address >= segment_end
+                && section.address <= align_up(segment_end, PAGE_SIZE).unwrap_or(u64::MAX)
+                && (section.is_nobits() || !nobits)
+            {
+                segment.p_memsz = end - segment.p_vaddr;
+                nobits = section.is_nobits();
+                continue;
+            }
+        }
+        segments.push(ElfProgramHeader {
+            p_type: u32::from(ProgramType::Load),
+            p_flags: flags,
+            p_offset: 0,
+            p_vaddr: section.address,
+            p_paddr: section.load_address,
+            p_filesz: 0,
+            p_memsz: section.size,
+            p_align: PAGE_SIZE,
+        });
+        nobits = section.is_nobits();
+    }
+    segments.push(gnu_stack());
+    segments
+}
+
+/// Asks for a stack that is not executable.
+fn gnu_stack() -> ElfProgramHeader {
+    ElfProgramHeader {
+        p_type: u32::from(ProgramType::GnuStack),
+        p_flags: SegmentKind::Writable.flags().0,
+        p_offset: 0,
+        p_vaddr: 0,
+        p_paddr: 0,
+        p_filesz: 0,
+        p_memsz: 0,
+        p_align: 16,
+    }
+}
+
+/// File, index and header of the sections of `objects` that are loaded, in input order.
+fn loaded_sections(
+    objects: &[ObjectFile],
+) -> Result<Vec<(usize, usize, ElfSectionHeader)>, LinkError> {
+    let mut sections = Vec::new();
+    for (file, object) in objects.iter().enumerate() {
+        let headers = object
+            .file
+            .get_section_headers()
+            .map_err(|error| LinkError::elf(&object.path, error))?;
+        for (index, header) in headers.iter().enumerate() {
+            if !object.is_loaded(header) {
+                continue;
+            }
+            if header.flags().contains(SectionFlags::TLS) {
+                return Err(LinkError::UnsupportedSection {
+                    path: object.path.clone(),
+                    section: object.section_name(index).to_string(),
+                });
+            }
+            sections.push((file, index, *header));
+        }
+    }
+    Ok(sections)
+}
+
+/// Position of the output section `name`, added at the end when there is none yet.
+fn find_or_add_section<'a>(
+    sections: &mut Vec<OutputSection<'a>>,
+    name: &'a str,
+    sh_type: u32,
+) -> usize {
+    match sections.iter().position(|section| section.name == name) {
+        Some(position) => position,
+        None => {
+            sections.push(OutputSection {
+                name,
+                sh_type,
+                sh_flags: 0,
+                address: 0,
+                load_address: 0,
+                size: 0,
+                align: 1,
+                inputs: Vec::new(),
+            });
+            sections.len() - 1
+        }
+    }
+}
+
+/// Output section for an input section, folding names like `.text.main` from
+/// `-ffunction-sections` into `.text`.
+fn output_section_name(name: &str) -> &str {
+    OUTPUT_SECTION_NAMES
+        .iter()
+        .find(|prefix| {
+            name == **prefix || (name.starts_with(*prefix) && name[prefix.len()..].starts_with('.'))
+        })
+        .copied()
+        .unwrap_or(name)
+}
+
+/// `value` rounded up to a multiple of `align`, `None` when that does not fit in 64 bits.
+fn align_up(value: u64, align: u64) -> Option<u64> {
+    if align <= 1 {
+        Some(value)
+    } else {
+        value.div_ceil(align).checked_mul(align)
+    }
+}
+
+fn overflow(section: &str) -> LinkError {
+    LinkError::AddressOverflow {
+        section: section.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestObject;
+    use elf::{SymbolBinding, SymbolVisibility};
+
+    /// Layout of an object with a 32 byte `.text`, placed by `script`.
+    fn layout_with_script(script: &str) -> Result<Vec<(String, u64)>, LinkError> {
+        let mut object = TestObject::new();
+        let text = object.text(&[0x90; 32]);
+        object.define("_start", SymbolBinding::Global, text, 0);
+        let data = object.build();
+        let objects = vec![ObjectFile::new("test.o".to_string(), &data)?];
+        let script = Script::parse("test.ld", script)?;
+        let symbols = SymbolTable::new(&objects, Some(&script))?;
+        let layout = Layout::with_script(&objects, &symbols, &script)?;
+        Ok(layout
+            .sections
+            .iter()
+            .map(|section| (section.name.to_string(), section.address))
+            .collect())
+    }
+
+    /// An object with a 32 byte `.text`, a 16 byte `.data`, an 8 byte `.rodata`, a 4 byte
+    /// `.comment` and a 64 byte common `buffer`, that weakly references `data_end` and
+    /// `bss_end`.
+    fn sample_object() -> Vec<u8> {
+        let mut object = TestObject::new();
+        let text = object.text(&[0x90; 32]);
+        object.data(&[1; 16]);
+        object.section(".rodata", SectionFlags(0), &[2; 8]);
+        object.section(".comment", SectionFlags(0), b"gcc\0");
+        object.define("_start", SymbolBinding::Global, text, 0);
+        object.common("buffer", 64, 8);
+        object.reference("data_end", SymbolBinding::Weak);
+        object.reference("bss_end", SymbolBinding::Weak);
+        object.build()
+    }
+
+    /// Runs `check` on the layout of `sample_object` placed by `script`.
+    fn with_sample_layout(
+        script: &str,
+        check: impl FnOnce(&Layout, &SymbolTable),
+    ) -> Result<(), LinkError> {
+        let data = sample_object();
+        let objects = vec![ObjectFile::new("sample.o".to_string(), &data)?];
+        let script = Script::parse("test.ld", script)?;
+        let symbols = SymbolTable::new(&objects, Some(&script))?;
+        let layout = Layout::with_script(&objects, &symbols, &script)?;
+        check(&layout, &symbols);
+        Ok(())
+    }
+
+    /// Name, address and load address of each output section.
+    fn placed<'l>(layout: &'l Layout) -> Vec<(&'l str, u64, u64)> {
+        layout
+            .sections
+            .iter()
+            .map(|section| (section.name, section.address, section.load_address))
+            .collect()
+    }
+
+    /// Address, load address, size in memory and flags of each `PT_LOAD`.
+    fn loads(layout: &Layout) -> Vec<(u64, u64, u64, u32)> {
+        layout
+            .segments
+            .iter()
+            .filter(|segment| ProgramType::from(segment.p_type) == ProgramType::Load)
+            .map(|segment| {
+                (
+                    segment.p_vaddr,
+                    segment.p_paddr,
+                    segment.p_memsz,
+                    segment.p_flags,
+                )
+            })
+            .collect()
+    }
+
+    fn script_error(script: &str) -> String {
+        match layout_with_script(script) {
+            Err(LinkError::Script { message, .. }) => message,
+            other => panic!("{:?} for {:?}", other, script),
+        }
+    }
+
+    #[test]
+    fn places_sections_where_the_script_says() {
+        let sections =
+            layout_with_script("SECTIONS { . = 0x10000; .text : ALIGN(0x100000) { *(.text) } }")
+                .unwrap();
+        assert_eq!(sections, [(".text".to_string(), 0x100000)]);
+    }
+
+    #[test]
+    fn reports_addresses_past_the_address_space() {
+        let cases = [
+            (
+                "SECTIONS { . = 0xfffffffffffff000; .text : ALIGN(0x100000) { *(.text) } }",
+                "aligning 0xfffffffffffff000 to 0x100000 for section `.text` overflows",
+            ),
+            (
+                "SECTIONS { . = ALIGN(0xfffffffffffffff1, 0x10); }",
+                "aligning 0xfffffffffffffff1 to 0x10 for `ALIGN` overflows",
+            ),
+            (
+                "SECTIONS { . = 0xfffffffffffffff0; .text : { *(.text) } }",
+                "section `.text` at 0xfffffffffffffff0 with size 0x20 overflows",
+            ),
+        ];
+        for (script, message) in cases.iter() {
+            let error = script_error(script);
+            assert!(error.starts_with(message), "{}", error);
+        }
+    }
+
+    #[test]
+    fn aligns_without_wrapping() {
+        assert_eq!(align_up(0x1001, 0x1000), Some(0x2000));
+        assert_eq!(align_up(0x1000, 0x1000), Some(0x1000));
+        assert_eq!(align_up(7, 0), Some(7));
+        assert_eq!(align_up(u64::MAX, 1), Some(u64::MAX));
+        assert_eq!(align_up(u64::MAX - 0xfff, 0x1000), Some(u64::MAX - 0xfff));
+        assert_eq!(align_up(u64::MAX - 0xffe, 0x1000), None);
+    }
+
+    #[test]
+    fn places_sections_in_memory_regions() {
+        let script = "MEMORY { rom (rx) : ORIGIN = 0x10000, LENGTH = 0x100
+                 ram (rw) : ORIGIN = 0x20000, LENGTH = 0x100 }
+        SECTIONS {
+          .text : { *(.text) } > rom
+          .rodata : { *(.rodata) } > rom
+          .data : { *(.data) } > ram AT> rom
+          .bss : { *(.bss) *(COMMON) } > ram
+          /DISCARD/ : { *(.comment) }
+        }";
+        with_sample_layout(script, |layout, _| {
+            // `.data` is loaded after `.rodata` in `rom`, and `.bss` keeps its distance.
+            assert_eq!(
+                placed(layout),
+                [
+                    (".text", 0x10000, 0x10000),
+                    (".rodata", 0x10020, 0x10020),
+                    (".data", 0x20000, 0x10030),
+                    (".bss", 0x20010, 0x10040),
+                ]
+            );
+            let read_execute = ProgramFlags::R.0 | ProgramFlags::X.0;
+            let read_write = ProgramFlags::R.0 | ProgramFlags::W.0;
+            assert_eq!(
+                loads(layout),
+                [
+                    (0x10000, 0x10000, 0x20, read_execute),
+                    (0x10020, 0x10020, 8, ProgramFlags::R.0),
+                    (0x20000, 0x10030, 0x50, read_write),
+                ]
+            );
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn rejects_sections_that_overflow_their_region() {
+        let script = "MEMORY { rom : ORIGIN = 0x10000, LENGTH = 0x10 }
+        SECTIONS { .text : { *(.text) } > rom }";
+        assert_eq!(
+            script_error(script),
+            "section `.text` at 0x10000 with size 0x20 does not fit in memory region `rom`"
+        );
+        let script = "MEMORY { rom : ORIGIN = 0x10000, LENGTH = 0x10
+                 ram : ORIGIN = 0x20000, LENGTH = 0x100 }
+        SECTIONS { .text : { *(.text) } > ram AT> rom }";
+        assert_eq!(
+            script_error(script),
+            "section `.text` at 0x10000 with size 0x20 does not fit in memory region `rom`"
+        );
+    }
+
+    #[test]
+    fn loads_sections_at_their_at_address() {
+        let script = "SECTIONS {
+          . = 0x10000;
+          .text : { *(.text) }
+          .data 0x20000 : AT(0x30000) { *(.data) }
+          .bss : { *(COMMON) }
+        }";
+        with_sample_layout(script, |layout, _| {
+            let sections = placed(layout);
+            assert_eq!(sections[1], (".data", 0x20000, 0x30000));
+            assert_eq!(sections[2], (".bss", 0x20010, 0x30010));
+            let loads = loads(layout);
+            assert_eq!((loads[1].0, loads[1].1), (0x20000, 0x30000));
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn provides_only_referenced_symbols() {
+        let script = "SECTIONS {
+          . = 0x10000;
+          .text : { *(.text) }
+          .data : { *(.data) PROVIDE(data_end = .); }
+          .bss : { *(COMMON) }
+          PROVIDE_HIDDEN(bss_end = .);
+          PROVIDE(unused = 1);
+          PROVIDE(_start = 2);
+        }";
+        with_sample_layout(script, |layout, symbols| {
+            let data = &layout.sections[1];
+            assert_eq!(
+                layout.script_symbol("data_end"),
+                Some(data.address + data.size)
+            );
+            let bss_end = symbols.get("bss_end").unwrap();
+            assert_eq!(bss_end.resolution, Resolution::Script);
+            assert_eq!(bss_end.visibility, SymbolVisibility::Hidden);
+            assert!(!bss_end.is_exported());
+            assert!(symbols.get("data_end").unwrap().is_exported());
+            assert!(symbols.get("unused").is_none());
+            assert_eq!(layout.script_symbol("unused"), None);
+            // `_start` is defined by the object, so the script leaves it alone.
+            assert!(matches!(
+                symbols.get("_start").unwrap().resolution,
+                Resolution::Strong(_)
+            ));
+            assert_eq!(layout.script_symbol("_start"), None);
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn discards_and_places_orphan_sections() {
+        let script = "SECTIONS {
+          . = 0x10000;
+          .text : { *(.text) }
+          .data : { *(.data) }
+          /DISCARD/ : { *(.comment) }
+        }";
+        with_sample_layout(script, |layout, _| {
+            // `.bss` follows the writable `.data`, and `.rodata` has no section of its kind.
+            let names = placed(layout)
+                .into_iter()
+                .map(|(name, _, _)| name)
+                .collect::<Vec<_>>();
+            assert_eq!(names, [".text", ".data", ".bss", ".rodata"]);
+            assert_eq!(layout.placement(0, 4), None);
+            assert_eq!(layout.common_placement("buffer"), Some((2, 0)));
+        })
+        .unwrap();
+    }
+
+    #[test]
+    fn splits_segments_where_sections_cannot_share_one() {
+        let cases = [
+            // Contiguous sections with the same permissions share a segment.
+            (
+                ".data : { *(.data) } .bss : { *(COMMON) }",
+                vec![(0x10000, 0x50)],
+            ),
+            // More than a page between them.
+            (
+                ".data : { *(.data) } . = 0x12000; .bss : { *(COMMON) }",
+                vec![(0x10000, 0x10), (0x12000, 0x40)],
+            ),
+            // Contents after `SHT_NOBITS` need their own bytes in the file.
+            (
+                ".bss : { *(COMMON) } .data : { *(.data) }",
+                vec![(0x10000, 0x40), (0x10040, 0x10)],
+            ),
+            // A different distance to the load address.
+            (
+                ".data : { *(.data) } .bss : AT(0x30000) { *(COMMON) }",
+                vec![(0x10000, 0x10), (0x10010, 0x40)],
+            ),
+        ];
+        for (sections, expected) in cases.iter() {
+            let script = format!(
+                "SECTIONS {{ . = 0x10000; {} /DISCARD/ : {{ *(.text .rodata .comment) }} }}",
+                sections
+            );
+            with_sample_layout(&script, |layout, _| {
+                let loads = loads(layout)
+                    .into_iter()
+                    .map(|(address, _, size, _)| (address, size))
+                    .collect::<Vec<_>>();
+                assert_eq!(&loads, expected, "{}", script);
+            })
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn sizes_the_headers_for_every_segment() {
+        let script = "SECTIONS {
+          . = 0x400000 + SIZEOF_HEADERS;
+          headers_end = .;
+          .text : { *(.text) }
+        }";
+        with_sample_layout(script, |layout, _| {
+            // Orphans bring the segments to four `PT_LOAD` and `PT_GNU_STACK`.
+            assert_eq!(layout.segments.len(), 5);
+            let headers_size =
+                ELF_IDENTIFICATION_SIZE + ELF64_HEADER_SIZE + 5 * ELF64_PROGRAM_HEADER_SIZE;
+            assert_eq!(
+                layout.script_symbol("headers_end"),
+                Some(BASE_ADDRESS + headers_size as u64)
+            );
+        })
+        .unwrap();
+    }
+}
@@ -0,0 +1,354 @@
+use crate::archive;
+use crate::error::LinkError;
+use crate::layout::Layout;
+use crate::output;
+use crate::relocate;
+use crate::script::Script;
+use crate::symbols::{Resolution, SymbolTable};
+use elf::{
+    ElfLoader, ElfSymbolEntry, SectionIndex, SymbolBinding, SymbolType, WriterSymbol, SHN_ABS,
+    SHN_LORESERVE, SHN_UNDEF,
+};
+use std::convert::TryFrom;
+use std::fs;
+
+/// An object or archive on the command line, with the options in effect where it appears.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    pub path: String,
+    /// Every member of the archive is linked, not only the ones that are needed.
+    pub whole_archive: bool,
+    /// The `--start-group` ... `--end-group` group the input is in, numbered from 0.
+    pub group: Option<usize>,
+}
+
+/// What to link and where to write the result.
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub inputs: Vec<Input>,
+    pub output: String,
+    /// Symbol whose address becomes `e_entry`. Defaults to the `ENTRY` of the linker script,
+    /// then to `_start`.
+    pub entry: Option<String>,
+    /// Linker script that places the sections instead of the default layout.
+    pub script: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            inputs: Vec::new(),
+            output: "a.out".to_string(),
+            entry: None,
+            script: None,
+        }
+    }
+}
+
+/// Links the relocatable objects in `options.inputs`, and the archive members they need,
+/// into a static executable.
+pub fn link(options: &Options) -> Result<(), LinkError> {
+    if options.inputs.is_empty() {
+        return Err(LinkError::NoInput);
+    }
+    let loaders = options
+        .inputs
+        .iter()
+        .map(|input| {
+            ElfLoader::try_new(&input.path).map_err(|error| LinkError::Io {
+                path: input.path.clone(),
+                error,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let script = match &options.script {
+        Some(path) => {
+            let text = fs::read_to_string(path).map_err(|error| LinkError::Io {
+                path: path.clone(),
+                error,
+            })?;
+            Some(Script::parse(path, &text)?)
+        }
+        None => None,
+    };
+    let objects = archive::load_objects(&options.inputs, &loaders)?;
+
+    let symbols = SymbolTable::new(&objects, script.as_ref())?;
+    let layout = match &script {
+        Some(script) => Layout::with_script(&objects, &symbols, script)?,
+        None => Layout::new(&objects, &symbols)?,
+    };
+    let mut contents = output::build_contents(&objects, &layout)?;
+    relocate::apply_relocations(&objects, &layout, &symbols, &mut contents)?;
+
+    let entry_name = options
+        .entry
+        .as_deref()
+        .or_else(|| script.as_ref().and_then(|script| script.entry.as_deref()))
+        .unwrap_or("_start");
+    let entry = match symbols.get(entry_name) {
+        Some(symbol) if symbol.resolution != Resolution::UndefinedWeak => {
+            symbols.global_address(&objects, &layout, symbol)?
+        }
+        _ => {
+            return Err(LinkError::MissingEntry {
+                name: entry_name.to_string(),
+            })
+        }
+    };
+    let mut output_symbols = Vec::<WriterSymbol>::new();
+    for symbol in symbols.iter() {
+        let definition = symbol
+            .definition()
+            .map(|id| &objects[id.file].symbols[id.index]);
+        let (mut binding, mut symbol_type, st_other) = match definition {
+            Some(definition) => (
+                definition.binding,
+                definition.symbol_type,
+                definition.entry.st_other,
+            ),
+            None => (SymbolBinding::Global, SymbolType::NoType, 0),
+        };
+        let (st_shndx, st_size) = match symbol.resolution {
+            Resolution::Strong(id) | Resolution::Weak(id) => {
+                let definition = &objects[id.file].symbols[id.index];
+                match definition.section_index {
+                    SectionIndex::Index(index) => match layout.placement(id.file, index as usize) {
+                        Some((position, _)) => (section_index(position)?, definition.entry.st_size),
+                        None => continue,
+                    },
+                    _ => (SHN_ABS, definition.entry.st_size),
+                }
+            }
+            Resolution::Common { size, .. } => match layout.common_placement(symbol.name) {
+                Some((position, _)) => {
+                    symbol_type = SymbolType::Object;
+                    (section_index(position)?, size)
+                }
+                None => continue,
+            },
+            Resolution::UndefinedWeak => {
+                binding = SymbolBinding::Weak;
+                (SHN_UNDEF, 0)
+            }
+            Resolution::Script => (SHN_ABS, 0),
+        };
+        if !symbol.is_exported() {
+            binding = SymbolBinding::Local;
+        }
+        output_symbols.push(WriterSymbol {
+            name: symbol.name.into(),
+            entry: ElfSymbolEntry {
+                st_name: 0,
+                st_info: u8::from(binding) << 4 | u8::from(symbol_type),
+                st_other: st_other & !0x3 | u8::from(symbol.visibility),
+                st_shndx,
+                st_value: symbols.global_address(&objects, &layout, symbol)?,
+                st_size,
+            },
+        });
+    }
+    output::write_executable(&options.output, &layout, contents, entry, output_symbols)
+}
+
+/// `st_shndx` of the output section at `position`, after the null section.
+fn section_index(position: usize) -> Result<u16, LinkError> {
+    u16::try_from(position + 1)
+        .ok()
+        .filter(|index| *index < SHN_LORESERVE)
+        .ok_or(LinkError::TooManySections {
+            count: position + 1,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::layout::{BASE_ADDRESS, PAGE_SIZE};
+    use crate::testing::*;
+    use elf::{ElfFile, ProgramFlags, ProgramType, SectionFlags};
+
+    /// `_start` loads `status` from `.data` and passes it to `exit`.
+    fn exit_program(status: u8) -> TestObject {
+        let mut object = TestObject::new();
+        let text = object.text(&[
+            0x8b, 0x3d, 0, 0, 0, 0, // mov edi, [rip + status]
+            0xb8, 0x3c, 0, 0, 0, // mov eax, 60
+            0x0f, 0x05, // syscall
+        ]);
+        let data = object.data(&[status, 0, 0, 0]);
+        object.define("_start", SymbolBinding::Global, text, 0);
+        object.define("status", SymbolBinding::Global, data, 0);
+        object.relocate(text, 2, "status", R_X86_64_PC32, -4);
+        object
+    }
+
+    #[test]
+    fn links_an_executable() {
+        let directory = temp_dir("link");
+        let input = directory.join("exit.o");
+        fs::write(&input, exit_program(42).build()).unwrap();
+        let output = directory.join("exit");
+        let options = Options {
+            inputs: vec![Input {
+                path: input.to_string_lossy().into_owned(),
+                whole_archive: false,
+                group: None,
+            }],
+            output: output.to_string_lossy().into_owned(),
+            ..Options::default()
+        };
+        link(&options).unwrap();
+
+        let binary = fs::read(&output).unwrap();
+        let file = ElfFile::new(&binary).unwrap();
+        let start = file
+            .get_symbols()
+            .unwrap()
+            .into_iter()
+            .find(|symbol| symbol.name == "_start")
+            .unwrap();
+        assert_eq!(file.get_elf_header().e_entry, start.entry.st_value);
+
+        let segments = file.get_program_headers().unwrap();
+        let loads = segments
+            .iter()
+            .filter(|segment| ProgramType::from(segment.p_type) == ProgramType::Load)
+            .collect::<Vec<_>>();
+        let flags = loads
+            .iter()
+            .map(|segment| segment.p_flags)
+            .collect::<Vec<_>>();
+        let read_execute = ProgramFlags::R.0 | ProgramFlags::X.0;
+        let read_write = ProgramFlags::R.0 | ProgramFlags::W.0;
+        assert_eq!(flags, [ProgramFlags::R.0, read_execute, read_write]);
+        // The headers are loaded at the base address, and each segment starts a new page.
+        assert_eq!((loads[0].p_vaddr, loads[0].p_offset), (BASE_ADDRESS, 0));
+        for segment in loads.iter() {
+            assert_eq!(segment.p_vaddr % PAGE_SIZE, segment.p_offset % PAGE_SIZE);
+        }
+        for pair in loads.windows(2) {
+            assert!(pair[0].p_vaddr + pair[0].p_memsz <= pair[1].p_vaddr & !(PAGE_SIZE - 1));
+        }
+        let entry = file.get_elf_header().e_entry;
+        assert!(loads[1].p_vaddr <= entry && entry < loads[1].p_vaddr + loads[1].p_memsz);
+        assert!(segments
+            .iter()
+            .any(|segment| ProgramType::from(segment.p_type) == ProgramType::GnuStack));
+        let text = file
+            .get_section_headers()
+            .unwrap()
+            .iter()
+            .find(|header| header.flags().contains(SectionFlags::EXECINSTR))
+            .unwrap();
+        assert_eq!(text.sh_addr, entry);
+
+        if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+            let status = std::process::Command::new(&output).status().unwrap();
+            assert_eq!(status.code(), Some(42));
+        }
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn links_with_a_script() {
+        let directory = temp_dir("script");
+        let input = directory.join("exit.o");
+        fs::write(&input, exit_program(7).build()).unwrap();
+        let script = directory.join("exit.ld");
+        fs::write(
+            &script,
+            "ENTRY(_start)
+            SECTIONS {
+              . = 0x400000 + SIZEOF_HEADERS;
+              .text : { *(.text) }
+              . = ALIGN(0x1000);
+              .data : AT(0x800000) { *(.data) }
+            }",
+        )
+        .unwrap();
+        let output = directory.join("exit");
+        let options = Options {
+            inputs: vec![Input {
+                path: input.to_string_lossy().into_owned(),
+                whole_archive: false,
+                group: None,
+            }],
+            output: output.to_string_lossy().into_owned(),
+            entry: None,
+            script: Some(script.to_string_lossy().into_owned()),
+        };
+        link(&options).unwrap();
+
+        let binary = fs::read(&output).unwrap();
+        let file = ElfFile::new(&binary).unwrap();
+        let segments = file.get_program_headers().unwrap();
+        let types = segments
+            .iter()
+            .map(|segment| ProgramType::from(segment.p_type))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            types,
+            [ProgramType::Load, ProgramType::Load, ProgramType::GnuStack]
+        );
+        // `.text` comes right after the headers, in the file as in memory.
+        let headers_size: u64 = 64 + 3 * 56;
+        let text = &segments[0];
+        assert_eq!(
+            text.p_vaddr,
+            BASE_ADDRESS + headers_size.next_multiple_of(16)
+        );
+        assert_eq!(text.p_vaddr, BASE_ADDRESS + text.p_offset);
+        assert_eq!(text.p_flags, ProgramFlags::R.0 | ProgramFlags::X.0);
+        assert_eq!(file.get_elf_header().e_entry, text.p_vaddr);
+        let data = &segments[1];
+        assert_eq!((data.p_vaddr, data.p_paddr), (0x401000, 0x800000));
+        assert_eq!(data.p_vaddr % PAGE_SIZE, data.p_offset % PAGE_SIZE);
+        assert_eq!(&binary[data.p_offset as usize..][..4], &[7, 0, 0, 0]);
+
+        if cfg!(all(target_os = "linux", target_arch = "x86_64")) {
+            let status = std::process::Command::new(&output).status().unwrap();
+            assert_eq!(status.code(), Some(7));
+        }
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn requires_an_entry_point() {
+        let directory = temp_dir("entry");
+        let input = directory.join("exit.o");
+        fs::write(&input, exit_program(0).build()).unwrap();
+        let options = Options {
+            inputs: vec![Input {
+                path: input.to_string_lossy().into_owned(),
+                whole_archive: false,
+                group: None,
+            }],
+            output: directory.join("exit").to_string_lossy().into_owned(),
+            entry: Some("main".to_string()),
+            script: None,
+        };
+        match link(&options) {
+            Err(LinkError::MissingEntry { name }) => assert_eq!(name, "main"),
+            other => panic!("{:?}", other),
+        }
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn numbers_output_sections_after_the_null_section() {
+        assert_eq!(section_index(0).unwrap(), 1);
+        assert_eq!(
+            section_index(SHN_LORESERVE as usize - 2).unwrap(),
+            SHN_LORESERVE - 1
+        );
+        assert!(matches!(
+            section_index(SHN_LORESERVE as usize - 1),
+            Err(LinkError::TooManySections { .. })
+        ));
+        assert!(matches!(
+            section_index(usize::from(u16::MAX)),
+            Err(LinkError::TooManySections { .. })
+        ));
+    }
+}
@@ -1,3 +1,61 @@
+mod archive;
+mod error;
+mod input;
+mod layout;
+mod link;
+mod output;
+mod relocate;
+mod script;
+mod symbols;
+#[cfg(test)]
+mod testing;
+
+use link::{Input, Options};
+use std::process;
+
+const USAGE: &str =
+    "usage: chapter8 [-o output] [-e entry] [-T script] [--whole-archive] [--no-whole-archive]
+                [--start-group] [--end-group] file...";
+
 fn main() {
-    println!("Hello, world!");
+    let mut options = Options::default();
+    let mut whole_archive = false;
+    let mut group = None;
+    let mut groups = 0;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-o" | "-e" | "-T" => match args.next() {
+                Some(value) if arg == "-o" => options.output = value,
+                Some(value) if arg == "-e" => options.entry = Some(value),
+                Some(value) => options.script = Some(value),
+                None => usage(),
+            },
+            "--whole-archive" => whole_archive = true,
+            "--no-whole-archive" => whole_archive = false,
+            "--start-group" | "-(" if group.is_none() => {
+                group = Some(groups);
+                groups += 1;
+            }
+            "--end-group" | "-)" if group.is_some() => group = None,
+            "--start-group" | "-(" | "--end-group" | "-)" => usage(),
+            _ => options.inputs.push(Input {
+                path: arg,
+                whole_archive,
+                group,
+            }),
+        }
+    }
+    if group.is_some() {
+        usage();
+    }
+    if let Err(error) = link::link(&options) {
+        eprintln!("chapter8: {}", error);
+        process::exit(1);
+    }
+}
+
+fn usage() -> ! {
+    eprintln!("{}", USAGE);
+    process::exit(2);
 }
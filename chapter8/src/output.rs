@@ -0,0 +1,81 @@
+use crate::error::LinkError;
+use crate::input::ObjectFile;
+use crate::layout::Layout;
+use elf::{Class, ElfSectionHeader, ElfType, ElfWriter, Encoding, Endian, Machine, WriterSymbol};
+use std::fs;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+
+/// Contents of each output section of `layout`: the input sections copied to their offsets.
+/// `SHT_NOBITS` sections are left empty.
+pub fn build_contents(objects: &[ObjectFile], layout: &Layout) -> Result<Vec<Vec<u8>>, LinkError> {
+    let mut contents = Vec::<Vec<u8>>::new();
+    for section in layout.sections.iter() {
+        if section.is_nobits() {
+            contents.push(Vec::new());
+            continue;
+        }
+        let mut data = vec![0; section.size as usize];
+        for input in section.inputs.iter() {
+            let object = &objects[input.file];
+            let input_data = object.section_data(object.section_header(input.index)?)?;
+            let start = input.offset as usize;
+            data[start..start + input_data.len()].copy_from_slice(input_data);
+        }
+        contents.push(data);
+    }
+    Ok(contents)
+}
+
+/// Writes the executable to `path`, with a `.symtab` holding `symbols`.
+pub fn write_executable(
+    path: &str,
+    layout: &Layout,
+    contents: Vec<Vec<u8>>,
+    entry: u64,
+    symbols: Vec<WriterSymbol>,
+) -> Result<(), LinkError> {
+    let encoding = Encoding {
+        class: Class::Elf64,
+        endian: Endian::Little,
+    };
+    let mut writer = ElfWriter::new(encoding, ElfType::Exec, Machine::X86_64);
+    writer.header.e_entry = entry;
+    writer.segments = layout.segments.clone();
+    for (section, data) in layout.sections.iter().zip(contents) {
+        writer.add_section(
+            section.name,
+            ElfSectionHeader {
+                sh_name: 0,
+                sh_type: section.sh_type,
+                sh_flags: section.sh_flags,
+                sh_addr: section.address,
+                sh_offset: 0,
+                sh_size: section.size,
+                sh_link: 0,
+                sh_info: 0,
+                sh_addralign: section.align,
+                sh_entsize: 0,
+            },
+            data,
+        );
+    }
+    writer.symbols = symbols;
+
+    let io_error = |error| LinkError::Io {
+        path: path.to_string(),
+        error,
+    };
+    let binary = writer
+        .to_bytes()
+        .map_err(|error| LinkError::elf(path, error))?;
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    options.mode(0o755);
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(&binary))
+        .map_err(io_error)
+}
@@ -0,0 +1,275 @@
+use crate::error::LinkError;
+use crate::input::ObjectFile;
+use crate::layout::Layout;
+use crate::symbols::SymbolTable;
+use elf::{Class, ElfError, ElfRelocation, RelocationType, X86_64RelocationType};
+use std::convert::{TryFrom, TryInto};
+
+/// How the value of a relocation is stored in the relocated field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Word64,
+    Signed32,
+    Unsigned32,
+}
+
+impl Field {
+    fn size(&self) -> usize {
+        match self {
+            Field::Word64 => 8,
+            Field::Signed32 | Field::Unsigned32 => 4,
+        }
+    }
+
+    fn fits(&self, value: i64) -> bool {
+        match self {
+            Field::Word64 => true,
+            Field::Signed32 => i32::try_from(value).is_ok(),
+            Field::Unsigned32 => u32::try_from(value).is_ok(),
+        }
+    }
+}
+
+/// Patches `contents`, the data of each output section of `layout`, with the relocations of
+/// every loaded section.
+///
+/// Relocations of sections that are not loaded, such as debug information, are skipped.
+pub fn apply_relocations(
+    objects: &[ObjectFile],
+    layout: &Layout,
+    symbols: &SymbolTable,
+    contents: &mut [Vec<u8>],
+) -> Result<(), LinkError> {
+    for (file, object) in objects.iter().enumerate() {
+        for relocations in object.relocations.iter() {
+            let target = match relocations.target_section {
+                Some(target) => target,
+                None => continue,
+            };
+            let (position, offset) = match layout.placement(file, target) {
+                Some(placement) => placement,
+                None => continue,
+            };
+            let address = layout.sections[position].address + offset;
+            // NOBITS sections have no contents, so any relocation in them is out of range.
+            let size = object.section_data(object.section_header(target)?)?.len() as u64;
+            for relocation in relocations.relocations() {
+                let relocation = relocation.map_err(|error| LinkError::elf(&object.path, error))?;
+                let error_context = |value: Option<i64>| {
+                    let path = object.path.clone();
+                    let section = object.section_name(target).to_string();
+                    let offset = relocation.offset;
+                    let relocation_type = relocation.relocation_type;
+                    match value {
+                        Some(value) => LinkError::RelocationOverflow {
+                            path,
+                            section,
+                            offset,
+                            relocation_type,
+                            value,
+                        },
+                        None => LinkError::UnsupportedRelocation {
+                            path,
+                            section,
+                            offset,
+                            relocation_type,
+                        },
+                    }
+                };
+                let (field, pc_relative) = match relocation.relocation_type {
+                    RelocationType::X86_64(X86_64RelocationType::None) => continue,
+                    RelocationType::X86_64(X86_64RelocationType::R64) => (Field::Word64, false),
+                    RelocationType::X86_64(X86_64RelocationType::Pc32)
+                    | RelocationType::X86_64(X86_64RelocationType::Plt32) => {
+                        (Field::Signed32, true)
+                    }
+                    RelocationType::X86_64(X86_64RelocationType::R32) => (Field::Unsigned32, false),
+                    RelocationType::X86_64(X86_64RelocationType::R32s) => (Field::Signed32, false),
+                    _ => return Err(error_context(None)),
+                };
+                if relocation
+                    .offset
+                    .checked_add(field.size() as u64)
+                    .is_none_or(|end| end > size)
+                {
+                    return Err(LinkError::elf(
+                        &object.path,
+                        ElfError::OffsetOutOfRange {
+                            offset: relocation.offset,
+                            size: field.size() as u64,
+                            file_size: size,
+                        },
+                    ));
+                }
+                let start = (offset + relocation.offset) as usize;
+                let bytes = &mut contents[position][start..start + field.size()];
+
+                let symbol = symbol_value(objects, layout, symbols, file, &relocation)?;
+                let addend = relocation
+                    .addend
+                    .unwrap_or_else(|| implicit_addend(bytes, field));
+                let mut value = symbol.wrapping_add(addend as u64) as i64;
+                if pc_relative {
+                    let place = address + relocation.offset;
+                    value = value.wrapping_sub(place as i64);
+                }
+                if !field.fits(value) {
+                    return Err(error_context(Some(value)));
+                }
+                match field {
+                    Field::Word64 => bytes.copy_from_slice(&(value as u64).to_le_bytes()),
+                    Field::Signed32 | Field::Unsigned32 => {
+                        bytes.copy_from_slice(&(value as u32).to_le_bytes())
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `S` of the relocation: the address of its symbol, or 0 without one.
+fn symbol_value(
+    objects: &[ObjectFile],
+    layout: &Layout,
+    symbols: &SymbolTable,
+    file: usize,
+    relocation: &ElfRelocation,
+) -> Result<u64, LinkError> {
+    match relocation.entry.symbol_index(Class::Elf64) {
+        0 => Ok(0),
+        index => symbols.address(objects, layout, file, index as usize),
+    }
+}
+
+/// Addend stored in the field itself by `SHT_REL` relocations.
+fn implicit_addend(bytes: &[u8], field: Field) -> i64 {
+    match field {
+        Field::Word64 => u64::from_le_bytes(bytes.try_into().unwrap()) as i64,
+        Field::Signed32 => i32::from_le_bytes(bytes.try_into().unwrap()) as i64,
+        Field::Unsigned32 => u32::from_le_bytes(bytes.try_into().unwrap()) as i64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output;
+    use crate::testing::*;
+    use elf::SymbolBinding;
+
+    /// Address and relocated contents of `.text` once `object` is linked alone.
+    fn link_text(object: &TestObject) -> Result<(u64, Vec<u8>), LinkError> {
+        let data = object.build();
+        let objects = vec![ObjectFile::new("test.o".to_string(), &data)?];
+        let symbols = SymbolTable::new(&objects, None)?;
+        let layout = Layout::new(&objects, &symbols)?;
+        let mut contents = output::build_contents(&objects, &layout)?;
+        apply_relocations(&objects, &layout, &symbols, &mut contents)?;
+        let text = layout
+            .sections
+            .iter()
+            .position(|section| section.name == ".text")
+            .unwrap();
+        Ok((layout.sections[text].address, contents.swap_remove(text)))
+    }
+
+    /// `.text` with one relocation at offset 4 against the absolute symbol `target`.
+    fn relocated(
+        relocation_type: u32,
+        target: u64,
+        addend: i64,
+    ) -> Result<(u64, Vec<u8>), LinkError> {
+        let mut object = TestObject::new();
+        let text = object.text(&[0x90; 16]);
+        object.absolute("target", target);
+        object.relocate(text, 4, "target", relocation_type, addend);
+        link_text(&object)
+    }
+
+    #[test]
+    fn computes_each_relocation() {
+        let target: u64 = 0x1234_5678;
+        // Type, addend, whether the place is subtracted, and field size.
+        let cases = [
+            (R_X86_64_64, 8, false, 8),
+            (R_X86_64_32, 8, false, 4),
+            (R_X86_64_32S, -8, false, 4),
+            (R_X86_64_PC32, -4, true, 4),
+            (R_X86_64_PLT32, -4, true, 4),
+        ];
+        for (relocation_type, addend, pc_relative, size) in cases.iter() {
+            let (address, text) = relocated(*relocation_type, target, *addend).unwrap();
+            let mut value = target.wrapping_add(*addend as u64);
+            if *pc_relative {
+                value = value.wrapping_sub(address + 4);
+            }
+            let field = &text[4..4 + size];
+            assert_eq!(
+                field,
+                &value.to_le_bytes()[..*size],
+                "type {}",
+                relocation_type
+            );
+            assert!(text[..4].iter().all(|byte| *byte == 0x90));
+            assert!(text[4 + size..].iter().all(|byte| *byte == 0x90));
+        }
+    }
+
+    #[test]
+    fn reports_values_out_of_range() {
+        let cases = [
+            (R_X86_64_32, 0x1_0000_0000, 0),
+            (R_X86_64_32, 0, -1),
+            (R_X86_64_32S, 0x8000_0000, 0),
+            (R_X86_64_PC32, 0x7fff_0000_0000, 0),
+            (R_X86_64_PLT32, 0x7fff_0000_0000, 0),
+        ];
+        for (relocation_type, target, addend) in cases.iter() {
+            match relocated(*relocation_type, *target, *addend) {
+                Err(LinkError::RelocationOverflow {
+                    section, offset, ..
+                }) => {
+                    assert_eq!((section.as_str(), offset), (".text", 4));
+                }
+                other => panic!("type {}: {:?}", relocation_type, other.map(|_| ())),
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_fields_past_the_section() {
+        let mut object = TestObject::new();
+        let text = object.text(&[0x90; 6]);
+        object.absolute("target", 0);
+        object.relocate(text, 4, "target", R_X86_64_32, 0);
+        match link_text(&object) {
+            Err(LinkError::Elf { .. }) => {}
+            other => panic!("{:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn relocates_against_a_global_in_another_section() {
+        let mut object = TestObject::new();
+        let text = object.text(&[0; 8]);
+        let data = object.data(&[0; 16]);
+        object.define("value", SymbolBinding::Global, data, 8);
+        object.relocate(text, 0, "value", R_X86_64_64, 2);
+        let data_file = object.build();
+        let objects = vec![ObjectFile::new("test.o".to_string(), &data_file).unwrap()];
+        let symbols = SymbolTable::new(&objects, None).unwrap();
+        let layout = Layout::new(&objects, &symbols).unwrap();
+        let mut contents = output::build_contents(&objects, &layout).unwrap();
+        apply_relocations(&objects, &layout, &symbols, &mut contents).unwrap();
+        let position = |name| {
+            layout
+                .sections
+                .iter()
+                .position(|section| section.name == name)
+                .unwrap()
+        };
+        let expected = layout.sections[position(".data")].address + 8 + 2;
+        assert_eq!(contents[position(".text")], expected.to_le_bytes());
+    }
+}
@@ -0,0 +1,894 @@
+use crate::error::LinkError;
+
+/// A GNU ld linker script, limited to the commands that place sections.
+///
+/// Understands `ENTRY`, `MEMORY`, `SECTIONS` with output section descriptions,
+/// input section wildcards, symbol and `.` assignments, `PROVIDE`, `PROVIDE_HIDDEN`,
+/// `KEEP`, `AT`, `AT>`, `>`, `/DISCARD/` and `SIZEOF_HEADERS`. `OUTPUT_FORMAT`, `OUTPUT_ARCH`, `SEARCH_DIR` and `TARGET`
+/// are accepted and ignored.
+#[derive(Debug, Clone)]
+pub struct Script {
+    /// Name used in diagnostics.
+    pub path: String,
+    pub entry: Option<String>,
+    pub regions: Vec<MemoryRegion>,
+    /// Assignments and output section descriptions, in order.
+    pub commands: Vec<Command>,
+}
+
+/// A `MEMORY` region that output sections can be placed in with `>` and `AT>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryRegion {
+    pub name: String,
+    /// Attributes such as `rx`. They are kept but do not pick sections.
+    pub attributes: String,
+    pub origin: u64,
+    pub length: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Assignment(Assignment),
+    Output(OutputDescription),
+}
+
+/// `symbol = expression;`, where the symbol `.` is the location counter.
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    pub symbol: String,
+    pub expression: Expr,
+    /// Defined only when an input references it and no input defines it.
+    pub provide: bool,
+    /// `PROVIDE_HIDDEN`: the symbol is `STV_HIDDEN`, so it stays local to the executable.
+    pub hidden: bool,
+}
+
+/// `name [address] [(NOLOAD)] : [AT(load)] [ALIGN(align)] { ... } [> region] [AT> region]`.
+#[derive(Debug, Clone)]
+pub struct OutputDescription {
+    pub name: String,
+    pub address: Option<Expr>,
+    pub no_load: bool,
+    pub load_address: Option<Expr>,
+    pub align: Option<Expr>,
+    pub contents: Vec<OutputCommand>,
+    pub region: Option<String>,
+    pub load_region: Option<String>,
+}
+
+impl OutputDescription {
+    /// `/DISCARD/` drops the sections that it matches.
+    pub fn is_discard(&self) -> bool {
+        self.name == "/DISCARD/"
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum OutputCommand {
+    Input(InputDescription),
+    Assignment(Assignment),
+}
+
+/// `file(section...)`, such as `*(.text .text.*)` or `KEEP(*crt0.o(.init))`.
+#[derive(Debug, Clone)]
+pub struct InputDescription {
+    pub file: String,
+    /// Section name patterns. `COMMON` stands for the common symbols.
+    pub sections: Vec<String>,
+}
+
+impl InputDescription {
+    /// Whether the section `section` of the input `path` is matched.
+    /// Archive members, named `lib.a(member.o)`, also match by member name.
+    pub fn matches(&self, path: &str, section: &str) -> bool {
+        let member = path
+            .strip_suffix(')')
+            .and_then(|path| path.rsplit_once('(').map(|(_, member)| member));
+        let file = wildcard_match(&self.file, path)
+            || member.is_some_and(|member| wildcard_match(&self.file, member))
+            || path
+                .rsplit_once('/')
+                .is_some_and(|(_, name)| wildcard_match(&self.file, name));
+        file && self
+            .sections
+            .iter()
+            .any(|pattern| pattern != "COMMON" && wildcard_match(pattern, section))
+    }
+
+    pub fn matches_common(&self) -> bool {
+        self.sections.iter().any(|pattern| pattern == "COMMON")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Number(u64),
+    /// `.`
+    Location,
+    Symbol(String),
+    Negate(Box<Expr>),
+    Not(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    /// `ALIGN(align)` aligns the location counter, `ALIGN(value, align)` the value.
+    Align(Option<Box<Expr>>, Box<Expr>),
+    Origin(String),
+    Length(String),
+    Addr(String),
+    LoadAddr(String),
+    SizeOf(String),
+    /// `SIZEOF_HEADERS`, the size of the ELF header and the program headers.
+    SizeOfHeaders,
+}
+
+impl Expr {
+    /// Value of an expression made of numbers only.
+    pub fn constant(&self) -> Option<u64> {
+        match self {
+            Expr::Number(value) => Some(*value),
+            Expr::Negate(value) => Some(value.constant()?.wrapping_neg()),
+            Expr::Not(value) => Some(!value.constant()?),
+            Expr::Binary(op, left, right) => op.apply(left.constant()?, right.constant()?),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    And,
+    Or,
+    Shl,
+    Shr,
+}
+
+impl BinaryOp {
+    /// `None` on division by zero.
+    pub fn apply(self, left: u64, right: u64) -> Option<u64> {
+        match self {
+            BinaryOp::Add => Some(left.wrapping_add(right)),
+            BinaryOp::Sub => Some(left.wrapping_sub(right)),
+            BinaryOp::Mul => Some(left.wrapping_mul(right)),
+            BinaryOp::Div => left.checked_div(right),
+            BinaryOp::Rem => left.checked_rem(right),
+            BinaryOp::And => Some(left & right),
+            BinaryOp::Or => Some(left | right),
+            BinaryOp::Shl => Some(left.wrapping_shl(right as u32)),
+            BinaryOp::Shr => Some(left.wrapping_shr(right as u32)),
+        }
+    }
+}
+
+impl Script {
+    pub fn parse(path: &str, text: &str) -> Result<Script, LinkError> {
+        let mut parser = Parser {
+            path,
+            text,
+            position: 0,
+        };
+        let mut script = Script {
+            path: path.to_string(),
+            entry: None,
+            regions: Vec::new(),
+            commands: Vec::new(),
+        };
+        while !parser.at_end()? {
+            let keyword = parser.word()?;
+            match keyword {
+                "ENTRY" => script.entry = Some(parser.parenthesized_name()?),
+                "MEMORY" => parser.memory(&mut script.regions)?,
+                "SECTIONS" => parser.sections(&mut script)?,
+                "OUTPUT_FORMAT" | "OUTPUT_ARCH" | "SEARCH_DIR" | "TARGET" => {
+                    parser.skip_parenthesized()?
+                }
+                _ if is_assignment_start(keyword) || parser.assignment_follows()? => {
+                    let assignment = parser.assignment(keyword)?;
+                    script.commands.push(Command::Assignment(assignment));
+                }
+                _ => return Err(parser.error(format!("unsupported command `{}`", keyword))),
+            }
+        }
+        Ok(script)
+    }
+
+    /// Symbol assignments anywhere in the script, in order. Assignments to `.` are left out.
+    pub fn symbol_assignments(&self) -> impl Iterator<Item = &Assignment> + '_ {
+        self.commands
+            .iter()
+            .flat_map(|command| match command {
+                Command::Assignment(assignment) => vec![assignment],
+                Command::Output(output) => output
+                    .contents
+                    .iter()
+                    .filter_map(|command| match command {
+                        OutputCommand::Assignment(assignment) => Some(assignment),
+                        OutputCommand::Input(_) => None,
+                    })
+                    .collect(),
+            })
+            .filter(|assignment| assignment.symbol != ".")
+    }
+}
+
+/// Characters of section names, file names and their wildcards.
+fn is_word_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_.$*?/-[]!~^".contains(c)
+}
+
+/// Characters of symbol names in expressions.
+fn is_symbol_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "_.$".contains(c)
+}
+
+struct Parser<'s> {
+    path: &'s str,
+    text: &'s str,
+    position: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn error(&self, message: String) -> LinkError {
+        LinkError::ScriptSyntax {
+            path: self.path.to_string(),
+            line: self.text[..self.position].matches('\n').count() + 1,
+            message,
+        }
+    }
+
+    fn rest(&self) -> &'s str {
+        &self.text[self.position..]
+    }
+
+    /// The start of what comes next, for error messages.
+    fn found(&self) -> String {
+        match self.rest().split_whitespace().next() {
+            Some(word) => format!("`{}`", word.chars().take(16).collect::<String>()),
+            None => "end of file".to_string(),
+        }
+    }
+
+    /// Skips whitespace and `/* */` comments.
+    fn skip_blanks(&mut self) -> Result<(), LinkError> {
+        loop {
+            let rest = self.rest();
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+            if !trimmed.starts_with("/*") {
+                return Ok(());
+            }
+            match trimmed.find("*/") {
+                Some(end) => self.position += end + 2,
+                None => return Err(self.error("unterminated comment".to_string())),
+            }
+        }
+    }
+
+    fn at_end(&mut self) -> Result<bool, LinkError> {
+        self.skip_blanks()?;
+        Ok(self.rest().is_empty())
+    }
+
+    fn peek(&mut self) -> Result<Option<char>, LinkError> {
+        self.skip_blanks()?;
+        Ok(self.rest().chars().next())
+    }
+
+    /// Consumes `token` if it comes next.
+    fn eat(&mut self, token: &str) -> Result<bool, LinkError> {
+        self.skip_blanks()?;
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Consumes the keyword `keyword` if it comes next as a whole word.
+    fn eat_keyword(&mut self, keyword: &str) -> Result<bool, LinkError> {
+        self.skip_blanks()?;
+        let rest = self.rest();
+        if rest.starts_with(keyword) && !rest[keyword.len()..].starts_with(is_symbol_char) {
+            self.position += keyword.len();
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), LinkError> {
+        if self.eat(token)? {
+            Ok(())
+        } else {
+            Err(self.error(format!("expected `{}`, found {}", token, self.found())))
+        }
+    }
+
+    fn take_while(&mut self, predicate: fn(char) -> bool) -> Result<&'s str, LinkError> {
+        self.skip_blanks()?;
+        let rest = self.rest();
+        let end = rest.find(|c| !predicate(c)).unwrap_or(rest.len());
+        self.position += end;
+        if end == 0 {
+            return Err(self.error(format!("expected a name, found {}", self.found())));
+        }
+        Ok(&rest[..end])
+    }
+
+    /// A section name, a file name or a wildcard pattern.
+    fn word(&mut self) -> Result<&'s str, LinkError> {
+        self.take_while(is_word_char)
+    }
+
+    fn symbol(&mut self) -> Result<&'s str, LinkError> {
+        self.take_while(is_symbol_char)
+    }
+
+    fn parenthesized_name(&mut self) -> Result<String, LinkError> {
+        self.expect("(")?;
+        let name = self.symbol()?;
+        self.expect(")")?;
+        Ok(name.to_string())
+    }
+
+    fn skip_parenthesized(&mut self) -> Result<(), LinkError> {
+        self.expect("(")?;
+        match self.rest().find(')') {
+            Some(end) => {
+                self.position += end + 1;
+                Ok(())
+            }
+            None => Err(self.error("expected `)`".to_string())),
+        }
+    }
+
+    /// `MEMORY { name [(attributes)] : ORIGIN = origin, LENGTH = length ... }`
+    fn memory(&mut self, regions: &mut Vec<MemoryRegion>) -> Result<(), LinkError> {
+        self.expect("{")?;
+        while !self.eat("}")? {
+            let name = self.symbol()?.to_string();
+            let mut attributes = String::new();
+            if self.eat("(")? {
+                attributes = self.word()?.to_string();
+                self.expect(")")?;
+            }
+            self.expect(":")?;
+            let origin = self.region_field(&["ORIGIN", "org", "o"])?;
+            self.eat(",")?;
+            let length = self.region_field(&["LENGTH", "len", "l"])?;
+            regions.push(MemoryRegion {
+                name,
+                attributes,
+                origin,
+                length,
+            });
+        }
+        Ok(())
+    }
+
+    fn region_field(&mut self, keywords: &[&str]) -> Result<u64, LinkError> {
+        let keyword = self.symbol()?;
+        if !keywords.contains(&keyword) {
+            return Err(self.error(format!("expected `{}`, found `{}`", keywords[0], keyword)));
+        }
+        self.expect("=")?;
+        let value = self.expression()?;
+        value.constant().ok_or_else(|| {
+            self.error(format!(
+                "{} of a memory region must be a constant",
+                keywords[0]
+            ))
+        })
+    }
+
+    /// `SECTIONS { ... }`
+    fn sections(&mut self, script: &mut Script) -> Result<(), LinkError> {
+        self.expect("{")?;
+        loop {
+            if self.eat("}")? {
+                return Ok(());
+            }
+            if self.eat(";")? {
+                continue;
+            }
+            let name = self.word()?;
+            if name == "ENTRY" {
+                script.entry = Some(self.parenthesized_name()?);
+            } else if is_assignment_start(name) || self.assignment_follows()? {
+                let assignment = self.assignment(name)?;
+                script.commands.push(Command::Assignment(assignment));
+            } else {
+                let output = self.output_description(name)?;
+                script.commands.push(Command::Output(output));
+            }
+        }
+    }
+
+    fn assignment_follows(&mut self) -> Result<bool, LinkError> {
+        self.skip_blanks()?;
+        let rest = self.rest();
+        Ok(rest.starts_with("+=")
+            || rest.starts_with("-=")
+            || (rest.starts_with('=') && !rest.starts_with("==")))
+    }
+
+    /// `symbol = expression;`, `PROVIDE(symbol = expression);` or
+    /// `PROVIDE_HIDDEN(symbol = expression);`, after the first word.
+    fn assignment(&mut self, first: &str) -> Result<Assignment, LinkError> {
+        let provide = is_assignment_start(first);
+        let symbol = if provide {
+            self.expect("(")?;
+            self.symbol()?
+        } else {
+            first
+        };
+        if !symbol.chars().all(is_symbol_char) {
+            return Err(self.error(format!("unsupported command `{}`", symbol)));
+        }
+        let operator = if self.eat("+=")? {
+            Some(BinaryOp::Add)
+        } else if self.eat("-=")? {
+            Some(BinaryOp::Sub)
+        } else {
+            self.expect("=")?;
+            None
+        };
+        let mut expression = self.expression()?;
+        if let Some(operator) = operator {
+            let target = match symbol {
+                "." => Expr::Location,
+                _ => Expr::Symbol(symbol.to_string()),
+            };
+            expression = Expr::Binary(operator, Box::new(target), Box::new(expression));
+        }
+        if provide {
+            self.expect(")")?;
+        }
+        self.eat(";")?;
+        Ok(Assignment {
+            symbol: symbol.to_string(),
+            expression,
+            provide,
+            hidden: first == "PROVIDE_HIDDEN",
+        })
+    }
+
+    fn output_description(&mut self, name: &str) -> Result<OutputDescription, LinkError> {
+        let mut output = OutputDescription {
+            name: name.to_string(),
+            address: None,
+            no_load: false,
+            load_address: None,
+            align: None,
+            contents: Vec::new(),
+            region: None,
+            load_region: None,
+        };
+        if self.peek()? != Some(':') && !self.section_type(&mut output)? {
+            output.address = Some(self.expression()?);
+            self.section_type(&mut output)?;
+        }
+        self.expect(":")?;
+        loop {
+            if self.eat_keyword("AT")? {
+                self.expect("(")?;
+                output.load_address = Some(self.expression()?);
+                self.expect(")")?;
+            } else if self.eat_keyword("ALIGN")? {
+                self.expect("(")?;
+                output.align = Some(self.expression()?);
+                self.expect(")")?;
+            } else {
+                break;
+            }
+        }
+        self.expect("{")?;
+        while !self.eat("}")? {
+            if self.eat(";")? {
+                continue;
+            }
+            let word = self.word()?;
+            let command = match word {
+                // Sections are never garbage collected, so they are all kept anyway.
+                "KEEP" => {
+                    self.expect("(")?;
+                    let file = self.word()?;
+                    let input = self.input_description(file)?;
+                    self.expect(")")?;
+                    OutputCommand::Input(input)
+                }
+                _ if is_assignment_start(word) || self.assignment_follows()? => {
+                    OutputCommand::Assignment(self.assignment(word)?)
+                }
+                "SORT" | "SORT_BY_NAME" | "SORT_BY_ALIGNMENT" | "EXCLUDE_FILE" | "FILL"
+                | "BYTE" | "SHORT" | "LONG" | "QUAD" => {
+                    return Err(self.error(format!("unsupported command `{}`", word)))
+                }
+                _ => OutputCommand::Input(self.input_description(word)?),
+            };
+            output.contents.push(command);
+        }
+        loop {
+            if self.eat_keyword("AT")? {
+                self.expect(">")?;
+                output.load_region = Some(self.symbol()?.to_string());
+            } else if self.eat(">")? {
+                output.region = Some(self.symbol()?.to_string());
+            } else {
+                break;
+            }
+        }
+        self.eat(",")?;
+        Ok(output)
+    }
+
+    /// `(NOLOAD)` and the other section types in parentheses. Returns false, consuming
+    /// nothing, when the parenthesis starts an address expression instead.
+    fn section_type(&mut self, output: &mut OutputDescription) -> Result<bool, LinkError> {
+        let start = self.position;
+        if !self.eat("(")? {
+            return Ok(false);
+        }
+        match self.take_while(is_symbol_char).ok() {
+            Some("NOLOAD") if self.eat(")")? => {
+                output.no_load = true;
+                Ok(true)
+            }
+            Some(word @ ("DSECT" | "COPY" | "INFO" | "OVERLAY")) if self.eat(")")? => {
+                Err(self.error(format!("unsupported section type `{}`", word)))
+            }
+            _ => {
+                self.position = start;
+                Ok(false)
+            }
+        }
+    }
+
+    /// `file(section...)` after the file pattern. A file alone takes all its sections.
+    fn input_description(&mut self, file: &str) -> Result<InputDescription, LinkError> {
+        let mut sections = Vec::new();
+        if self.eat("(")? {
+            while !self.eat(")")? {
+                sections.push(self.word()?.to_string());
+            }
+        } else {
+            sections.push("*".to_string());
+        }
+        Ok(InputDescription {
+            file: file.to_string(),
+            sections,
+        })
+    }
+
+    fn expression(&mut self) -> Result<Expr, LinkError> {
+        self.binary(0)
+    }
+
+    /// Operators of one precedence level and the ones above it, from `|` to `*`.
+    fn binary(&mut self, level: usize) -> Result<Expr, LinkError> {
+        const LEVELS: [&[(&str, BinaryOp)]; 5] = [
+            &[("|", BinaryOp::Or)],
+            &[("&", BinaryOp::And)],
+            &[("<<", BinaryOp::Shl), (">>", BinaryOp::Shr)],
+            &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+            &[
+                ("*", BinaryOp::Mul),
+                ("/", BinaryOp::Div),
+                ("%", BinaryOp::Rem),
+            ],
+        ];
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        'operators: loop {
+            self.skip_blanks()?;
+            for (token, op) in LEVELS[level].iter() {
+                let rest = self.rest();
+                if !rest.starts_with(token) {
+                    continue;
+                }
+                // `|`, `&` and `+` are not `||`, `&&` or `+=`.
+                if !rest[token.len()..].starts_with(['|', '&', '=']) {
+                    self.position += token.len();
+                    let right = self.binary(level + 1)?;
+                    left = Expr::Binary(*op, Box::new(left), Box::new(right));
+                    continue 'operators;
+                }
+            }
+            return Ok(left);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, LinkError> {
+        if self.eat("-")? {
+            return Ok(Expr::Negate(Box::new(self.unary()?)));
+        }
+        if self.eat("~")? {
+            return Ok(Expr::Not(Box::new(self.unary()?)));
+        }
+        if self.eat("(")? {
+            let expression = self.expression()?;
+            self.expect(")")?;
+            return Ok(expression);
+        }
+        let start = self.position;
+        let word = self.symbol()?;
+        if word.starts_with(|c: char| c.is_ascii_digit()) {
+            return parse_number(word).map(Expr::Number).ok_or_else(|| {
+                self.position = start;
+                self.error(format!("bad number `{}`", word))
+            });
+        }
+        let function = match word {
+            "ALIGN" => {
+                self.expect("(")?;
+                let first = self.expression()?;
+                let expression = if self.eat(",")? {
+                    Expr::Align(Some(Box::new(first)), Box::new(self.expression()?))
+                } else {
+                    Expr::Align(None, Box::new(first))
+                };
+                self.expect(")")?;
+                return Ok(expression);
+            }
+            "ORIGIN" | "LENGTH" => {
+                let name = self.parenthesized_name()?;
+                match word {
+                    "ORIGIN" => Expr::Origin(name),
+                    _ => Expr::Length(name),
+                }
+            }
+            "ADDR" | "LOADADDR" | "SIZEOF" => {
+                self.expect("(")?;
+                let name = self.word()?.to_string();
+                self.expect(")")?;
+                match word {
+                    "ADDR" => Expr::Addr(name),
+                    "LOADADDR" => Expr::LoadAddr(name),
+                    _ => Expr::SizeOf(name),
+                }
+            }
+            "SIZEOF_HEADERS" => Expr::SizeOfHeaders,
+            "." => Expr::Location,
+            _ => Expr::Symbol(word.to_string()),
+        };
+        Ok(function)
+    }
+}
+
+fn is_assignment_start(word: &str) -> bool {
+    word == "PROVIDE" || word == "PROVIDE_HIDDEN"
+}
+
+/// Decimal, `0x` hexadecimal or `0` octal, with an optional `K` or `M` suffix.
+fn parse_number(text: &str) -> Option<u64> {
+    let (text, multiplier) = match text.strip_suffix(['K', 'k']) {
+        Some(text) => (text, 1024),
+        None => match text.strip_suffix(['M', 'm']) {
+            Some(text) => (text, 1024 * 1024),
+            None => (text, 1),
+        },
+    };
+    let value = if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok()?
+    } else if text.len() > 1 && text.starts_with('0') {
+        u64::from_str_radix(&text[1..], 8).ok()?
+    } else {
+        text.parse().ok()?
+    };
+    value.checked_mul(multiplier)
+}
+
+/// Matches `*` (any run of characters), `?` (any character) and `[...]` (a set or range
+/// of characters, negated with `!` or `^`).
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let text = text.chars().collect::<Vec<_>>();
+    match_from(&pattern, &text)
+}
+
+fn match_from(pattern: &[char], text: &[char]) -> bool {
+    match pattern.first() {
+        None => text.is_empty(),
+        Some('*') => (0..=text.len()).any(|skip| match_from(&pattern[1..], &text[skip..])),
+        Some('?') => !text.is_empty() && match_from(&pattern[1..], &text[1..]),
+        Some('[') => {
+            let end = match pattern.iter().skip(2).position(|c| *c == ']') {
+                Some(end) => end + 2,
+                None => return text.first() == Some(&'[') && match_from(&pattern[1..], &text[1..]),
+            };
+            let (negated, set) = match pattern[1] {
+                '!' | '^' => (true, &pattern[2..end]),
+                _ => (false, &pattern[1..end]),
+            };
+            let c = match text.first() {
+                Some(c) => *c,
+                None => return false,
+            };
+            let mut found = false;
+            let mut i = 0;
+            while i < set.len() {
+                if i + 2 < set.len() && set[i + 1] == '-' {
+                    found |= set[i] <= c && c <= set[i + 2];
+                    i += 3;
+                } else {
+                    found |= set[i] == c;
+                    i += 1;
+                }
+            }
+            found != negated && match_from(&pattern[end + 1..], &text[1..])
+        }
+        Some(c) => text.first() == Some(c) && match_from(&pattern[1..], &text[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = "ENTRY(_start)
+MEMORY { rom (rx) : ORIGIN = 0x8000000, LENGTH = 64K
+         ram (rwx) : ORIGIN = 0x20000000, LENGTH = 16K }
+SECTIONS {
+  . = 0x400000 + SIZEOF_HEADERS;
+  .text : { KEEP(*crt0.o(.init)) *(.text .text.*) } > rom
+  .data : AT(ALIGN(LOADADDR(.text) + SIZEOF(.text), 8)) { *(.data) } > ram AT> rom
+  /* comment */
+  .bss (NOLOAD) : ALIGN(16) { *(.bss) *(COMMON) } > ram
+  PROVIDE(_end = .);
+  PROVIDE_HIDDEN(__bss_end = .);
+  _stack = ORIGIN(ram) + LENGTH(ram) - (1 << 4) | 0 & ~0xf;
+  /DISCARD/ : { *(.comment) }
+}
+";
+
+    fn syntax_error(text: &str) -> (usize, String) {
+        match Script::parse("test.ld", text) {
+            Err(LinkError::ScriptSyntax { line, message, .. }) => (line, message),
+            other => panic!("{:?} for {:?}", other.map(|_| ()), text),
+        }
+    }
+
+    #[test]
+    fn parses_the_supported_commands() {
+        let script = Script::parse("test.ld", SCRIPT).unwrap();
+        assert_eq!(script.entry.as_deref(), Some("_start"));
+        assert_eq!(script.regions.len(), 2);
+        assert_eq!(
+            (script.regions[0].origin, script.regions[0].length),
+            (0x800_0000, 0x10000)
+        );
+        let outputs = script
+            .commands
+            .iter()
+            .filter_map(|command| match command {
+                Command::Output(output) => Some(output),
+                Command::Assignment(_) => None,
+            })
+            .collect::<Vec<_>>();
+        let names = outputs
+            .iter()
+            .map(|output| output.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, [".text", ".data", ".bss", "/DISCARD/"]);
+        assert_eq!(outputs[1].load_region.as_deref(), Some("rom"));
+        assert!(outputs[2].no_load && outputs[2].align.is_some());
+        assert!(outputs[3].is_discard());
+        let symbols = script
+            .symbol_assignments()
+            .map(|assignment| {
+                (
+                    assignment.symbol.as_str(),
+                    assignment.provide,
+                    assignment.hidden,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            symbols,
+            [
+                ("_end", true, false),
+                ("__bss_end", true, true),
+                ("_stack", false, false)
+            ]
+        );
+        match &script.commands[0] {
+            Command::Assignment(assignment) => assert!(matches!(
+                &assignment.expression,
+                Expr::Binary(BinaryOp::Add, _, size) if matches!(**size, Expr::SizeOfHeaders)
+            )),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_malformed_scripts_with_their_line() {
+        let cases = [
+            (
+                "SECTIONS {\n  .text : { *(.text) \n",
+                3,
+                "expected a name, found end of file",
+            ),
+            (
+                "SECTIONS {\n  . = 0x400000 é;\n}",
+                2,
+                "expected a name, found `é;`",
+            ),
+            (
+                "SECTIONS { . = 0x400000 + é; }",
+                1,
+                "expected a name, found `é;`",
+            ),
+            ("SECTIONS { . = 0x1z; }", 1, "bad number `0x1z`"),
+            ("/* open\n\n", 1, "unterminated comment"),
+            (
+                "SECTIONS { .text : { SORT(*)(.text) } }",
+                1,
+                "unsupported command `SORT`",
+            ),
+            ("INCLUDE other.ld", 1, "unsupported command `INCLUDE`"),
+            ("ENTRY(_start", 1, "expected `)`, found end of file"),
+            (
+                "MEMORY { rom : ORIGIN = ., LENGTH = 1 }",
+                1,
+                "ORIGIN of a memory region must be a constant",
+            ),
+            (
+                "SECTIONS { .text (DSECT) : { } }",
+                1,
+                "unsupported section type `DSECT`",
+            ),
+            ("ÉNTRY(_start)", 1, "expected a name, found `ÉNTRY(_start)`"),
+        ];
+        for (text, line, message) in cases.iter() {
+            assert_eq!(
+                syntax_error(text),
+                (*line, message.to_string()),
+                "{:?}",
+                text
+            );
+        }
+    }
+
+    /// Every prefix of a valid script, and the script with a multibyte character put at any
+    /// point, parse or fail with an error, never a panic.
+    #[test]
+    fn never_panics_on_malformed_input() {
+        let boundaries = (0..=SCRIPT.len()).filter(|index| SCRIPT.is_char_boundary(*index));
+        for index in boundaries {
+            let _ = Script::parse("test.ld", &SCRIPT[..index]);
+            for inserted in ["é", "→", "\u{1f600}", "\u{0}"].iter() {
+                let text = format!("{}{}{}", &SCRIPT[..index], inserted, &SCRIPT[index..]);
+                let _ = Script::parse("test.ld", &text);
+            }
+        }
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        let cases = [
+            ("*", "", true),
+            ("*.o", "crt0.o", true),
+            (".text.*", ".text", false),
+            ("?.o", "é.o", true),
+            ("[a-c]*", "b.o", true),
+            ("[!a-c]*", "b.o", false),
+            ("[", "[", true),
+            ("[é]", "é", true),
+        ];
+        for (pattern, text, expected) in cases.iter() {
+            assert_eq!(
+                wildcard_match(pattern, text),
+                *expected,
+                "{} {}",
+                pattern,
+                text
+            );
+        }
+    }
+}
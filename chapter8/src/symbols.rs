@@ -0,0 +1,461 @@
+use crate::error::LinkError;
+use crate::input::ObjectFile;
+use crate::layout::Layout;
+use crate::script::Script;
+use elf::{ElfSymbol, SectionIndex, SymbolBinding, SymbolVisibility};
+use std::collections::HashMap;
+
+/// Symbol `index` of the object at `file`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolId {
+    pub file: usize,
+    pub index: usize,
+}
+
+/// What a global symbol resolved to once every input has been seen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Defined by a `STB_GLOBAL` symbol, in a section or as an absolute value.
+    Strong(SymbolId),
+    /// Defined by `STB_WEAK` symbols only. The first one wins.
+    Weak(SymbolId),
+    /// Tentative definitions from `SHN_COMMON`, merged to the largest size and alignment.
+    /// They are allocated in `.bss` by the layout. `id` is the largest one.
+    Common { id: SymbolId, size: u64, align: u64 },
+    /// Only ever referenced, and only weakly, so the symbol is 0.
+    UndefinedWeak,
+    /// Assigned by the linker script, which wins over the inputs. The value comes from
+    /// the layout.
+    Script,
+}
+
+/// A global symbol of the link, bound to the definition that won.
+#[derive(Debug, Clone, Copy)]
+pub struct GlobalSymbol<'a> {
+    pub name: &'a str,
+    pub resolution: Resolution,
+    /// Most constraining visibility of all references and definitions.
+    pub visibility: SymbolVisibility,
+}
+
+impl<'a> GlobalSymbol<'a> {
+    /// Input symbol that provides the definition, if an input defines it.
+    pub fn definition(&self) -> Option<SymbolId> {
+        match self.resolution {
+            Resolution::Strong(id) | Resolution::Weak(id) | Resolution::Common { id, .. } => {
+                Some(id)
+            }
+            Resolution::UndefinedWeak | Resolution::Script => None,
+        }
+    }
+
+    /// Whether the symbol stays global in the executable.
+    /// `STV_HIDDEN` and `STV_INTERNAL` symbols become local once they are linked.
+    pub fn is_exported(&self) -> bool {
+        !matches!(
+            self.visibility,
+            SymbolVisibility::Hidden | SymbolVisibility::Internal
+        )
+    }
+}
+
+/// Global symbols of every input, resolved with the ELF rules: a global definition beats
+/// commons and weak definitions, and commons beat weak definitions.
+pub struct SymbolTable<'a> {
+    symbols: Vec<GlobalSymbol<'a>>,
+    /// Position in `symbols` by name.
+    indices: HashMap<&'a str, usize>,
+}
+
+impl<'a> SymbolTable<'a> {
+    /// Resolves the global symbols of `objects` and of the assignments of `script`, and checks
+    /// that every non weak reference is defined.
+    pub fn new(
+        objects: &[ObjectFile<'a>],
+        script: Option<&'a Script>,
+    ) -> Result<SymbolTable<'a>, LinkError> {
+        let mut table = SymbolTable {
+            symbols: Vec::new(),
+            indices: HashMap::new(),
+        };
+        // First reference that is not weak, for the symbols that may end up undefined.
+        let mut strong_references = HashMap::<&'a str, SymbolId>::new();
+        for (file, object) in objects.iter().enumerate() {
+            for (index, symbol) in object.symbols.iter().enumerate().skip(1) {
+                if symbol.binding == SymbolBinding::Local {
+                    continue;
+                }
+                let id = SymbolId { file, index };
+                if !is_defined(symbol) && symbol.binding != SymbolBinding::Weak {
+                    strong_references.entry(symbol.name).or_insert(id);
+                }
+                table.add(objects, id, symbol)?;
+            }
+        }
+        for assignment in script.iter().flat_map(|script| script.symbol_assignments()) {
+            table.assign(&assignment.symbol, assignment.provide, assignment.hidden);
+        }
+        for symbol in table.symbols.iter() {
+            if symbol.resolution != Resolution::UndefinedWeak {
+                continue;
+            }
+            if let Some(id) = strong_references.get(symbol.name) {
+                return Err(LinkError::UndefinedSymbol {
+                    name: symbol.name.to_string(),
+                    path: objects[id.file].path.clone(),
+                });
+            }
+        }
+        Ok(table)
+    }
+
+    /// Merges `symbol`, the symbol `id`, into the definition seen so far under its name.
+    fn add(
+        &mut self,
+        objects: &[ObjectFile<'a>],
+        id: SymbolId,
+        symbol: &ElfSymbol<'a>,
+    ) -> Result<(), LinkError> {
+        let resolution = match symbol.section_index {
+            SectionIndex::Undefined => Resolution::UndefinedWeak,
+            SectionIndex::Common => Resolution::Common {
+                id,
+                size: symbol.entry.st_size,
+                // st_value holds the alignment of a common symbol.
+                align: symbol.entry.st_value.max(1),
+            },
+            _ if symbol.binding == SymbolBinding::Weak => Resolution::Weak(id),
+            _ => Resolution::Strong(id),
+        };
+        let position = match self.indices.get(symbol.name) {
+            Some(position) => *position,
+            None => {
+                self.indices.insert(symbol.name, self.symbols.len());
+                self.symbols.push(GlobalSymbol {
+                    name: symbol.name,
+                    resolution,
+                    visibility: symbol.visibility,
+                });
+                return Ok(());
+            }
+        };
+        let current = &mut self.symbols[position];
+        current.visibility = most_constraining(current.visibility, symbol.visibility);
+        match (current.resolution, resolution) {
+            (Resolution::Strong(first_id), Resolution::Strong(_)) => {
+                let first = &objects[first_id.file];
+                let second = &objects[id.file];
+                return Err(LinkError::DuplicateSymbol {
+                    name: symbol.name.to_string(),
+                    first: first.path.clone(),
+                    first_section: section_label(first, &first.symbols[first_id.index]),
+                    second: second.path.clone(),
+                    second_section: section_label(second, symbol),
+                });
+            }
+            (
+                Resolution::Common {
+                    id: first_id,
+                    size,
+                    align,
+                },
+                Resolution::Common {
+                    size: new_size,
+                    align: new_align,
+                    ..
+                },
+            ) => {
+                current.resolution = Resolution::Common {
+                    id: if new_size > size { id } else { first_id },
+                    size: size.max(new_size),
+                    align: align.max(new_align),
+                };
+            }
+            (_, Resolution::UndefinedWeak) => {}
+            (current_resolution, _) if strength(resolution) > strength(current_resolution) => {
+                current.resolution = resolution;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Defines `name` from a linker script assignment. `PROVIDE` only defines names that are
+    /// referenced and not defined by any input, and `hidden` ones become `STV_HIDDEN`.
+    fn assign(&mut self, name: &'a str, provide: bool, hidden: bool) {
+        match self.indices.get(name) {
+            Some(position) => {
+                let symbol = &mut self.symbols[*position];
+                if !provide || symbol.resolution == Resolution::UndefinedWeak {
+                    symbol.resolution = Resolution::Script;
+                    if hidden {
+                        symbol.visibility =
+                            most_constraining(symbol.visibility, SymbolVisibility::Hidden);
+                    }
+                }
+            }
+            None if !provide => {
+                self.indices.insert(name, self.symbols.len());
+                self.symbols.push(GlobalSymbol {
+                    name,
+                    resolution: Resolution::Script,
+                    visibility: SymbolVisibility::Default,
+                });
+            }
+            None => {}
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&GlobalSymbol<'a>> {
+        self.indices
+            .get(name)
+            .map(|position| &self.symbols[*position])
+    }
+
+    /// Global symbols in the order they first appear in the inputs.
+    pub fn iter(&self) -> impl Iterator<Item = &GlobalSymbol<'a>> + '_ {
+        self.symbols.iter()
+    }
+
+    /// Final address of the symbol `index` of `objects[file]` as seen from that file.
+    ///
+    /// Global references go through the table, so they land on the definition that won.
+    pub fn address(
+        &self,
+        objects: &[ObjectFile<'a>],
+        layout: &Layout,
+        file: usize,
+        index: usize,
+    ) -> Result<u64, LinkError> {
+        let object = &objects[file];
+        let symbol = &object.symbols[index];
+        if symbol.binding == SymbolBinding::Local {
+            return definition_address(objects, layout, SymbolId { file, index });
+        }
+        match self.get(symbol.name) {
+            Some(global) => self.global_address(objects, layout, global),
+            None => Err(LinkError::UndefinedSymbol {
+                name: symbol.name.to_string(),
+                path: object.path.clone(),
+            }),
+        }
+    }
+
+    /// Final address of a global symbol of the table.
+    pub fn global_address(
+        &self,
+        objects: &[ObjectFile<'a>],
+        layout: &Layout,
+        symbol: &GlobalSymbol<'a>,
+    ) -> Result<u64, LinkError> {
+        match symbol.resolution {
+            Resolution::Strong(id) | Resolution::Weak(id) => {
+                definition_address(objects, layout, id)
+            }
+            Resolution::Common { id, .. } => {
+                layout
+                    .common_address(symbol.name)
+                    .ok_or(LinkError::DiscardedSymbol {
+                        name: symbol.name.to_string(),
+                        path: objects[id.file].path.clone(),
+                    })
+            }
+            Resolution::UndefinedWeak => Ok(0),
+            Resolution::Script => Ok(layout.script_symbol(symbol.name).unwrap_or(0)),
+        }
+    }
+}
+
+fn definition_address(
+    objects: &[ObjectFile],
+    layout: &Layout,
+    id: SymbolId,
+) -> Result<u64, LinkError> {
+    let object = &objects[id.file];
+    let symbol = &object.symbols[id.index];
+    match symbol.section_index {
+        SectionIndex::Absolute => Ok(symbol.entry.st_value),
+        SectionIndex::Index(section) => layout
+            .section_address(id.file, section as usize)
+            .map(|address| address + symbol.entry.st_value)
+            .ok_or_else(|| LinkError::DiscardedSymbol {
+                // Section symbols have no name of their own.
+                name: match symbol.name {
+                    "" => object.section_name(section as usize).to_string(),
+                    name => name.to_string(),
+                },
+                path: object.path.clone(),
+            }),
+        _ => Err(LinkError::UndefinedSymbol {
+            name: symbol.name.to_string(),
+            path: object.path.clone(),
+        }),
+    }
+}
+
+fn is_defined(symbol: &ElfSymbol) -> bool {
+    symbol.section_index != SectionIndex::Undefined
+}
+
+/// Order in which definitions replace each other.
+fn strength(resolution: Resolution) -> u8 {
+    match resolution {
+        Resolution::UndefinedWeak => 0,
+        Resolution::Weak(_) => 1,
+        Resolution::Common { .. } => 2,
+        Resolution::Strong(_) => 3,
+        Resolution::Script => 4,
+    }
+}
+
+/// `STV_INTERNAL` is the most constraining visibility, then `STV_HIDDEN`, then `STV_PROTECTED`.
+fn most_constraining(a: SymbolVisibility, b: SymbolVisibility) -> SymbolVisibility {
+    let rank = |visibility| match visibility {
+        SymbolVisibility::Default => 0,
+        SymbolVisibility::Protected => 1,
+        SymbolVisibility::Hidden => 2,
+        SymbolVisibility::Internal => 3,
+    };
+    if rank(b) > rank(a) {
+        b
+    } else {
+        a
+    }
+}
+
+/// Section a symbol is defined in, for diagnostics.
+fn section_label(object: &ObjectFile, symbol: &ElfSymbol) -> String {
+    match symbol.section_index {
+        SectionIndex::Index(index) => object.section_name(index as usize).to_string(),
+        SectionIndex::Absolute => "*ABS*".to_string(),
+        SectionIndex::Common => "COMMON".to_string(),
+        index => index.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestObject;
+    use elf::SectionFlags;
+
+    /// How one input file declares the symbol `x`.
+    #[derive(Clone, Copy)]
+    enum Declaration {
+        Strong,
+        Weak,
+        Common(u64, u64),
+        Undefined,
+        UndefinedWeak,
+    }
+
+    fn object(declaration: Declaration) -> Vec<u8> {
+        let mut object = TestObject::new();
+        let data = object.section(".data", SectionFlags::WRITE, &[0; 8]);
+        match declaration {
+            Declaration::Strong => object.define("x", SymbolBinding::Global, data, 0),
+            Declaration::Weak => object.define("x", SymbolBinding::Weak, data, 0),
+            Declaration::Common(size, align) => object.common("x", size, align),
+            Declaration::Undefined => object.reference("x", SymbolBinding::Global),
+            Declaration::UndefinedWeak => object.reference("x", SymbolBinding::Weak),
+        }
+        object.build()
+    }
+
+    /// Resolution of `x` in a link of one file per declaration, in order.
+    fn resolve(declarations: &[Declaration]) -> Result<Resolution, LinkError> {
+        let data = declarations
+            .iter()
+            .map(|declaration| object(*declaration))
+            .collect::<Vec<_>>();
+        let objects = data
+            .iter()
+            .enumerate()
+            .map(|(file, data)| ObjectFile::new(format!("{}.o", file), data))
+            .collect::<Result<Vec<_>, _>>()?;
+        let table = SymbolTable::new(&objects, None)?;
+        Ok(table.get("x").unwrap().resolution)
+    }
+
+    /// `x` is the only symbol of each file, so it is always symbol 1.
+    fn id(file: usize) -> SymbolId {
+        SymbolId { file, index: 1 }
+    }
+
+    #[test]
+    fn resolves_by_strength() {
+        use Declaration::*;
+        let cases: &[(&[Declaration], Resolution)] = &[
+            (&[Weak, Strong], Resolution::Strong(id(1))),
+            (&[Strong, Weak], Resolution::Strong(id(0))),
+            (&[Weak, Weak], Resolution::Weak(id(0))),
+            (&[Undefined, Weak], Resolution::Weak(id(1))),
+            (&[Common(8, 8), Strong], Resolution::Strong(id(1))),
+            (&[Strong, Common(8, 8)], Resolution::Strong(id(0))),
+            (
+                &[Weak, Common(4, 4)],
+                Resolution::Common {
+                    id: id(1),
+                    size: 4,
+                    align: 4,
+                },
+            ),
+            (
+                &[Common(4, 16), Common(32, 4), Common(8, 8)],
+                Resolution::Common {
+                    id: id(1),
+                    size: 32,
+                    align: 16,
+                },
+            ),
+            (&[UndefinedWeak, Strong], Resolution::Strong(id(1))),
+            (&[UndefinedWeak], Resolution::UndefinedWeak),
+            (&[UndefinedWeak, UndefinedWeak], Resolution::UndefinedWeak),
+        ];
+        for (declarations, expected) in cases.iter() {
+            assert_eq!(resolve(declarations).unwrap(), *expected);
+        }
+    }
+
+    #[test]
+    fn rejects_two_strong_definitions() {
+        match resolve(&[Declaration::Weak, Declaration::Strong, Declaration::Strong]) {
+            Err(LinkError::DuplicateSymbol {
+                name,
+                first,
+                first_section,
+                second,
+                second_section,
+            }) => {
+                assert_eq!(name, "x");
+                assert_eq!((first.as_str(), first_section.as_str()), ("1.o", ".data"));
+                assert_eq!((second.as_str(), second_section.as_str()), ("2.o", ".data"));
+            }
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_undefined_strong_references() {
+        for declarations in [
+            &[Declaration::Undefined][..],
+            &[Declaration::UndefinedWeak, Declaration::Undefined],
+        ] {
+            match resolve(declarations) {
+                Err(LinkError::UndefinedSymbol { name, .. }) => assert_eq!(name, "x"),
+                other => panic!("{:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn undefined_weak_symbols_are_zero() {
+        let data = object(Declaration::UndefinedWeak);
+        let objects = vec![ObjectFile::new("weak.o".to_string(), &data).unwrap()];
+        let table = SymbolTable::new(&objects, None).unwrap();
+        let layout = Layout::new(&objects, &table).unwrap();
+        let symbol = table.get("x").unwrap();
+        assert_eq!(table.global_address(&objects, &layout, symbol).unwrap(), 0);
+        assert_eq!(table.address(&objects, &layout, 0, 1).unwrap(), 0);
+    }
+}
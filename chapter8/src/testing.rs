@@ -0,0 +1,300 @@
+//! Inputs for the tests, assembled in memory so they do not need a toolchain.
+
+use elf::{
+    Class, ElfRelocationEntry, ElfSectionHeader, ElfSymbolEntry, ElfType, ElfWriter, Encoding,
+    Endian, Machine, SectionFlags, SectionType, SymbolBinding, SymbolType, SymbolVisibility,
+    WriterSymbol, SHN_ABS, SHN_COMMON, SHN_UNDEF,
+};
+use std::fs;
+use std::path::PathBuf;
+
+pub const R_X86_64_64: u32 = 1;
+pub const R_X86_64_PC32: u32 = 2;
+pub const R_X86_64_PLT32: u32 = 4;
+pub const R_X86_64_32: u32 = 10;
+pub const R_X86_64_32S: u32 = 11;
+
+const ENCODING: Encoding = Encoding {
+    class: Class::Elf64,
+    endian: Endian::Little,
+};
+
+struct Section {
+    name: &'static str,
+    sh_type: u32,
+    sh_flags: u64,
+    data: Vec<u8>,
+    align: u64,
+}
+
+struct Symbol {
+    name: &'static str,
+    binding: SymbolBinding,
+    st_shndx: u16,
+    st_value: u64,
+    st_size: u64,
+    visibility: SymbolVisibility,
+}
+
+struct Relocation {
+    section: u16,
+    offset: u64,
+    symbol: &'static str,
+    relocation_type: u32,
+    addend: i64,
+}
+
+/// An x86-64 relocatable object under construction.
+///
+/// Sections are numbered from 1 in the order they are added. Relocations name their symbol,
+/// which must have been added with `define`, `absolute`, `common` or `reference`.
+pub struct TestObject {
+    sections: Vec<Section>,
+    symbols: Vec<Symbol>,
+    relocations: Vec<Relocation>,
+}
+
+impl TestObject {
+    pub fn new() -> TestObject {
+        TestObject {
+            sections: Vec::new(),
+            symbols: Vec::new(),
+            relocations: Vec::new(),
+        }
+    }
+
+    /// Adds an allocated section and returns its index.
+    pub fn section(&mut self, name: &'static str, flags: SectionFlags, data: &[u8]) -> u16 {
+        self.sections.push(Section {
+            name,
+            sh_type: u32::from(SectionType::Progbits),
+            sh_flags: flags.0 | SectionFlags::ALLOC.0,
+            data: data.to_vec(),
+            align: 16,
+        });
+        self.sections.len() as u16
+    }
+
+    pub fn text(&mut self, data: &[u8]) -> u16 {
+        self.section(".text", SectionFlags::EXECINSTR, data)
+    }
+
+    pub fn data(&mut self, data: &[u8]) -> u16 {
+        self.section(".data", SectionFlags::WRITE, data)
+    }
+
+    /// Defines `name` at `value` in `section`.
+    pub fn define(&mut self, name: &'static str, binding: SymbolBinding, section: u16, value: u64) {
+        self.symbol(name, binding, section, value, 0);
+    }
+
+    pub fn absolute(&mut self, name: &'static str, value: u64) {
+        self.symbol(name, SymbolBinding::Global, SHN_ABS, value, 0);
+    }
+
+    pub fn common(&mut self, name: &'static str, size: u64, align: u64) {
+        self.symbol(name, SymbolBinding::Global, SHN_COMMON, align, size);
+    }
+
+    /// References `name` without defining it.
+    pub fn reference(&mut self, name: &'static str, binding: SymbolBinding) {
+        self.symbol(name, binding, SHN_UNDEF, 0, 0);
+    }
+
+    fn symbol(
+        &mut self,
+        name: &'static str,
+        binding: SymbolBinding,
+        st_shndx: u16,
+        st_value: u64,
+        st_size: u64,
+    ) {
+        self.symbols.push(Symbol {
+            name,
+            binding,
+            st_shndx,
+            st_value,
+            st_size,
+            visibility: SymbolVisibility::Default,
+        });
+    }
+
+    /// Relocates `offset` of `section` against `symbol`.
+    pub fn relocate(
+        &mut self,
+        section: u16,
+        offset: u64,
+        symbol: &'static str,
+        relocation_type: u32,
+        addend: i64,
+    ) {
+        self.relocations.push(Relocation {
+            section,
+            offset,
+            symbol,
+            relocation_type,
+            addend,
+        });
+    }
+
+    /// Index of `name` in the `.symtab` written by `build`, after the null symbol.
+    fn symbol_index(&self, name: &str) -> u64 {
+        let (local, global): (Vec<_>, Vec<_>) = self
+            .symbols
+            .iter()
+            .partition(|symbol| symbol.binding == SymbolBinding::Local);
+        let position = local
+            .iter()
+            .chain(global.iter())
+            .position(|symbol| symbol.name == name)
+            .unwrap_or_else(|| panic!("no symbol `{}` to relocate against", name));
+        position as u64 + 1
+    }
+
+    pub fn build(&self) -> Vec<u8> {
+        let mut writer = ElfWriter::new(ENCODING, ElfType::Rel, Machine::X86_64);
+        for section in self.sections.iter() {
+            writer.add_section(
+                section.name,
+                header(section.sh_type, section.sh_flags, section.align),
+                section.data.clone(),
+            );
+        }
+        // The symbol table comes after the relocation sections, which link to it.
+        let targets = (1..=self.sections.len() as u16)
+            .filter(|index| {
+                self.relocations
+                    .iter()
+                    .any(|relocation| relocation.section == *index)
+            })
+            .collect::<Vec<_>>();
+        let symtab = (self.sections.len() + targets.len() + 1) as u32;
+        for target in targets {
+            let mut data = Vec::new();
+            for relocation in self.relocations.iter().filter(|r| r.section == target) {
+                let entry = ElfRelocationEntry {
+                    r_offset: relocation.offset,
+                    r_info: self.symbol_index(relocation.symbol) << 32
+                        | relocation.relocation_type as u64,
+                    r_addend: Some(relocation.addend),
+                };
+                data.extend_from_slice(&entry.to_bytes(ENCODING));
+            }
+            let mut rela = header(u32::from(SectionType::Rela), SectionFlags::INFO_LINK.0, 8);
+            rela.sh_link = symtab;
+            rela.sh_info = target as u32;
+            rela.sh_entsize = 24;
+            let name = match self.sections[target as usize - 1].name {
+                ".text" => ".rela.text",
+                ".data" => ".rela.data",
+                _ => ".rela",
+            };
+            writer.add_section(name, rela, data);
+        }
+        writer.add_section(
+            ".symtab",
+            header(u32::from(SectionType::Symtab), 0, 8),
+            Vec::new(),
+        );
+        writer.add_section(
+            ".strtab",
+            header(u32::from(SectionType::Strtab), 0, 1),
+            Vec::new(),
+        );
+        for symbol in self.symbols.iter() {
+            let symbol_type = match symbol.st_shndx {
+                SHN_UNDEF | SHN_ABS => SymbolType::NoType,
+                _ => SymbolType::Object,
+            };
+            writer.symbols.push(WriterSymbol {
+                name: symbol.name.into(),
+                entry: ElfSymbolEntry {
+                    st_name: 0,
+                    st_info: u8::from(symbol.binding) << 4 | u8::from(symbol_type),
+                    st_other: u8::from(symbol.visibility),
+                    st_shndx: symbol.st_shndx,
+                    st_value: symbol.st_value,
+                    st_size: symbol.st_size,
+                },
+            });
+        }
+        writer.to_bytes().expect("test object lays out")
+    }
+}
+
+fn header(sh_type: u32, sh_flags: u64, align: u64) -> ElfSectionHeader {
+    ElfSectionHeader {
+        sh_name: 0,
+        sh_type,
+        sh_flags,
+        sh_addr: 0,
+        sh_offset: 0,
+        sh_size: 0,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: align,
+        sh_entsize: 0,
+    }
+}
+
+/// A GNU `ar` archive of `members`, each with the names its index lists for it.
+pub fn archive(members: &[(&str, &[u8], &[&str])]) -> Vec<u8> {
+    let names = members
+        .iter()
+        .flat_map(|(_, _, names)| names.iter())
+        .collect::<Vec<_>>();
+    let mut index_size = 4 + 4 * names.len();
+    for name in names.iter() {
+        index_size += name.len() + 1;
+    }
+    // Members start after the magic and the index, each on an even offset.
+    let mut offsets = Vec::new();
+    let mut offset = 8 + 60 + index_size + index_size % 2;
+    for (_, data, _) in members.iter() {
+        offsets.push(offset as u32);
+        offset += 60 + data.len() + data.len() % 2;
+    }
+
+    let mut index = (names.len() as u32).to_be_bytes().to_vec();
+    for ((_, _, member_names), offset) in members.iter().zip(offsets.iter()) {
+        for _ in member_names.iter() {
+            index.extend_from_slice(&offset.to_be_bytes());
+        }
+    }
+    for name in names.iter() {
+        index.extend_from_slice(name.as_bytes());
+        index.push(0);
+    }
+
+    let mut binary = b"!<arch>\n".to_vec();
+    append_member(&mut binary, "/", &index);
+    for (name, data, _) in members.iter() {
+        append_member(&mut binary, &format!("{}/", name), data);
+    }
+    binary
+}
+
+fn append_member(binary: &mut Vec<u8>, name: &str, data: &[u8]) {
+    let header = format!(
+        "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+        name,
+        0,
+        0,
+        0,
+        644,
+        data.len()
+    );
+    binary.extend_from_slice(header.as_bytes());
+    binary.extend_from_slice(data);
+    if data.len() % 2 == 1 {
+        binary.push(b'\n');
+    }
+}
+
+/// A directory of its own for the test `name`, emptied first.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("chapter8-{}-{}", std::process::id(), name));
+    let _ = fs::remove_dir_all(&path);
+    fs::create_dir_all(&path).unwrap();
+    path
+}
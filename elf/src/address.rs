@@ -0,0 +1,181 @@
+use crate::error::ElfError;
+use crate::file::ElfFile;
+use crate::program::{ElfProgramHeader, ProgramType};
+use std::borrow::Cow;
+
+impl<'a> ElfFile<'a> {
+    /// File offset of the virtual address `vaddr`, found through the `PT_LOAD` segments.
+    ///
+    /// Addresses in the zero-filled tail of a segment (`.bss`, where `p_memsz > p_filesz`)
+    /// have no file offset and return `ElfError::AddressNotInFile`.
+    pub fn vaddr_to_offset(&self, vaddr: u64) -> Result<u64, ElfError> {
+        let header = self.find_load_segment(vaddr)?;
+        let delta = vaddr - header.p_vaddr;
+        if delta >= header.p_filesz {
+            return Err(ElfError::AddressNotInFile { address: vaddr });
+        }
+        header
+            .p_offset
+            .checked_add(delta)
+            .ok_or(ElfError::OffsetOutOfRange {
+                offset: header.p_offset,
+                size: delta,
+                file_size: self.data().len() as u64,
+            })
+    }
+
+    /// Virtual address the file offset `offset` is loaded at.
+    pub fn offset_to_vaddr(&self, offset: u64) -> Result<u64, ElfError> {
+        self.get_load_segments()?
+            .iter()
+            .find(|header| header.p_offset <= offset && offset - header.p_offset < header.p_filesz)
+            .and_then(|header| header.p_vaddr.checked_add(offset - header.p_offset))
+            .ok_or(ElfError::UnloadedOffset { offset })
+    }
+
+    /// `len` bytes of the memory image starting at `vaddr`.
+    ///
+    /// Borrowed from the file when the range is backed by file contents,
+    /// otherwise copied with the `.bss` part filled with zeros.
+    /// The range may span adjacent segments.
+    pub fn read_at_vaddr(&self, vaddr: u64, len: u64) -> Result<Cow<'a, [u8]>, ElfError> {
+        let end = vaddr
+            .checked_add(len)
+            .ok_or(ElfError::UnmappedAddress { address: vaddr })?;
+        // The whole range is checked before anything is copied, so the zeros of a segment
+        // never take more than `len` bytes, and an unmapped range allocates nothing.
+        let mut pieces = Vec::<(Option<&'a [u8]>, u64)>::new();
+        let mut address = vaddr;
+        while address < end {
+            let header = self.find_load_segment(address)?;
+            let delta = address - header.p_vaddr;
+            let size = (end - address).min(header.p_memsz - delta);
+            let data = if delta < header.p_filesz {
+                let in_file = size.min(header.p_filesz - delta);
+                let offset =
+                    header
+                        .p_offset
+                        .checked_add(delta)
+                        .ok_or(ElfError::OffsetOutOfRange {
+                            offset: header.p_offset,
+                            size: delta,
+                            file_size: self.data().len() as u64,
+                        })?;
+                Some(self.get_bytes(offset, in_file)?)
+            } else {
+                None
+            };
+            match data {
+                Some(data) if address == vaddr && data.len() as u64 == len => {
+                    return Ok(Cow::Borrowed(data));
+                }
+                _ => pieces.push((data, size)),
+            }
+            address += size;
+        }
+        let mut bytes = Vec::<u8>::new();
+        for (data, size) in pieces {
+            let data = data.unwrap_or(&[]);
+            bytes.extend_from_slice(data);
+            bytes.resize(bytes.len() + (size - data.len() as u64) as usize, 0);
+        }
+        Ok(Cow::Owned(bytes))
+    }
+
+    pub(crate) fn find_load_segment(&self, vaddr: u64) -> Result<ElfProgramHeader, ElfError> {
+        self.get_load_segments()?
+            .into_iter()
+            .find(|header| header.p_vaddr <= vaddr && vaddr - header.p_vaddr < header.p_memsz)
+            .ok_or(ElfError::UnmappedAddress { address: vaddr })
+    }
+
+    fn get_load_segments(&self) -> Result<Vec<ElfProgramHeader>, ElfError> {
+        Ok(self
+            .get_program_headers()?
+            .into_iter()
+            .filter(|header| ProgramType::from(header.p_type) == ProgramType::Load)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::ElfType;
+    use crate::testing;
+    use crate::writer::ElfWriter;
+
+    const BASE: u64 = 0x10_0000;
+
+    /// A file with `contents` at `BASE`, at the start of a segment of `memsz` bytes.
+    fn file_with_segment(contents: &[u8], memsz: u64) -> Vec<u8> {
+        testing::file_with_segment(ElfType::Exec, BASE, contents, memsz)
+    }
+
+    #[test]
+    fn reads_file_contents_and_zeros() {
+        let binary = file_with_segment(b"abcd", 0x10);
+        let file = ElfFile::new(&binary).unwrap();
+        assert!(matches!(
+            file.read_at_vaddr(BASE + 1, 2).unwrap(),
+            Cow::Borrowed(b"bc")
+        ));
+        assert_eq!(&file.read_at_vaddr(BASE + 2, 4).unwrap()[..], b"cd\0\0");
+        assert_eq!(&file.read_at_vaddr(BASE + 8, 8).unwrap()[..], &[0; 8]);
+        assert!(file.read_at_vaddr(BASE + 8, 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn checks_the_whole_range_before_copying() {
+        // Filling 1 TiB of zeros would abort the test.
+        let binary = file_with_segment(b"abcd", 1 << 40);
+        let file = ElfFile::new(&binary).unwrap();
+        assert_eq!(&file.read_at_vaddr(BASE + 2, 4).unwrap()[..], b"cd\0\0");
+        assert!(matches!(
+            file.read_at_vaddr(BASE, (1 << 40) + 1),
+            Err(ElfError::UnmappedAddress { .. })
+        ));
+        assert!(matches!(
+            file.read_at_vaddr(BASE, u64::MAX),
+            Err(ElfError::UnmappedAddress { .. })
+        ));
+    }
+
+    /// `binary` with its only segment changed by `edit`.
+    fn edit_segment(binary: &[u8], edit: impl Fn(&mut ElfProgramHeader)) -> Vec<u8> {
+        let file = ElfFile::new(binary).unwrap();
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        edit(&mut writer.segments[0]);
+        writer.to_bytes().unwrap()
+    }
+
+    #[test]
+    fn segments_that_wrap_around_are_errors() {
+        let binary = file_with_segment(b"abcd", 0x10);
+        let past_the_end = edit_segment(&binary, |segment| {
+            segment.p_offset = u64::MAX - 1;
+            segment.p_filesz = 0x10;
+        });
+        let file = ElfFile::new(&past_the_end).unwrap();
+        assert!(matches!(
+            file.vaddr_to_offset(BASE + 4),
+            Err(ElfError::OffsetOutOfRange { .. })
+        ));
+        assert!(matches!(
+            file.read_at_vaddr(BASE + 4, 2),
+            Err(ElfError::OffsetOutOfRange { .. })
+        ));
+
+        let top = edit_segment(&binary, |segment| {
+            segment.p_offset = 0;
+            segment.p_vaddr = u64::MAX - 1;
+            segment.p_filesz = 0x10;
+        });
+        let file = ElfFile::new(&top).unwrap();
+        assert_eq!(file.offset_to_vaddr(1).unwrap(), u64::MAX);
+        assert!(matches!(
+            file.offset_to_vaddr(2),
+            Err(ElfError::UnloadedOffset { offset: 2 })
+        ));
+    }
+}
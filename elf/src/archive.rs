@@ -0,0 +1,509 @@
+use crate::error::ElfError;
+use crate::file::{ElfFile, HEADER_MAGIC};
+use std::path::{Path, PathBuf};
+
+pub const ARCHIVE_MAGIC: &[u8; 8] = b"!<arch>\n";
+/// Magic of GNU thin archives, whose members stay in their own files.
+pub const THIN_ARCHIVE_MAGIC: &[u8; 8] = b"!<thin>\n";
+pub const ARCHIVE_MEMBER_HEADER_SIZE: usize = 60;
+
+const ARCHIVE_HEADER_END: &[u8; 2] = b"`\n";
+const BSD_NAME_PREFIX: &str = "#1/";
+
+/// `ar` archive borrowed from a byte slice, such as `libfoo.a`.
+///
+/// Understands GNU (`//` long names, `/` and `/SYM64/` symbol indexes, thin archives)
+/// and BSD (`#1/` names, `__.SYMDEF` symbol index) archives.
+pub struct ElfArchive<'a> {
+    data: &'a [u8],
+    thin: bool,
+    members: Vec<ArchiveMember<'a>>,
+    symbols: Vec<ArchiveSymbol<'a>>,
+}
+
+impl<'a> ElfArchive<'a> {
+    pub fn new(data: &'a [u8]) -> Result<ElfArchive<'a>, ElfError> {
+        let magic = data.get(0..ARCHIVE_MAGIC.len());
+        let thin = match magic {
+            Some(magic) if magic == ARCHIVE_MAGIC => false,
+            Some(magic) if magic == THIN_ARCHIVE_MAGIC => true,
+            _ => {
+                return Err(ElfError::BadMagic {
+                    offset: 0,
+                    magic: data.iter().take(ARCHIVE_MAGIC.len()).copied().collect(),
+                })
+            }
+        };
+        let mut archive = ElfArchive {
+            data,
+            thin,
+            members: Vec::new(),
+            symbols: Vec::new(),
+        };
+        archive.read_members()?;
+        Ok(archive)
+    }
+
+    pub fn is_thin(&self) -> bool {
+        self.thin
+    }
+
+    /// Regular members, without the symbol index and the long name table.
+    pub fn members(&self) -> &[ArchiveMember<'a>] {
+        &self.members
+    }
+
+    /// Entries of the symbol index, in index order.
+    pub fn symbols(&self) -> &[ArchiveSymbol<'a>] {
+        &self.symbols
+    }
+
+    /// Member whose header starts at `offset`, as referenced by the symbol index.
+    pub fn get_member_at(&self, offset: u64) -> Option<&ArchiveMember<'a>> {
+        self.members.iter().find(|member| member.offset == offset)
+    }
+
+    /// Member that defines `name` according to the symbol index.
+    pub fn find_symbol(&self, name: &str) -> Option<&ArchiveMember<'a>> {
+        self.symbols
+            .iter()
+            .find(|symbol| symbol.name == name)
+            .and_then(|symbol| self.get_member_at(symbol.member_offset))
+    }
+
+    fn read_members(&mut self) -> Result<(), ElfError> {
+        let mut long_names: &'a [u8] = &[];
+        let mut offset = ARCHIVE_MAGIC.len();
+        // Members are aligned to 2 bytes, so a lone padding byte may end the file.
+        while offset + 1 < self.data.len() {
+            let header = ArchiveMemberHeader::new(self.data, offset)?;
+            let data_start = offset + ARCHIVE_MEMBER_HEADER_SIZE;
+            let raw_name = header.name.trim_end_matches(' ');
+            let special = raw_name == "/" || raw_name == "//" || raw_name == "/SYM64/";
+            // Thin archives only store their own tables, members stay in separate files.
+            let stored = !self.thin || special;
+            let data = if stored {
+                Some(self.get(data_start, header.size)?)
+            } else {
+                None
+            };
+
+            match (raw_name, data) {
+                ("/", Some(data)) => self.read_gnu_symbols(data, data_start, 4)?,
+                ("/SYM64/", Some(data)) => self.read_gnu_symbols(data, data_start, 8)?,
+                ("//", Some(data)) => long_names = data,
+                _ => {
+                    let (name, data) = self.resolve_name(raw_name, data, long_names, offset)?;
+                    if name == "__.SYMDEF" || name == "__.SYMDEF SORTED" {
+                        if let Some(data) = data {
+                            self.read_bsd_symbols(data, data_start)?;
+                        }
+                    } else {
+                        self.members.push(ArchiveMember {
+                            name,
+                            offset: offset as u64,
+                            date: header.date,
+                            uid: header.uid,
+                            gid: header.gid,
+                            mode: header.mode,
+                            size: data.map_or(header.size as u64, |data| data.len() as u64),
+                            data,
+                        });
+                    }
+                }
+            }
+            let size = if stored { header.size } else { 0 };
+            offset = data_start + size + (size & 1);
+        }
+        Ok(())
+    }
+
+    /// Name of a regular member, and its data without a BSD name in front.
+    fn resolve_name(
+        &self,
+        raw_name: &'a str,
+        data: Option<&'a [u8]>,
+        long_names: &'a [u8],
+        offset: usize,
+    ) -> Result<(&'a str, Option<&'a [u8]>), ElfError> {
+        let bad_header = || ElfError::BadArchiveHeader {
+            offset: offset as u64,
+        };
+        if let Some(length) = raw_name.strip_prefix(BSD_NAME_PREFIX) {
+            let length = length.parse::<usize>().map_err(|_| bad_header())?;
+            let data = data.ok_or_else(bad_header)?;
+            let name = data.get(..length).ok_or_else(bad_header)?;
+            let name_length = name.iter().position(|b| *b == 0).unwrap_or(length);
+            return Ok((to_str(&name[..name_length], offset)?, Some(&data[length..])));
+        }
+        if let Some(index) = raw_name.strip_prefix('/') {
+            let index = index.parse::<usize>().map_err(|_| bad_header())?;
+            let rest = long_names.get(index..).ok_or(ElfError::BadStringIndex {
+                offset: offset as u64,
+                index: index as u64,
+            })?;
+            let length = rest.iter().position(|b| *b == b'\n').unwrap_or(rest.len());
+            let name = &rest[..length];
+            let name = name.strip_suffix(b"/").unwrap_or(name);
+            return Ok((to_str(name, offset)?, data));
+        }
+        Ok((raw_name.strip_suffix('/').unwrap_or(raw_name), data))
+    }
+
+    /// GNU symbol index: a big endian count, member offsets, then NUL terminated names.
+    fn read_gnu_symbols(
+        &mut self,
+        data: &'a [u8],
+        offset: usize,
+        word_size: usize,
+    ) -> Result<(), ElfError> {
+        let file_size = self.data.len() as u64;
+        let read_word = |start: usize| -> Result<u64, ElfError> {
+            let bytes = data
+                .get(start..start + word_size)
+                .ok_or(ElfError::OffsetOutOfRange {
+                    offset: (offset + start) as u64,
+                    size: word_size as u64,
+                    file_size,
+                })?;
+            Ok(bytes.iter().fold(0u64, |value, b| value << 8 | *b as u64))
+        };
+        let count = read_word(0)? as usize;
+        let names_start = word_size.saturating_mul(count.saturating_add(1));
+        let mut names = data.get(names_start..).unwrap_or(&[]).split(|b| *b == 0);
+        for i in 0..count {
+            let member_offset = read_word(word_size * (i + 1))?;
+            let name = names.next().ok_or(ElfError::BadStringIndex {
+                offset: (offset + names_start) as u64,
+                index: i as u64,
+            })?;
+            self.symbols.push(ArchiveSymbol {
+                name: to_str(name, offset)?,
+                member_offset,
+            });
+        }
+        Ok(())
+    }
+
+    /// BSD `__.SYMDEF`: a little endian array of `ranlib` (name index, member offset)
+    /// pairs followed by a string table, each prefixed with its size in bytes.
+    fn read_bsd_symbols(&mut self, data: &'a [u8], offset: usize) -> Result<(), ElfError> {
+        let file_size = self.data.len() as u64;
+        let read_u32 = |start: usize| -> Result<u32, ElfError> {
+            let bytes = data
+                .get(start..start + 4)
+                .ok_or(ElfError::OffsetOutOfRange {
+                    offset: (offset + start) as u64,
+                    size: 4,
+                    file_size,
+                })?;
+            Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        };
+        let ranlib_size = read_u32(0)? as usize;
+        let strings_start = 4 + ranlib_size + 4;
+        let strings_size = read_u32(4 + ranlib_size)? as usize;
+        let strings = data
+            .get(strings_start..strings_start + strings_size)
+            .ok_or(ElfError::OffsetOutOfRange {
+                offset: (offset + strings_start) as u64,
+                size: strings_size as u64,
+                file_size: self.data.len() as u64,
+            })?;
+        for i in 0..ranlib_size / 8 {
+            let index = read_u32(4 + i * 8)? as usize;
+            let member_offset = read_u32(4 + i * 8 + 4)? as u64;
+            let rest = strings.get(index..).ok_or(ElfError::BadStringIndex {
+                offset: (offset + strings_start) as u64,
+                index: index as u64,
+            })?;
+            let length = rest.iter().position(|b| *b == 0).unwrap_or(rest.len());
+            self.symbols.push(ArchiveSymbol {
+                name: to_str(&rest[..length], offset)?,
+                member_offset,
+            });
+        }
+        Ok(())
+    }
+
+    fn get(&self, offset: usize, size: usize) -> Result<&'a [u8], ElfError> {
+        self.data
+            .get(offset..offset.saturating_add(size))
+            .ok_or(ElfError::OffsetOutOfRange {
+                offset: offset as u64,
+                size: size as u64,
+                file_size: self.data.len() as u64,
+            })
+    }
+}
+
+fn to_str(bytes: &[u8], offset: usize) -> Result<&str, ElfError> {
+    std::str::from_utf8(bytes).map_err(|_| ElfError::InvalidUtf8 {
+        offset: offset as u64,
+    })
+}
+
+/// The fixed 60 byte header in front of each member. Numbers are ASCII decimal, `mode` octal.
+struct ArchiveMemberHeader<'a> {
+    name: &'a str,
+    date: u64,
+    uid: u32,
+    gid: u32,
+    mode: u32,
+    size: usize,
+}
+
+impl<'a> ArchiveMemberHeader<'a> {
+    fn new(data: &'a [u8], offset: usize) -> Result<ArchiveMemberHeader<'a>, ElfError> {
+        let binary = data
+            .get(offset..offset + ARCHIVE_MEMBER_HEADER_SIZE)
+            .ok_or(ElfError::TruncatedHeader {
+                offset: offset as u64,
+                size: ARCHIVE_MEMBER_HEADER_SIZE as u64,
+                file_size: data.len() as u64,
+            })?;
+        let bad_header = ElfError::BadArchiveHeader {
+            offset: offset as u64,
+        };
+        if &binary[58..60] != ARCHIVE_HEADER_END {
+            return Err(bad_header);
+        }
+        let field = |range: std::ops::Range<usize>| -> Result<&'a str, ElfError> {
+            std::str::from_utf8(&binary[range]).map_err(|_| ElfError::InvalidUtf8 {
+                offset: offset as u64,
+            })
+        };
+        let number = |range: std::ops::Range<usize>, radix: u32| -> Result<u64, ElfError> {
+            let text = field(range)?.trim_end_matches(' ');
+            if text.is_empty() {
+                return Ok(0);
+            }
+            u64::from_str_radix(text, radix).map_err(|_| ElfError::BadArchiveHeader {
+                offset: offset as u64,
+            })
+        };
+        Ok(ArchiveMemberHeader {
+            name: field(0..16)?,
+            date: number(16..28, 10)?,
+            uid: number(28..34, 10)? as u32,
+            gid: number(34..40, 10)? as u32,
+            mode: number(40..48, 8)? as u32,
+            size: number(48..58, 10)? as usize,
+        })
+    }
+}
+
+/// One member of an archive.
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveMember<'a> {
+    pub name: &'a str,
+    /// File offset of the member header in the archive.
+    pub offset: u64,
+    pub date: u64,
+    pub uid: u32,
+    pub gid: u32,
+    pub mode: u32,
+    pub size: u64,
+    data: Option<&'a [u8]>,
+}
+
+impl<'a> ArchiveMember<'a> {
+    /// Contents of the member, `None` for members of thin archives.
+    pub fn data(&self) -> Option<&'a [u8]> {
+        self.data
+    }
+
+    pub fn is_elf(&self) -> bool {
+        self.data.and_then(|data| data.get(0..4)) == Some(&HEADER_MAGIC[..])
+    }
+
+    /// The member parsed in place as an elf file.
+    pub fn elf_file(&self) -> Result<ElfFile<'a>, ElfError> {
+        let data = self.data.ok_or(ElfError::ThinArchiveMember {
+            offset: self.offset,
+        })?;
+        ElfFile::new(data)
+    }
+
+    /// Where a thin archive member lives: `name` relative to the directory of the archive.
+    pub fn thin_path(&self, archive_path: &Path) -> PathBuf {
+        match archive_path.parent() {
+            Some(directory) => directory.join(self.name),
+            None => PathBuf::from(self.name),
+        }
+    }
+}
+
+/// One entry of the archive symbol index.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveSymbol<'a> {
+    pub name: &'a str,
+    /// File offset of the header of the member defining the symbol.
+    pub member_offset: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LONG_NAME: &str = "a_very_long_member_name.o";
+
+    struct ArchiveBuilder {
+        data: Vec<u8>,
+    }
+
+    impl ArchiveBuilder {
+        fn new(magic: &[u8; 8]) -> ArchiveBuilder {
+            ArchiveBuilder {
+                data: magic.to_vec(),
+            }
+        }
+
+        /// Appends a member header and returns its offset.
+        fn header(&mut self, name: &str, size: usize) -> u32 {
+            let offset = self.data.len() as u32;
+            let header = format!(
+                "{:<16}{:<12}{:<6}{:<6}{:<8}{:<10}`\n",
+                name, 0, 1000, 1000, 644, size
+            );
+            assert_eq!(header.len(), ARCHIVE_MEMBER_HEADER_SIZE);
+            self.data.extend_from_slice(header.as_bytes());
+            offset
+        }
+
+        fn member(&mut self, name: &str, data: &[u8]) -> u32 {
+            let offset = self.header(name, data.len());
+            self.data.extend_from_slice(data);
+            if data.len() % 2 == 1 {
+                self.data.push(b'\n');
+            }
+            offset
+        }
+    }
+
+    /// A GNU archive whose symbol index puts `foo` in `short.o` and `bar` in `LONG_NAME`.
+    fn gnu_archive() -> Vec<u8> {
+        // The index has the same size whatever it holds, so a first pass finds the offsets.
+        let build = |offsets: [u32; 2]| {
+            let mut archive = ArchiveBuilder::new(ARCHIVE_MAGIC);
+            let mut index = 2u32.to_be_bytes().to_vec();
+            for offset in offsets.iter() {
+                index.extend_from_slice(&offset.to_be_bytes());
+            }
+            index.extend_from_slice(b"foo\0bar\0");
+            archive.member("/", &index);
+            archive.member("//", format!("{}/\n", LONG_NAME).as_bytes());
+            let short = archive.member("short.o/", b"short");
+            let long = archive.member("/0", b"long data");
+            (archive.data, [short, long])
+        };
+        let (_, offsets) = build([0, 0]);
+        build(offsets).0
+    }
+
+    #[test]
+    fn reads_gnu_archives() {
+        let data = gnu_archive();
+        let archive = ElfArchive::new(&data).unwrap();
+        assert!(!archive.is_thin());
+        let members = archive.members();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].name, "short.o");
+        assert_eq!(members[0].data(), Some(&b"short"[..]));
+        assert_eq!((members[0].uid, members[0].mode), (1000, 0o644));
+        assert_eq!(members[1].name, LONG_NAME);
+        assert_eq!(members[1].data(), Some(&b"long data"[..]));
+        assert!(!members[1].is_elf());
+
+        let names = archive
+            .symbols()
+            .iter()
+            .map(|symbol| symbol.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["foo", "bar"]);
+        assert_eq!(archive.find_symbol("foo").unwrap().name, "short.o");
+        assert_eq!(archive.find_symbol("bar").unwrap().name, LONG_NAME);
+        assert!(archive.find_symbol("baz").is_none());
+    }
+
+    #[test]
+    fn reads_bsd_archives() {
+        let mut archive = ArchiveBuilder::new(ARCHIVE_MAGIC);
+        // The member follows the magic, the index header and the 40 bytes of the index.
+        let mut index = b"__.SYMDEF SORTED\0\0\0\0".to_vec();
+        for value in [8, 0, 108, 4].iter() {
+            index.extend_from_slice(&u32::to_le_bytes(*value));
+        }
+        index.extend_from_slice(b"baz\0");
+        archive.member("#1/20", &index);
+        let mut member = b"long_bsd_member_name.o\0\0".to_vec();
+        member.extend_from_slice(b"contents");
+        let offset = archive.member("#1/24", &member);
+        assert_eq!(offset, 108);
+
+        let archive = ElfArchive::new(&archive.data).unwrap();
+        let members = archive.members();
+        assert_eq!(members.len(), 1);
+        assert_eq!(members[0].name, "long_bsd_member_name.o");
+        assert_eq!(members[0].data(), Some(&b"contents"[..]));
+        assert_eq!(members[0].size, 8);
+        assert_eq!(
+            archive.symbols(),
+            [ArchiveSymbol {
+                name: "baz",
+                member_offset: 108,
+            }]
+        );
+        assert_eq!(archive.find_symbol("baz").unwrap().name, members[0].name);
+    }
+
+    #[test]
+    fn thin_archives_point_to_their_members() {
+        let mut archive = ArchiveBuilder::new(THIN_ARCHIVE_MAGIC);
+        archive.member("//", format!("{}/\n", LONG_NAME).as_bytes());
+        archive.header("/0", 1234);
+        archive.header("short.o/", 10);
+        let archive = ElfArchive::new(&archive.data).unwrap();
+        assert!(archive.is_thin());
+        let members = archive.members();
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[0].name, LONG_NAME);
+        assert_eq!(members[0].size, 1234);
+        assert_eq!(members[0].data(), None);
+        assert!(matches!(
+            members[0].elf_file(),
+            Err(ElfError::ThinArchiveMember { .. })
+        ));
+        assert_eq!(
+            members[1].thin_path(Path::new("lib/libthin.a")),
+            Path::new("lib/short.o")
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_archives() {
+        assert!(matches!(
+            ElfArchive::new(b"!<arch>"),
+            Err(ElfError::BadMagic { .. })
+        ));
+        let mut archive = ArchiveBuilder::new(ARCHIVE_MAGIC);
+        archive.member("short.o/", b"data");
+        let mut data = archive.data.clone();
+        data[8 + 58] = b'x';
+        assert!(matches!(
+            ElfArchive::new(&data),
+            Err(ElfError::BadArchiveHeader { offset: 8 })
+        ));
+        let mut data = archive.data.clone();
+        data[8 + 48] = b'z';
+        assert!(matches!(
+            ElfArchive::new(&data),
+            Err(ElfError::BadArchiveHeader { offset: 8 })
+        ));
+        // A long name past the end of the `//` table.
+        archive.member("/99", b"data");
+        assert!(matches!(
+            ElfArchive::new(&archive.data),
+            Err(ElfError::BadStringIndex { index: 99, .. })
+        ));
+    }
+}
@@ -0,0 +1,752 @@
+use crate::error::ElfError;
+use crate::file::ElfFile;
+use crate::header::{Class, ElfType, Machine};
+use crate::note::{ElfNote, NoteContent};
+use crate::reader::Reader;
+use std::borrow::Cow;
+use std::fmt;
+
+pub const NT_PRSTATUS: u32 = 1;
+pub const NT_PRPSINFO: u32 = 3;
+pub const NT_AUXV: u32 = 6;
+pub const NT_SIGINFO: u32 = 0x5349_4749;
+pub const NT_FILE: u32 = 0x4649_4c45;
+
+/// Bytes `ElfCore::read_string` reads at a time.
+const STRING_CHUNK: u64 = 256;
+
+/// Process state saved in a core file (`ET_CORE`).
+///
+/// Threads come from `NT_PRSTATUS`, each followed by the `NT_SIGINFO` of the same thread.
+/// Memory is read through the `PT_LOAD` segments of the core.
+pub struct ElfCore<'f, 'a> {
+    file: &'f ElfFile<'a>,
+    pub threads: Vec<CoreThread>,
+    pub process: Option<PrPsInfo<'a>>,
+    pub auxv: Vec<AuxvEntry>,
+    pub mapped_files: Vec<MappedFile<'a>>,
+}
+
+impl<'f, 'a> ElfCore<'f, 'a> {
+    /// `len` bytes of the process memory at `vaddr`.
+    ///
+    /// Pages the kernel did not dump (`p_filesz` smaller than `p_memsz`) read as zeros.
+    pub fn read_memory(&self, vaddr: u64, len: u64) -> Result<Cow<'a, [u8]>, ElfError> {
+        self.file.read_at_vaddr(vaddr, len)
+    }
+
+    /// NUL terminated string at `vaddr`, such as the path in `AT_EXECFN`.
+    /// Stops at the end of the segment when no NUL is found.
+    pub fn read_string(&self, vaddr: u64) -> Result<Cow<'a, str>, ElfError> {
+        let header = self.file.find_load_segment(vaddr)?;
+        let segment_end = header.p_vaddr.saturating_add(header.p_memsz);
+        // Read a chunk at a time, so a huge segment is not copied to find a short string.
+        let mut bytes = Vec::<u8>::new();
+        let mut address = vaddr;
+        while address < segment_end {
+            let chunk = self.read_memory(address, (segment_end - address).min(STRING_CHUNK))?;
+            let length = chunk.iter().position(|b| *b == 0);
+            if let (Cow::Borrowed(chunk), Some(_), true) = (&chunk, length, bytes.is_empty()) {
+                return Ok(c_string(chunk));
+            }
+            bytes.extend_from_slice(&chunk[..length.unwrap_or(chunk.len())]);
+            if length.is_some() {
+                break;
+            }
+            address += chunk.len() as u64;
+        }
+        Ok(Cow::Owned(c_string(&bytes).into_owned()))
+    }
+
+    pub fn get_auxv_value(&self, auxv_type: AuxvType) -> Option<u64> {
+        self.auxv
+            .iter()
+            .find(|entry| entry.auxv_type() == auxv_type)
+            .map(|entry| entry.a_val)
+    }
+
+    pub fn machine(&self) -> Machine {
+        self.file.get_elf_header().machine()
+    }
+}
+
+impl<'a> ElfFile<'a> {
+    /// Threads, process information, auxiliary vector and mapped files of a core file.
+    pub fn get_core(&self) -> Result<ElfCore<'_, 'a>, ElfError> {
+        let elf_type = self.get_elf_header().e_type;
+        if ElfType::from(elf_type) != ElfType::Core {
+            return Err(ElfError::NotCoreFile { elf_type });
+        }
+        let mut core = ElfCore {
+            file: self,
+            threads: Vec::new(),
+            process: None,
+            auxv: Vec::new(),
+            mapped_files: Vec::new(),
+        };
+        for note in self.get_notes()?.iter() {
+            match note.content()? {
+                Some(NoteContent::CorePrStatus(status)) => core.threads.push(CoreThread {
+                    status,
+                    signal: None,
+                }),
+                Some(NoteContent::CoreSigInfo(signal)) => {
+                    if let Some(thread) = core.threads.last_mut() {
+                        thread.signal = Some(signal);
+                    }
+                }
+                Some(NoteContent::CorePrPsInfo(process)) => core.process = Some(process),
+                Some(NoteContent::CoreAuxv(auxv)) => core.auxv = auxv,
+                Some(NoteContent::CoreFile(mapped_files)) => core.mapped_files = mapped_files,
+                _ => {}
+            }
+        }
+        Ok(core)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoreThread {
+    pub status: PrStatus,
+    pub signal: Option<SigInfo>,
+}
+
+/// `NT_PRSTATUS`: `struct elf_prstatus` of one thread.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrStatus {
+    /// Signal that stopped the thread.
+    pub cursig: u16,
+    pub sigpend: u64,
+    pub sighold: u64,
+    pub pid: u32,
+    pub ppid: u32,
+    pub pgrp: u32,
+    pub sid: u32,
+    /// General purpose registers in the order of `register_names`.
+    pub registers: Vec<u64>,
+}
+
+impl PrStatus {
+    pub(crate) fn new(note: &ElfNote) -> Result<PrStatus, ElfError> {
+        let word_size = note.encoding.word_size();
+        // Signal information, the padded pr_cursig, two sigsets, four pids and four timevals.
+        let registers_start = 32 + word_size * 10;
+        let registers_end = note.desc.len().saturating_sub(word_size);
+        let header = note.get_desc(0, registers_start.max(registers_end))?;
+        let mut reader = Reader::new(&header[12..], note.encoding);
+        let cursig = reader.read_u16();
+        reader.read_u16();
+        let sigpend = reader.read_word();
+        let sighold = reader.read_word();
+        let pid = reader.read_u32();
+        let ppid = reader.read_u32();
+        let pgrp = reader.read_u32();
+        let sid = reader.read_u32();
+
+        let mut reader = Reader::new(&header[registers_start..], note.encoding);
+        let registers = (0..registers_end.saturating_sub(registers_start) / word_size)
+            .map(|_| reader.read_word())
+            .collect();
+        Ok(PrStatus {
+            cursig,
+            sigpend,
+            sighold,
+            pid,
+            ppid,
+            pgrp,
+            sid,
+            registers,
+        })
+    }
+
+    pub fn display(&self, machine: Machine) -> PrStatusDisplay<'_> {
+        PrStatusDisplay {
+            status: self,
+            machine,
+        }
+    }
+}
+
+impl fmt::Display for PrStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PID {}, Signal {}, {} registers",
+            self.pid,
+            signal_name(self.cursig as u32),
+            self.registers.len()
+        )
+    }
+}
+
+/// Prints a `PrStatus` with register names of the machine.
+pub struct PrStatusDisplay<'a> {
+    status: &'a PrStatus,
+    machine: Machine,
+}
+
+impl<'a> fmt::Display for PrStatusDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = self.status;
+        write!(
+            f,
+            "---PrStatus---
+PID       = {}
+PPID      = {}
+PGRP      = {}
+SID       = {}
+Signal    = {}
+Pending   = {:#x}
+Held      = {:#x}",
+            status.pid,
+            status.ppid,
+            status.pgrp,
+            status.sid,
+            signal_name(status.cursig as u32),
+            status.sigpend,
+            status.sighold,
+        )?;
+        let names = register_names(self.machine);
+        for (i, value) in status.registers.iter().enumerate() {
+            match names.get(i) {
+                Some(name) => write!(f, "\n{:<9} = {:#018x}", name, value)?,
+                None => write!(f, "\nreg{:<6} = {:#018x}", i, value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Names of the registers in `pr_reg`, empty for machines this crate does not know.
+pub fn register_names(machine: Machine) -> &'static [&'static str] {
+    match machine {
+        Machine::X86_64 => &[
+            "r15", "r14", "r13", "r12", "rbp", "rbx", "r11", "r10", "r9", "r8", "rax", "rcx",
+            "rdx", "rsi", "rdi", "orig_rax", "rip", "cs", "eflags", "rsp", "ss", "fs_base",
+            "gs_base", "ds", "es", "fs", "gs",
+        ],
+        Machine::I386 => &[
+            "ebx", "ecx", "edx", "esi", "edi", "ebp", "eax", "ds", "es", "fs", "gs", "orig_eax",
+            "eip", "cs", "eflags", "esp", "ss",
+        ],
+        Machine::Aarch64 => &[
+            "x0", "x1", "x2", "x3", "x4", "x5", "x6", "x7", "x8", "x9", "x10", "x11", "x12", "x13",
+            "x14", "x15", "x16", "x17", "x18", "x19", "x20", "x21", "x22", "x23", "x24", "x25",
+            "x26", "x27", "x28", "x29", "x30", "sp", "pc", "pstate",
+        ],
+        Machine::Riscv => &[
+            "pc", "ra", "sp", "gp", "tp", "t0", "t1", "t2", "s0", "s1", "a0", "a1", "a2", "a3",
+            "a4", "a5", "a6", "a7", "s2", "s3", "s4", "s5", "s6", "s7", "s8", "s9", "s10", "s11",
+            "t3", "t4", "t5", "t6",
+        ],
+        _ => &[],
+    }
+}
+
+/// `NT_PRPSINFO`: `struct elf_prpsinfo` of the process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrPsInfo<'a> {
+    pub state: u8,
+    /// State as a character of `ps`, such as `R` or `S`.
+    pub sname: char,
+    pub zombie: bool,
+    pub nice: i8,
+    pub flag: u64,
+    pub uid: u32,
+    pub gid: u32,
+    pub pid: u32,
+    pub ppid: u32,
+    pub pgrp: u32,
+    pub sid: u32,
+    /// Executable name, truncated to 15 bytes.
+    pub fname: Cow<'a, str>,
+    /// Command line, truncated to 79 bytes.
+    pub psargs: Cow<'a, str>,
+}
+
+impl<'a> PrPsInfo<'a> {
+    pub(crate) fn new(note: &ElfNote<'a>) -> Result<PrPsInfo<'a>, ElfError> {
+        let word_size = note.encoding.word_size();
+        // uid and gid are 16 bits in 32 bit cores.
+        let (names_start, uid_size) = match note.encoding.class {
+            Class::Elf32 => (28, 2),
+            Class::Elf64 => (40, 4),
+        };
+        let desc = note.get_desc(0, names_start + 16 + 80)?;
+        let mut reader = Reader::new(desc, note.encoding);
+        let state = reader.read_u8();
+        let sname = reader.read_u8() as char;
+        let zombie = reader.read_u8() != 0;
+        let nice = reader.read_u8() as i8;
+        let mut reader = Reader::new(&desc[word_size..], note.encoding);
+        let flag = reader.read_word();
+        let (uid, gid) = match uid_size {
+            2 => (reader.read_u16() as u32, reader.read_u16() as u32),
+            _ => (reader.read_u32(), reader.read_u32()),
+        };
+        Ok(PrPsInfo {
+            state,
+            sname,
+            zombie,
+            nice,
+            flag,
+            uid,
+            gid,
+            pid: reader.read_u32(),
+            ppid: reader.read_u32(),
+            pgrp: reader.read_u32(),
+            sid: reader.read_u32(),
+            fname: c_string(&desc[names_start..names_start + 16]),
+            psargs: c_string(&desc[names_start + 16..names_start + 16 + 80]),
+        })
+    }
+}
+
+impl<'a> fmt::Display for PrPsInfo<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "---PrPsInfo---
+Name      = {}
+Args      = {}
+State     = {} ({})
+PID       = {}
+PPID      = {}
+UID       = {}
+GID       = {}",
+            self.fname,
+            self.psargs,
+            self.sname,
+            self.state,
+            self.pid,
+            self.ppid,
+            self.uid,
+            self.gid,
+        )
+    }
+}
+
+/// `NT_SIGINFO`: the `siginfo_t` of the signal that stopped a thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SigInfo {
+    pub signo: u32,
+    pub errno: u32,
+    pub code: i32,
+    /// Faulting address, for signals raised by the kernel on a fault.
+    pub address: Option<u64>,
+}
+
+impl SigInfo {
+    pub(crate) fn new(note: &ElfNote) -> Result<SigInfo, ElfError> {
+        let word_size = note.encoding.word_size();
+        // The union after the three ints is aligned to a word.
+        let address_start = 12usize.div_ceil(word_size) * word_size;
+        let desc = note.get_desc(0, address_start + word_size)?;
+        let mut reader = Reader::new(desc, note.encoding);
+        let signo = reader.read_u32();
+        let errno = reader.read_u32();
+        let code = reader.read_u32() as i32;
+        let address = match signo {
+            SIGILL | SIGTRAP | SIGBUS | SIGFPE | SIGSEGV if code > 0 => {
+                Some(Reader::new(&desc[address_start..], note.encoding).read_word())
+            }
+            _ => None,
+        };
+        Ok(SigInfo {
+            signo,
+            errno,
+            code,
+            address,
+        })
+    }
+}
+
+impl fmt::Display for SigInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (code {})", signal_name(self.signo), self.code)?;
+        if let Some(address) = self.address {
+            write!(f, " at {:#x}", address)?;
+        }
+        Ok(())
+    }
+}
+
+const SIGILL: u32 = 4;
+const SIGTRAP: u32 = 5;
+const SIGBUS: u32 = 7;
+const SIGFPE: u32 = 8;
+const SIGSEGV: u32 = 11;
+
+/// Linux name of a signal number.
+pub fn signal_name(signo: u32) -> Cow<'static, str> {
+    let name = match signo {
+        0 => "0",
+        1 => "SIGHUP",
+        2 => "SIGINT",
+        3 => "SIGQUIT",
+        SIGILL => "SIGILL",
+        SIGTRAP => "SIGTRAP",
+        6 => "SIGABRT",
+        SIGBUS => "SIGBUS",
+        SIGFPE => "SIGFPE",
+        9 => "SIGKILL",
+        10 => "SIGUSR1",
+        SIGSEGV => "SIGSEGV",
+        12 => "SIGUSR2",
+        13 => "SIGPIPE",
+        14 => "SIGALRM",
+        15 => "SIGTERM",
+        16 => "SIGSTKFLT",
+        17 => "SIGCHLD",
+        18 => "SIGCONT",
+        19 => "SIGSTOP",
+        20 => "SIGTSTP",
+        21 => "SIGTTIN",
+        22 => "SIGTTOU",
+        23 => "SIGURG",
+        24 => "SIGXCPU",
+        25 => "SIGXFSZ",
+        26 => "SIGVTALRM",
+        27 => "SIGPROF",
+        28 => "SIGWINCH",
+        29 => "SIGIO",
+        30 => "SIGPWR",
+        31 => "SIGSYS",
+        _ => return Cow::Owned(format!("signal {}", signo)),
+    };
+    Cow::Borrowed(name)
+}
+
+/// One `NT_FILE` entry: a file mapped into the process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MappedFile<'a> {
+    pub start: u64,
+    pub end: u64,
+    /// Offset in the file in bytes.
+    pub file_offset: u64,
+    pub path: Cow<'a, str>,
+}
+
+impl<'a> MappedFile<'a> {
+    pub(crate) fn new_list(note: &ElfNote<'a>) -> Result<Vec<MappedFile<'a>>, ElfError> {
+        let word_size = note.encoding.word_size();
+        let mut reader = Reader::new(note.get_desc(0, word_size * 2)?, note.encoding);
+        let count = reader.read_word() as usize;
+        let page_size = reader.read_word();
+        // Three words per mapping, so a count the descriptor cannot hold fails here.
+        let ranges = note.get_desc(word_size * 2, count.saturating_mul(word_size * 3))?;
+        let mut reader = Reader::new(ranges, note.encoding);
+        let mut paths = note.desc[word_size * 2 + ranges.len()..].split(|b| *b == 0);
+        let mut mapped_files = Vec::<MappedFile<'a>>::new();
+        for _ in 0..count {
+            mapped_files.push(MappedFile {
+                start: reader.read_word(),
+                end: reader.read_word(),
+                file_offset: reader.read_word().wrapping_mul(page_size),
+                path: String::from_utf8_lossy(paths.next().unwrap_or(&[])),
+            });
+        }
+        Ok(mapped_files)
+    }
+}
+
+impl<'a> fmt::Display for MappedFile<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:#018x} {:#018x} {:#010x} {}",
+            self.start, self.end, self.file_offset, self.path
+        )
+    }
+}
+
+/// One `NT_AUXV` entry, the auxiliary vector the kernel passed to the process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuxvEntry {
+    pub a_type: u64,
+    pub a_val: u64,
+}
+
+impl AuxvEntry {
+    /// Entries up to, and without, `AT_NULL`.
+    pub(crate) fn new_list(note: &ElfNote) -> Vec<AuxvEntry> {
+        let entry_size = note.encoding.word_size() * 2;
+        note.desc
+            .chunks_exact(entry_size)
+            .map(|chunk| {
+                let mut reader = Reader::new(chunk, note.encoding);
+                AuxvEntry {
+                    a_type: reader.read_word(),
+                    a_val: reader.read_word(),
+                }
+            })
+            .take_while(|entry| entry.auxv_type() != AuxvType::Null)
+            .collect()
+    }
+
+    pub fn auxv_type(&self) -> AuxvType {
+        AuxvType::from(self.a_type)
+    }
+}
+
+impl fmt::Display for AuxvEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:<20} {:#x}", self.auxv_type().to_string(), self.a_val)
+    }
+}
+
+/// Type in `a_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuxvType {
+    Null,
+    Ignore,
+    Execfd,
+    Phdr,
+    Phent,
+    Phnum,
+    Pagesz,
+    Base,
+    Flags,
+    Entry,
+    Notelf,
+    Uid,
+    Euid,
+    Gid,
+    Egid,
+    Platform,
+    Hwcap,
+    Clktck,
+    Secure,
+    BasePlatform,
+    Random,
+    Hwcap2,
+    RseqFeatureSize,
+    RseqAlign,
+    Execfn,
+    Sysinfo,
+    SysinfoEhdr,
+    MinsigStkSz,
+    Unknown(u64),
+}
+
+impl From<u64> for AuxvType {
+    fn from(value: u64) -> AuxvType {
+        match value {
+            0 => AuxvType::Null,
+            1 => AuxvType::Ignore,
+            2 => AuxvType::Execfd,
+            3 => AuxvType::Phdr,
+            4 => AuxvType::Phent,
+            5 => AuxvType::Phnum,
+            6 => AuxvType::Pagesz,
+            7 => AuxvType::Base,
+            8 => AuxvType::Flags,
+            9 => AuxvType::Entry,
+            10 => AuxvType::Notelf,
+            11 => AuxvType::Uid,
+            12 => AuxvType::Euid,
+            13 => AuxvType::Gid,
+            14 => AuxvType::Egid,
+            15 => AuxvType::Platform,
+            16 => AuxvType::Hwcap,
+            17 => AuxvType::Clktck,
+            23 => AuxvType::Secure,
+            24 => AuxvType::BasePlatform,
+            25 => AuxvType::Random,
+            26 => AuxvType::Hwcap2,
+            27 => AuxvType::RseqFeatureSize,
+            28 => AuxvType::RseqAlign,
+            31 => AuxvType::Execfn,
+            32 => AuxvType::Sysinfo,
+            33 => AuxvType::SysinfoEhdr,
+            51 => AuxvType::MinsigStkSz,
+            value => AuxvType::Unknown(value),
+        }
+    }
+}
+
+impl From<AuxvType> for u64 {
+    fn from(value: AuxvType) -> u64 {
+        match value {
+            AuxvType::Null => 0,
+            AuxvType::Ignore => 1,
+            AuxvType::Execfd => 2,
+            AuxvType::Phdr => 3,
+            AuxvType::Phent => 4,
+            AuxvType::Phnum => 5,
+            AuxvType::Pagesz => 6,
+            AuxvType::Base => 7,
+            AuxvType::Flags => 8,
+            AuxvType::Entry => 9,
+            AuxvType::Notelf => 10,
+            AuxvType::Uid => 11,
+            AuxvType::Euid => 12,
+            AuxvType::Gid => 13,
+            AuxvType::Egid => 14,
+            AuxvType::Platform => 15,
+            AuxvType::Hwcap => 16,
+            AuxvType::Clktck => 17,
+            AuxvType::Secure => 23,
+            AuxvType::BasePlatform => 24,
+            AuxvType::Random => 25,
+            AuxvType::Hwcap2 => 26,
+            AuxvType::RseqFeatureSize => 27,
+            AuxvType::RseqAlign => 28,
+            AuxvType::Execfn => 31,
+            AuxvType::Sysinfo => 32,
+            AuxvType::SysinfoEhdr => 33,
+            AuxvType::MinsigStkSz => 51,
+            AuxvType::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for AuxvType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuxvType::Null => write!(f, "AT_NULL"),
+            AuxvType::Ignore => write!(f, "AT_IGNORE"),
+            AuxvType::Execfd => write!(f, "AT_EXECFD"),
+            AuxvType::Phdr => write!(f, "AT_PHDR"),
+            AuxvType::Phent => write!(f, "AT_PHENT"),
+            AuxvType::Phnum => write!(f, "AT_PHNUM"),
+            AuxvType::Pagesz => write!(f, "AT_PAGESZ"),
+            AuxvType::Base => write!(f, "AT_BASE"),
+            AuxvType::Flags => write!(f, "AT_FLAGS"),
+            AuxvType::Entry => write!(f, "AT_ENTRY"),
+            AuxvType::Notelf => write!(f, "AT_NOTELF"),
+            AuxvType::Uid => write!(f, "AT_UID"),
+            AuxvType::Euid => write!(f, "AT_EUID"),
+            AuxvType::Gid => write!(f, "AT_GID"),
+            AuxvType::Egid => write!(f, "AT_EGID"),
+            AuxvType::Platform => write!(f, "AT_PLATFORM"),
+            AuxvType::Hwcap => write!(f, "AT_HWCAP"),
+            AuxvType::Clktck => write!(f, "AT_CLKTCK"),
+            AuxvType::Secure => write!(f, "AT_SECURE"),
+            AuxvType::BasePlatform => write!(f, "AT_BASE_PLATFORM"),
+            AuxvType::Random => write!(f, "AT_RANDOM"),
+            AuxvType::Hwcap2 => write!(f, "AT_HWCAP2"),
+            AuxvType::RseqFeatureSize => write!(f, "AT_RSEQ_FEATURE_SIZE"),
+            AuxvType::RseqAlign => write!(f, "AT_RSEQ_ALIGN"),
+            AuxvType::Execfn => write!(f, "AT_EXECFN"),
+            AuxvType::Sysinfo => write!(f, "AT_SYSINFO"),
+            AuxvType::SysinfoEhdr => write!(f, "AT_SYSINFO_EHDR"),
+            AuxvType::MinsigStkSz => write!(f, "AT_MINSIGSTKSZ"),
+            AuxvType::Unknown(value) => write!(f, "{:#x}", value),
+        }
+    }
+}
+
+fn c_string(bytes: &[u8]) -> Cow<'_, str> {
+    let length = bytes.iter().position(|b| *b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..length])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::note::NoteIterator;
+    use crate::testing::{file_with_segment, note, ELF64_LE};
+
+    const BASE: u64 = 0x7fff_0000;
+
+    /// A core file whose only segment holds `contents`, then zeros up to `memsz` bytes.
+    fn core_file(contents: &[u8], memsz: u64) -> Vec<u8> {
+        file_with_segment(ElfType::Core, BASE, contents, memsz)
+    }
+
+    #[test]
+    fn reads_strings_from_memory() {
+        let long = "x".repeat(STRING_CHUNK as usize * 2 + 3);
+        let contents = format!("/bin/true\0{}\0tail", long);
+        let binary = core_file(contents.as_bytes(), 0x1000);
+        let file = ElfFile::new(&binary).unwrap();
+        let core = file.get_core().unwrap();
+        assert!(matches!(
+            core.read_string(BASE).unwrap(),
+            Cow::Borrowed("/bin/true")
+        ));
+        assert_eq!(core.read_string(BASE + 5).unwrap(), "true");
+        assert_eq!(core.read_string(BASE + 10).unwrap(), long);
+        // Not NUL terminated in the file, so the string ends at the zeros after it.
+        let tail = BASE + contents.len() as u64 - 4;
+        assert_eq!(core.read_string(tail).unwrap(), "tail");
+        assert_eq!(core.read_string(BASE + 0x800).unwrap(), "");
+        assert!(core.read_string(BASE + 0x1000).is_err());
+    }
+
+    #[test]
+    fn stops_at_the_end_of_the_segment() {
+        let binary = core_file(b"abc", 3);
+        let file = ElfFile::new(&binary).unwrap();
+        assert_eq!(file.get_core().unwrap().read_string(BASE).unwrap(), "abc");
+    }
+
+    #[test]
+    fn reads_strings_in_huge_segments() {
+        // Copying the whole 1 TiB segment would abort the test.
+        let binary = core_file(b"abc\0", 1 << 40);
+        let file = ElfFile::new(&binary).unwrap();
+        let core = file.get_core().unwrap();
+        assert_eq!(core.read_string(BASE + 1).unwrap(), "bc");
+        assert_eq!(core.read_string(BASE + 0x1234_5678).unwrap(), "");
+        assert_eq!(&core.read_memory(BASE + 2, 4).unwrap()[..], b"c\0\0\0");
+    }
+
+    /// `NT_FILE` descriptor claiming `count` mappings, with `ranges` and `paths` after it.
+    fn file_note(count: u64, ranges: &[u64], paths: &[u8]) -> Vec<u8> {
+        let mut desc = Vec::new();
+        for value in [count, 0x1000].iter().chain(ranges.iter()) {
+            desc.extend_from_slice(&value.to_le_bytes());
+        }
+        desc.extend_from_slice(paths);
+        note("CORE", NT_FILE, &desc, 4)
+    }
+
+    fn mapped_files(data: &[u8]) -> Result<Option<NoteContent<'_>>, ElfError> {
+        let mut notes = NoteIterator::new(data, 0x200, 4, ELF64_LE);
+        notes.next().unwrap().unwrap().content()
+    }
+
+    #[test]
+    fn reads_mapped_files() {
+        let ranges = [0x40_0000, 0x40_1000, 0, 0x7f00_0000, 0x7f00_3000, 2];
+        let data = file_note(2, &ranges, b"/bin/cat\0/lib/libc.so.6\0");
+        let expected = vec![
+            MappedFile {
+                start: 0x40_0000,
+                end: 0x40_1000,
+                file_offset: 0,
+                path: Cow::Borrowed("/bin/cat"),
+            },
+            MappedFile {
+                start: 0x7f00_0000,
+                end: 0x7f00_3000,
+                file_offset: 0x2000,
+                path: Cow::Borrowed("/lib/libc.so.6"),
+            },
+        ];
+        assert_eq!(
+            mapped_files(&data).unwrap(),
+            Some(NoteContent::CoreFile(expected))
+        );
+    }
+
+    #[test]
+    fn rejects_counts_the_descriptor_cannot_hold() {
+        let ranges = [0x40_0000, 0x40_1000, 0];
+        for count in [2, u64::MAX / 24 + 1, u64::MAX].iter() {
+            let data = file_note(*count, &ranges, b"/bin/cat\0");
+            match mapped_files(&data) {
+                // The ranges follow the 12 byte header, "CORE\0" padded to 8 and two words.
+                Err(ElfError::OffsetOutOfRange {
+                    offset, file_size, ..
+                }) => {
+                    assert_eq!(offset, 0x200 + 20 + 16);
+                    assert_eq!(file_size, 0x200 + 20 + 16 + 24 + 9);
+                }
+                other => panic!("count {:#x}: {:?}", count, other),
+            }
+        }
+    }
+}
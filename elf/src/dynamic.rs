@@ -0,0 +1,731 @@
+use crate::error::ElfError;
+use crate::file::ElfFile;
+use crate::header::Encoding;
+use crate::program::ProgramType;
+use crate::reader::Reader;
+use crate::section::SectionType;
+use crate::string_table::StringTable;
+use std::fmt;
+
+/// Raw `Elf_Dyn` entry.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfDynamicEntry {
+    pub d_tag: i64,
+    /// `d_val` or `d_ptr`, which share the same storage.
+    pub d_val: u64,
+}
+
+impl ElfDynamicEntry {
+    pub fn new(binary: &[u8], encoding: Encoding) -> ElfDynamicEntry {
+        let mut reader = Reader::new(binary, encoding);
+        ElfDynamicEntry {
+            d_tag: reader.read_sword(),
+            d_val: reader.read_word(),
+        }
+    }
+
+    pub fn tag(&self) -> DynamicTag {
+        DynamicTag::from(self.d_tag)
+    }
+}
+
+/// Entries of the dynamic section, with string values resolved through `DT_STRTAB`.
+pub struct ElfDynamic<'a> {
+    pub entries: Vec<ElfDynamicEntry>,
+    /// `None` when the file has no `DT_STRTAB`.
+    pub strings: Option<StringTable<'a>>,
+}
+
+impl<'a> ElfDynamic<'a> {
+    /// Value of the first entry with `tag`.
+    pub fn get_value(&self, tag: DynamicTag) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|entry| entry.tag() == tag)
+            .map(|entry| entry.d_val)
+    }
+
+    /// String an entry such as `DT_NEEDED` points to.
+    pub fn get_string(&self, entry: &ElfDynamicEntry) -> Result<&'a str, ElfError> {
+        let strings = self.strings.ok_or(ElfError::MissingDynamicEntry {
+            tag: i64::from(DynamicTag::Strtab),
+        })?;
+        strings.get_str(entry.d_val)
+    }
+
+    /// Libraries in `DT_NEEDED`, in order.
+    pub fn needed(&self) -> Result<Vec<&'a str>, ElfError> {
+        self.get_strings(DynamicTag::Needed)
+    }
+
+    pub fn soname(&self) -> Result<Option<&'a str>, ElfError> {
+        Ok(self.get_strings(DynamicTag::Soname)?.first().copied())
+    }
+
+    pub fn rpath(&self) -> Result<Option<&'a str>, ElfError> {
+        Ok(self.get_strings(DynamicTag::Rpath)?.first().copied())
+    }
+
+    pub fn runpath(&self) -> Result<Option<&'a str>, ElfError> {
+        Ok(self.get_strings(DynamicTag::Runpath)?.first().copied())
+    }
+
+    pub fn flags(&self) -> Option<DynamicFlags> {
+        self.get_value(DynamicTag::Flags).map(DynamicFlags)
+    }
+
+    pub fn flags_1(&self) -> Option<DynamicFlags1> {
+        self.get_value(DynamicTag::Flags1).map(DynamicFlags1)
+    }
+
+    /// Address and size in bytes of `DT_INIT_ARRAY`.
+    pub fn init_array(&self) -> Option<(u64, u64)> {
+        let address = self.get_value(DynamicTag::InitArray)?;
+        Some((address, self.get_value(DynamicTag::InitArraysz)?))
+    }
+
+    /// Address and size in bytes of `DT_FINI_ARRAY`.
+    pub fn fini_array(&self) -> Option<(u64, u64)> {
+        let address = self.get_value(DynamicTag::FiniArray)?;
+        Some((address, self.get_value(DynamicTag::FiniArraysz)?))
+    }
+
+    /// Address and size in bytes of `DT_PREINIT_ARRAY`.
+    pub fn preinit_array(&self) -> Option<(u64, u64)> {
+        let address = self.get_value(DynamicTag::PreinitArray)?;
+        Some((address, self.get_value(DynamicTag::PreinitArraysz)?))
+    }
+
+    fn get_strings(&self, tag: DynamicTag) -> Result<Vec<&'a str>, ElfError> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.tag() == tag)
+            .map(|entry| self.get_string(entry))
+            .collect()
+    }
+}
+
+impl<'a> fmt::Display for ElfDynamic<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "---ElfDynamic---")?;
+        for entry in self.entries.iter() {
+            let tag = entry.tag();
+            write!(f, "\n{:<15} = ", tag.to_string())?;
+            match tag {
+                DynamicTag::Needed
+                | DynamicTag::Soname
+                | DynamicTag::Rpath
+                | DynamicTag::Runpath
+                | DynamicTag::Auxiliary
+                | DynamicTag::Filter => match self.get_string(entry) {
+                    Ok(string) => write!(f, "[{}]", string)?,
+                    Err(_) => write!(f, "<bad string {:#x}>", entry.d_val)?,
+                },
+                DynamicTag::Flags => {
+                    write!(f, "{} ({:#x})", DynamicFlags(entry.d_val), entry.d_val)?
+                }
+                DynamicTag::Flags1 => {
+                    write!(f, "{} ({:#x})", DynamicFlags1(entry.d_val), entry.d_val)?
+                }
+                _ => write!(f, "{:#x}", entry.d_val)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ElfFile<'a> {
+    /// Entries of `.dynamic`, up to but excluding `DT_NULL`.
+    ///
+    /// Read from the `SHT_DYNAMIC` section, or from the `PT_DYNAMIC` segment
+    /// when the section headers have been stripped.
+    pub fn get_dynamic_entries(&self) -> Result<Vec<ElfDynamicEntry>, ElfError> {
+        let data = match self
+            .get_section_headers()?
+            .iter()
+            .find(|header| header.sh_type == u32::from(SectionType::Dynamic))
+        {
+            Some(header) => self.get_section_data(header)?,
+            None => {
+                let header = self
+                    .get_program_headers()?
+                    .into_iter()
+                    .find(|header| ProgramType::from(header.p_type) == ProgramType::Dynamic)
+                    .ok_or(ElfError::MissingSection {
+                        offset: self.get_elf_header().e_shoff,
+                        section_type: u32::from(SectionType::Dynamic),
+                    })?;
+                self.get_bytes(header.p_offset, header.p_filesz)?
+            }
+        };
+        let encoding = self.get_encoding();
+        Ok(data
+            .chunks_exact(encoding.dynamic_entry_size())
+            .map(|chunk| ElfDynamicEntry::new(chunk, encoding))
+            .take_while(|entry| entry.tag() != DynamicTag::Null)
+            .collect())
+    }
+
+    /// Dynamic entries with the string table found through `DT_STRTAB` and `DT_STRSZ`.
+    pub fn get_dynamic(&self) -> Result<ElfDynamic<'a>, ElfError> {
+        let mut dynamic = ElfDynamic {
+            entries: self.get_dynamic_entries()?,
+            strings: None,
+        };
+        if let Some(address) = dynamic.get_value(DynamicTag::Strtab) {
+            let size =
+                dynamic
+                    .get_value(DynamicTag::Strsz)
+                    .ok_or(ElfError::MissingDynamicEntry {
+                        tag: i64::from(DynamicTag::Strsz),
+                    })?;
+            let offset = self.vaddr_to_offset(address)?;
+            dynamic.strings = Some(StringTable::new(self.get_bytes(offset, size)?, offset));
+        }
+        Ok(dynamic)
+    }
+}
+
+/// Bits in `DT_FLAGS`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DynamicFlags(pub u64);
+
+impl DynamicFlags {
+    pub const ORIGIN: DynamicFlags = DynamicFlags(0x1);
+    pub const SYMBOLIC: DynamicFlags = DynamicFlags(0x2);
+    pub const TEXTREL: DynamicFlags = DynamicFlags(0x4);
+    pub const BIND_NOW: DynamicFlags = DynamicFlags(0x8);
+    pub const STATIC_TLS: DynamicFlags = DynamicFlags(0x10);
+
+    pub fn contains(&self, flags: DynamicFlags) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+}
+
+impl fmt::Display for DynamicFlags {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names = [
+            (DynamicFlags::ORIGIN, "ORIGIN"),
+            (DynamicFlags::SYMBOLIC, "SYMBOLIC"),
+            (DynamicFlags::TEXTREL, "TEXTREL"),
+            (DynamicFlags::BIND_NOW, "BIND_NOW"),
+            (DynamicFlags::STATIC_TLS, "STATIC_TLS"),
+        ];
+        write_flag_names(f, self.0, &names.map(|(flag, name)| (flag.0, name)))
+    }
+}
+
+/// Bits in `DT_FLAGS_1`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DynamicFlags1(pub u64);
+
+impl DynamicFlags1 {
+    pub const NOW: DynamicFlags1 = DynamicFlags1(0x1);
+    pub const GLOBAL: DynamicFlags1 = DynamicFlags1(0x2);
+    pub const GROUP: DynamicFlags1 = DynamicFlags1(0x4);
+    pub const NODELETE: DynamicFlags1 = DynamicFlags1(0x8);
+    pub const LOADFLTR: DynamicFlags1 = DynamicFlags1(0x10);
+    pub const INITFIRST: DynamicFlags1 = DynamicFlags1(0x20);
+    pub const NOOPEN: DynamicFlags1 = DynamicFlags1(0x40);
+    pub const ORIGIN: DynamicFlags1 = DynamicFlags1(0x80);
+    pub const DIRECT: DynamicFlags1 = DynamicFlags1(0x100);
+    pub const INTERPOSE: DynamicFlags1 = DynamicFlags1(0x400);
+    pub const NODEFLIB: DynamicFlags1 = DynamicFlags1(0x800);
+    pub const NODUMP: DynamicFlags1 = DynamicFlags1(0x1000);
+    pub const CONFALT: DynamicFlags1 = DynamicFlags1(0x2000);
+    pub const ENDFILTEE: DynamicFlags1 = DynamicFlags1(0x4000);
+    pub const DISPRELDNE: DynamicFlags1 = DynamicFlags1(0x8000);
+    pub const DISPRELPND: DynamicFlags1 = DynamicFlags1(0x1_0000);
+    pub const NODIRECT: DynamicFlags1 = DynamicFlags1(0x2_0000);
+    pub const IGNMULDEF: DynamicFlags1 = DynamicFlags1(0x4_0000);
+    pub const NOKSYMS: DynamicFlags1 = DynamicFlags1(0x8_0000);
+    pub const NOHDR: DynamicFlags1 = DynamicFlags1(0x10_0000);
+    pub const EDITED: DynamicFlags1 = DynamicFlags1(0x20_0000);
+    pub const NORELOC: DynamicFlags1 = DynamicFlags1(0x40_0000);
+    pub const SYMINTPOSE: DynamicFlags1 = DynamicFlags1(0x80_0000);
+    pub const GLOBAUDIT: DynamicFlags1 = DynamicFlags1(0x100_0000);
+    pub const SINGLETON: DynamicFlags1 = DynamicFlags1(0x200_0000);
+    pub const STUB: DynamicFlags1 = DynamicFlags1(0x400_0000);
+    pub const PIE: DynamicFlags1 = DynamicFlags1(0x800_0000);
+
+    pub fn contains(&self, flags: DynamicFlags1) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+}
+
+impl fmt::Display for DynamicFlags1 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names = [
+            (DynamicFlags1::NOW, "NOW"),
+            (DynamicFlags1::GLOBAL, "GLOBAL"),
+            (DynamicFlags1::GROUP, "GROUP"),
+            (DynamicFlags1::NODELETE, "NODELETE"),
+            (DynamicFlags1::LOADFLTR, "LOADFLTR"),
+            (DynamicFlags1::INITFIRST, "INITFIRST"),
+            (DynamicFlags1::NOOPEN, "NOOPEN"),
+            (DynamicFlags1::ORIGIN, "ORIGIN"),
+            (DynamicFlags1::DIRECT, "DIRECT"),
+            (DynamicFlags1::INTERPOSE, "INTERPOSE"),
+            (DynamicFlags1::NODEFLIB, "NODEFLIB"),
+            (DynamicFlags1::NODUMP, "NODUMP"),
+            (DynamicFlags1::CONFALT, "CONFALT"),
+            (DynamicFlags1::ENDFILTEE, "ENDFILTEE"),
+            (DynamicFlags1::DISPRELDNE, "DISPRELDNE"),
+            (DynamicFlags1::DISPRELPND, "DISPRELPND"),
+            (DynamicFlags1::NODIRECT, "NODIRECT"),
+            (DynamicFlags1::IGNMULDEF, "IGNMULDEF"),
+            (DynamicFlags1::NOKSYMS, "NOKSYMS"),
+            (DynamicFlags1::NOHDR, "NOHDR"),
+            (DynamicFlags1::EDITED, "EDITED"),
+            (DynamicFlags1::NORELOC, "NORELOC"),
+            (DynamicFlags1::SYMINTPOSE, "SYMINTPOSE"),
+            (DynamicFlags1::GLOBAUDIT, "GLOBAUDIT"),
+            (DynamicFlags1::SINGLETON, "SINGLETON"),
+            (DynamicFlags1::STUB, "STUB"),
+            (DynamicFlags1::PIE, "PIE"),
+        ];
+        write_flag_names(f, self.0, &names.map(|(flag, name)| (flag.0, name)))
+    }
+}
+
+/// Writes the names of the set bits separated by spaces, then any unnamed bits in hex.
+fn write_flag_names(f: &mut fmt::Formatter, value: u64, names: &[(u64, &str)]) -> fmt::Result {
+    let mut rest = value;
+    let mut separator = "";
+    for (flag, name) in names.iter() {
+        if value & flag != 0 {
+            write!(f, "{}{}", separator, name)?;
+            separator = " ";
+            rest &= !flag;
+        }
+    }
+    if rest != 0 {
+        write!(f, "{}{:#x}", separator, rest)?;
+    }
+    Ok(())
+}
+
+/// Tag in `d_tag`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DynamicTag {
+    Null,
+    Needed,
+    Pltrelsz,
+    Pltgot,
+    Hash,
+    Strtab,
+    Symtab,
+    Rela,
+    Relasz,
+    Relaent,
+    Strsz,
+    Syment,
+    Init,
+    Fini,
+    Soname,
+    Rpath,
+    Symbolic,
+    Rel,
+    Relsz,
+    Relent,
+    Pltrel,
+    Debug,
+    Textrel,
+    Jmprel,
+    BindNow,
+    InitArray,
+    FiniArray,
+    InitArraysz,
+    FiniArraysz,
+    Runpath,
+    Flags,
+    PreinitArray,
+    PreinitArraysz,
+    SymtabShndx,
+    Relrsz,
+    Relr,
+    Relrent,
+    GnuPrelinked,
+    GnuConflictsz,
+    GnuLiblistsz,
+    Checksum,
+    Pltpadsz,
+    Moveent,
+    Movesz,
+    Posflag1,
+    Syminsz,
+    Syminent,
+    GnuHash,
+    TlsdescPlt,
+    TlsdescGot,
+    GnuConflict,
+    GnuLiblist,
+    Config,
+    Depaudit,
+    Audit,
+    Pltpad,
+    Movetab,
+    Syminfo,
+    Versym,
+    Relacount,
+    Relcount,
+    Flags1,
+    Verdef,
+    Verdefnum,
+    Verneed,
+    Verneednum,
+    Auxiliary,
+    Filter,
+    /// Operating system specific range `DT_LOOS..=DT_HIOS`.
+    Os(i64),
+    /// Processor specific range `DT_LOPROC..=DT_HIPROC`.
+    Processor(i64),
+    Unknown(i64),
+}
+
+impl From<i64> for DynamicTag {
+    fn from(value: i64) -> DynamicTag {
+        match value {
+            0 => DynamicTag::Null,
+            1 => DynamicTag::Needed,
+            2 => DynamicTag::Pltrelsz,
+            3 => DynamicTag::Pltgot,
+            4 => DynamicTag::Hash,
+            5 => DynamicTag::Strtab,
+            6 => DynamicTag::Symtab,
+            7 => DynamicTag::Rela,
+            8 => DynamicTag::Relasz,
+            9 => DynamicTag::Relaent,
+            10 => DynamicTag::Strsz,
+            11 => DynamicTag::Syment,
+            12 => DynamicTag::Init,
+            13 => DynamicTag::Fini,
+            14 => DynamicTag::Soname,
+            15 => DynamicTag::Rpath,
+            16 => DynamicTag::Symbolic,
+            17 => DynamicTag::Rel,
+            18 => DynamicTag::Relsz,
+            19 => DynamicTag::Relent,
+            20 => DynamicTag::Pltrel,
+            21 => DynamicTag::Debug,
+            22 => DynamicTag::Textrel,
+            23 => DynamicTag::Jmprel,
+            24 => DynamicTag::BindNow,
+            25 => DynamicTag::InitArray,
+            26 => DynamicTag::FiniArray,
+            27 => DynamicTag::InitArraysz,
+            28 => DynamicTag::FiniArraysz,
+            29 => DynamicTag::Runpath,
+            30 => DynamicTag::Flags,
+            32 => DynamicTag::PreinitArray,
+            33 => DynamicTag::PreinitArraysz,
+            34 => DynamicTag::SymtabShndx,
+            35 => DynamicTag::Relrsz,
+            36 => DynamicTag::Relr,
+            37 => DynamicTag::Relrent,
+            0x6fff_fdf5 => DynamicTag::GnuPrelinked,
+            0x6fff_fdf6 => DynamicTag::GnuConflictsz,
+            0x6fff_fdf7 => DynamicTag::GnuLiblistsz,
+            0x6fff_fdf8 => DynamicTag::Checksum,
+            0x6fff_fdf9 => DynamicTag::Pltpadsz,
+            0x6fff_fdfa => DynamicTag::Moveent,
+            0x6fff_fdfb => DynamicTag::Movesz,
+            0x6fff_fdfd => DynamicTag::Posflag1,
+            0x6fff_fdfe => DynamicTag::Syminsz,
+            0x6fff_fdff => DynamicTag::Syminent,
+            0x6fff_fef5 => DynamicTag::GnuHash,
+            0x6fff_fef6 => DynamicTag::TlsdescPlt,
+            0x6fff_fef7 => DynamicTag::TlsdescGot,
+            0x6fff_fef8 => DynamicTag::GnuConflict,
+            0x6fff_fef9 => DynamicTag::GnuLiblist,
+            0x6fff_fefa => DynamicTag::Config,
+            0x6fff_fefb => DynamicTag::Depaudit,
+            0x6fff_fefc => DynamicTag::Audit,
+            0x6fff_fefd => DynamicTag::Pltpad,
+            0x6fff_fefe => DynamicTag::Movetab,
+            0x6fff_feff => DynamicTag::Syminfo,
+            0x6fff_fff0 => DynamicTag::Versym,
+            0x6fff_fff9 => DynamicTag::Relacount,
+            0x6fff_fffa => DynamicTag::Relcount,
+            0x6fff_fffb => DynamicTag::Flags1,
+            0x6fff_fffc => DynamicTag::Verdef,
+            0x6fff_fffd => DynamicTag::Verdefnum,
+            0x6fff_fffe => DynamicTag::Verneed,
+            0x6fff_ffff => DynamicTag::Verneednum,
+            0x7fff_fffd => DynamicTag::Auxiliary,
+            0x7fff_ffff => DynamicTag::Filter,
+            0x6000_0000..=0x6fff_ffff => DynamicTag::Os(value),
+            0x7000_0000..=0x7fff_ffff => DynamicTag::Processor(value),
+            value => DynamicTag::Unknown(value),
+        }
+    }
+}
+
+impl From<DynamicTag> for i64 {
+    fn from(value: DynamicTag) -> i64 {
+        match value {
+            DynamicTag::Null => 0,
+            DynamicTag::Needed => 1,
+            DynamicTag::Pltrelsz => 2,
+            DynamicTag::Pltgot => 3,
+            DynamicTag::Hash => 4,
+            DynamicTag::Strtab => 5,
+            DynamicTag::Symtab => 6,
+            DynamicTag::Rela => 7,
+            DynamicTag::Relasz => 8,
+            DynamicTag::Relaent => 9,
+            DynamicTag::Strsz => 10,
+            DynamicTag::Syment => 11,
+            DynamicTag::Init => 12,
+            DynamicTag::Fini => 13,
+            DynamicTag::Soname => 14,
+            DynamicTag::Rpath => 15,
+            DynamicTag::Symbolic => 16,
+            DynamicTag::Rel => 17,
+            DynamicTag::Relsz => 18,
+            DynamicTag::Relent => 19,
+            DynamicTag::Pltrel => 20,
+            DynamicTag::Debug => 21,
+            DynamicTag::Textrel => 22,
+            DynamicTag::Jmprel => 23,
+            DynamicTag::BindNow => 24,
+            DynamicTag::InitArray => 25,
+            DynamicTag::FiniArray => 26,
+            DynamicTag::InitArraysz => 27,
+            DynamicTag::FiniArraysz => 28,
+            DynamicTag::Runpath => 29,
+            DynamicTag::Flags => 30,
+            DynamicTag::PreinitArray => 32,
+            DynamicTag::PreinitArraysz => 33,
+            DynamicTag::SymtabShndx => 34,
+            DynamicTag::Relrsz => 35,
+            DynamicTag::Relr => 36,
+            DynamicTag::Relrent => 37,
+            DynamicTag::GnuPrelinked => 0x6fff_fdf5,
+            DynamicTag::GnuConflictsz => 0x6fff_fdf6,
+            DynamicTag::GnuLiblistsz => 0x6fff_fdf7,
+            DynamicTag::Checksum => 0x6fff_fdf8,
+            DynamicTag::Pltpadsz => 0x6fff_fdf9,
+            DynamicTag::Moveent => 0x6fff_fdfa,
+            DynamicTag::Movesz => 0x6fff_fdfb,
+            DynamicTag::Posflag1 => 0x6fff_fdfd,
+            DynamicTag::Syminsz => 0x6fff_fdfe,
+            DynamicTag::Syminent => 0x6fff_fdff,
+            DynamicTag::GnuHash => 0x6fff_fef5,
+            DynamicTag::TlsdescPlt => 0x6fff_fef6,
+            DynamicTag::TlsdescGot => 0x6fff_fef7,
+            DynamicTag::GnuConflict => 0x6fff_fef8,
+            DynamicTag::GnuLiblist => 0x6fff_fef9,
+            DynamicTag::Config => 0x6fff_fefa,
+            DynamicTag::Depaudit => 0x6fff_fefb,
+            DynamicTag::Audit => 0x6fff_fefc,
+            DynamicTag::Pltpad => 0x6fff_fefd,
+            DynamicTag::Movetab => 0x6fff_fefe,
+            DynamicTag::Syminfo => 0x6fff_feff,
+            DynamicTag::Versym => 0x6fff_fff0,
+            DynamicTag::Relacount => 0x6fff_fff9,
+            DynamicTag::Relcount => 0x6fff_fffa,
+            DynamicTag::Flags1 => 0x6fff_fffb,
+            DynamicTag::Verdef => 0x6fff_fffc,
+            DynamicTag::Verdefnum => 0x6fff_fffd,
+            DynamicTag::Verneed => 0x6fff_fffe,
+            DynamicTag::Verneednum => 0x6fff_ffff,
+            DynamicTag::Auxiliary => 0x7fff_fffd,
+            DynamicTag::Filter => 0x7fff_ffff,
+            DynamicTag::Os(value) | DynamicTag::Processor(value) | DynamicTag::Unknown(value) => {
+                value
+            }
+        }
+    }
+}
+
+impl fmt::Display for DynamicTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DynamicTag::Null => write!(f, "NULL"),
+            DynamicTag::Needed => write!(f, "NEEDED"),
+            DynamicTag::Pltrelsz => write!(f, "PLTRELSZ"),
+            DynamicTag::Pltgot => write!(f, "PLTGOT"),
+            DynamicTag::Hash => write!(f, "HASH"),
+            DynamicTag::Strtab => write!(f, "STRTAB"),
+            DynamicTag::Symtab => write!(f, "SYMTAB"),
+            DynamicTag::Rela => write!(f, "RELA"),
+            DynamicTag::Relasz => write!(f, "RELASZ"),
+            DynamicTag::Relaent => write!(f, "RELAENT"),
+            DynamicTag::Strsz => write!(f, "STRSZ"),
+            DynamicTag::Syment => write!(f, "SYMENT"),
+            DynamicTag::Init => write!(f, "INIT"),
+            DynamicTag::Fini => write!(f, "FINI"),
+            DynamicTag::Soname => write!(f, "SONAME"),
+            DynamicTag::Rpath => write!(f, "RPATH"),
+            DynamicTag::Symbolic => write!(f, "SYMBOLIC"),
+            DynamicTag::Rel => write!(f, "REL"),
+            DynamicTag::Relsz => write!(f, "RELSZ"),
+            DynamicTag::Relent => write!(f, "RELENT"),
+            DynamicTag::Pltrel => write!(f, "PLTREL"),
+            DynamicTag::Debug => write!(f, "DEBUG"),
+            DynamicTag::Textrel => write!(f, "TEXTREL"),
+            DynamicTag::Jmprel => write!(f, "JMPREL"),
+            DynamicTag::BindNow => write!(f, "BIND_NOW"),
+            DynamicTag::InitArray => write!(f, "INIT_ARRAY"),
+            DynamicTag::FiniArray => write!(f, "FINI_ARRAY"),
+            DynamicTag::InitArraysz => write!(f, "INIT_ARRAYSZ"),
+            DynamicTag::FiniArraysz => write!(f, "FINI_ARRAYSZ"),
+            DynamicTag::Runpath => write!(f, "RUNPATH"),
+            DynamicTag::Flags => write!(f, "FLAGS"),
+            DynamicTag::PreinitArray => write!(f, "PREINIT_ARRAY"),
+            DynamicTag::PreinitArraysz => write!(f, "PREINIT_ARRAYSZ"),
+            DynamicTag::SymtabShndx => write!(f, "SYMTAB_SHNDX"),
+            DynamicTag::Relrsz => write!(f, "RELRSZ"),
+            DynamicTag::Relr => write!(f, "RELR"),
+            DynamicTag::Relrent => write!(f, "RELRENT"),
+            DynamicTag::GnuPrelinked => write!(f, "GNU_PRELINKED"),
+            DynamicTag::GnuConflictsz => write!(f, "GNU_CONFLICTSZ"),
+            DynamicTag::GnuLiblistsz => write!(f, "GNU_LIBLISTSZ"),
+            DynamicTag::Checksum => write!(f, "CHECKSUM"),
+            DynamicTag::Pltpadsz => write!(f, "PLTPADSZ"),
+            DynamicTag::Moveent => write!(f, "MOVEENT"),
+            DynamicTag::Movesz => write!(f, "MOVESZ"),
+            DynamicTag::Posflag1 => write!(f, "POSFLAG_1"),
+            DynamicTag::Syminsz => write!(f, "SYMINSZ"),
+            DynamicTag::Syminent => write!(f, "SYMINENT"),
+            DynamicTag::GnuHash => write!(f, "GNU_HASH"),
+            DynamicTag::TlsdescPlt => write!(f, "TLSDESC_PLT"),
+            DynamicTag::TlsdescGot => write!(f, "TLSDESC_GOT"),
+            DynamicTag::GnuConflict => write!(f, "GNU_CONFLICT"),
+            DynamicTag::GnuLiblist => write!(f, "GNU_LIBLIST"),
+            DynamicTag::Config => write!(f, "CONFIG"),
+            DynamicTag::Depaudit => write!(f, "DEPAUDIT"),
+            DynamicTag::Audit => write!(f, "AUDIT"),
+            DynamicTag::Pltpad => write!(f, "PLTPAD"),
+            DynamicTag::Movetab => write!(f, "MOVETAB"),
+            DynamicTag::Syminfo => write!(f, "SYMINFO"),
+            DynamicTag::Versym => write!(f, "VERSYM"),
+            DynamicTag::Relacount => write!(f, "RELACOUNT"),
+            DynamicTag::Relcount => write!(f, "RELCOUNT"),
+            DynamicTag::Flags1 => write!(f, "FLAGS_1"),
+            DynamicTag::Verdef => write!(f, "VERDEF"),
+            DynamicTag::Verdefnum => write!(f, "VERDEFNUM"),
+            DynamicTag::Verneed => write!(f, "VERNEED"),
+            DynamicTag::Verneednum => write!(f, "VERNEEDNUM"),
+            DynamicTag::Auxiliary => write!(f, "AUXILIARY"),
+            DynamicTag::Filter => write!(f, "FILTER"),
+            DynamicTag::Os(value) => write!(f, "LOOS+{:#x}", value - 0x6000_0000),
+            DynamicTag::Processor(value) => write!(f, "LOPROC+{:#x}", value - 0x7000_0000),
+            DynamicTag::Unknown(value) => write!(f, "<unknown>: {:#x}", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::{ElfType, Machine};
+    use crate::program::ElfProgramHeader;
+    use crate::section::{ElfSectionHeader, SectionFlags};
+    use crate::testing::{load_segment, section_header, ELF64_LE};
+    use crate::writer::ElfWriter;
+
+    const STRINGS: &[u8] = b"\0libc.so.6\0libm.so.6\0libfoo.so.1\0$ORIGIN/lib\0";
+
+    /// A shared object whose `.dynamic` holds `entries`, then `DT_NULL` and one entry past it,
+    /// with `STRINGS` in `.dynstr` at 0x1000.
+    fn shared_object(entries: &[(DynamicTag, u64)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let null = (DynamicTag::Null, 0);
+        let past_null = (DynamicTag::Needed, 1);
+        for (tag, value) in entries.iter().chain([null, past_null].iter()) {
+            data.extend_from_slice(&i64::from(*tag).to_le_bytes());
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        let mut writer = ElfWriter::new(ELF64_LE, ElfType::Dyn, Machine::X86_64);
+        writer.segments.push(load_segment(0x1000, 0x1000));
+        writer.segments.push(ElfProgramHeader {
+            p_type: u32::from(ProgramType::Dynamic),
+            p_vaddr: 0x1100,
+            p_filesz: data.len() as u64,
+            p_memsz: data.len() as u64,
+            ..load_segment(0x1100, 0)
+        });
+        let alloc = SectionFlags::ALLOC.0 | SectionFlags::WRITE.0;
+        writer.add_section(
+            ".dynstr",
+            ElfSectionHeader {
+                sh_flags: alloc,
+                sh_addr: 0x1000,
+                ..section_header(SectionType::Strtab)
+            },
+            STRINGS,
+        );
+        writer.add_section(
+            ".dynamic",
+            ElfSectionHeader {
+                sh_flags: alloc,
+                sh_addr: 0x1100,
+                sh_link: 1,
+                sh_entsize: 16,
+                ..section_header(SectionType::Dynamic)
+            },
+            data,
+        );
+        writer.to_bytes().unwrap()
+    }
+
+    fn entries() -> Vec<(DynamicTag, u64)> {
+        vec![
+            (DynamicTag::Needed, 1),
+            (DynamicTag::Needed, 11),
+            (DynamicTag::Soname, 21),
+            (DynamicTag::Runpath, 33),
+            (DynamicTag::Strtab, 0x1000),
+            (DynamicTag::Strsz, STRINGS.len() as u64),
+            (DynamicTag::Flags, 0x8),
+            (DynamicTag::Flags1, 0x800_0001),
+        ]
+    }
+
+    fn check_strings(dynamic: &ElfDynamic) {
+        assert_eq!(dynamic.entries.len(), 8);
+        assert_eq!(dynamic.needed().unwrap(), ["libc.so.6", "libm.so.6"]);
+        assert_eq!(dynamic.soname().unwrap(), Some("libfoo.so.1"));
+        assert_eq!(dynamic.runpath().unwrap(), Some("$ORIGIN/lib"));
+        assert_eq!(dynamic.rpath().unwrap(), None);
+    }
+
+    #[test]
+    fn resolves_strings_through_dt_strtab() {
+        let binary = shared_object(&entries());
+        let file = ElfFile::new(&binary).unwrap();
+        let dynamic = file.get_dynamic().unwrap();
+        check_strings(&dynamic);
+        assert!(dynamic.flags().unwrap().contains(DynamicFlags::BIND_NOW));
+        let flags_1 = dynamic.flags_1().unwrap();
+        assert!(flags_1.contains(DynamicFlags1::NOW));
+        assert!(flags_1.contains(DynamicFlags1::PIE));
+        assert_eq!(flags_1.to_string(), "NOW PIE");
+    }
+
+    #[test]
+    fn reads_pt_dynamic_without_section_headers() {
+        let mut binary = shared_object(&entries());
+        // Drops the section header table by clearing e_shoff, e_shnum and e_shstrndx.
+        binary[40..48].fill(0);
+        binary[60..64].fill(0);
+        let file = ElfFile::new(&binary).unwrap();
+        assert!(file.get_section_headers().unwrap().is_empty());
+        check_strings(&file.get_dynamic().unwrap());
+    }
+
+    #[test]
+    fn needs_dt_strsz_with_dt_strtab() {
+        let mut entries = entries();
+        entries.retain(|(tag, _)| *tag != DynamicTag::Strsz);
+        let binary = shared_object(&entries);
+        let file = ElfFile::new(&binary).unwrap();
+        assert_eq!(file.get_dynamic_entries().unwrap().len(), 7);
+        assert!(matches!(
+            file.get_dynamic(),
+            Err(ElfError::MissingDynamicEntry { tag }) if tag == i64::from(DynamicTag::Strsz)
+        ));
+    }
+}
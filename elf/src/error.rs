@@ -0,0 +1,207 @@
+use crate::dynamic::DynamicTag;
+use crate::header::ElfType;
+use crate::section::SectionType;
+use std::fmt;
+
+/// Errors that can occur while reading an elf file.
+///
+/// Every variant carries the file offset at which the problem was found.
+#[derive(Debug)]
+pub enum ElfError {
+    /// The file is too short to contain the requested header.
+    TruncatedHeader {
+        offset: u64,
+        size: u64,
+        file_size: u64,
+    },
+    /// The file does not start with `0x7f 'E' 'L' 'F'`, or `!<arch>` for archives.
+    BadMagic { offset: u64, magic: Vec<u8> },
+    /// `EI_CLASS` is neither `ELFCLASS32` nor `ELFCLASS64`.
+    BadClass { offset: u64, value: u8 },
+    /// `EI_DATA` is neither `ELFDATA2LSB` nor `ELFDATA2MSB`.
+    BadEncoding { offset: u64, value: u8 },
+    /// A header points to a range outside of the file.
+    OffsetOutOfRange {
+        offset: u64,
+        size: u64,
+        file_size: u64,
+    },
+    /// A section index points outside of the section header table.
+    BadSectionIndex { offset: u64, index: u64, count: u64 },
+    /// A symbol index points outside of its symbol table.
+    BadSymbolIndex { offset: u64, index: u64, count: u64 },
+    /// A string index points outside of its string table or is not NUL terminated.
+    BadStringIndex { offset: u64, index: u64 },
+    /// A string in a string table is not valid UTF-8.
+    InvalidUtf8 { offset: u64 },
+    /// A virtual address is not covered by any `PT_LOAD` segment.
+    UnmappedAddress { address: u64 },
+    /// A virtual address lies in the zero-filled part of a segment and has no file contents.
+    AddressNotInFile { address: u64 },
+    /// A file offset is not loaded by any `PT_LOAD` segment.
+    UnloadedOffset { offset: u64 },
+    /// A dynamic entry required to interpret another one is missing.
+    MissingDynamicEntry { tag: i64 },
+    /// No section of the requested type exists.
+    MissingSection { offset: u64, section_type: u32 },
+    /// A core file was expected but `e_type` is not `ET_CORE`.
+    NotCoreFile { elf_type: u16 },
+    /// An archive member header has a bad terminator or a field that is not a number.
+    BadArchiveHeader { offset: u64 },
+    /// The contents of a thin archive member are stored in a separate file.
+    ThinArchiveMember { offset: u64 },
+    /// While laying out a file to write, an allocated section could not get a file offset
+    /// congruent with its address because the sections before it already end past it.
+    SectionOutOfOrder { index: u64, address: u64 },
+    /// The header table at `offset` has entries of `entry_size` bytes, too small for the
+    /// `size` bytes of a header.
+    BadEntrySize {
+        offset: u64,
+        entry_size: u64,
+        size: u64,
+    },
+    /// A range of `size` bytes at `offset` ends past the largest 64-bit offset.
+    OffsetOverflow { offset: u64, size: u64 },
+    /// A file to write has more program headers than `e_phnum` can count.
+    TooManySegments { count: u64 },
+    /// The headers of a file written in its own layout place contents `size` bytes in, past
+    /// the `limit` bytes its original contents and new data could need.
+    FileTooLarge { size: u64, limit: u64 },
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElfError::TruncatedHeader {
+                offset,
+                size,
+                file_size,
+            } => write!(
+                f,
+                "truncated header: {:#x} bytes at offset {:#x}, but file is {:#x} bytes",
+                size, offset, file_size
+            ),
+            ElfError::BadMagic { offset, magic } => {
+                write!(f, "bad magic {:x?} at offset {:#x}", magic, offset)
+            }
+            ElfError::BadClass { offset, value } => {
+                write!(f, "bad file class {} at offset {:#x}", value, offset)
+            }
+            ElfError::BadEncoding { offset, value } => {
+                write!(f, "bad data encoding {} at offset {:#x}", value, offset)
+            }
+            ElfError::OffsetOutOfRange {
+                offset,
+                size,
+                file_size,
+            } => write!(
+                f,
+                "range {:#x}..{:#x} is out of file (size {:#x})",
+                offset,
+                offset.saturating_add(*size),
+                file_size
+            ),
+            ElfError::BadSectionIndex {
+                offset,
+                index,
+                count,
+            } => write!(
+                f,
+                "bad section index {} in section header table at offset {:#x} with {} entries",
+                index, offset, count
+            ),
+            ElfError::BadSymbolIndex {
+                offset,
+                index,
+                count,
+            } => write!(
+                f,
+                "bad symbol index {} at offset {:#x} for symbol table with {} entries",
+                index, offset, count
+            ),
+            ElfError::BadStringIndex { offset, index } => write!(
+                f,
+                "bad string index {:#x} in string table at offset {:#x}",
+                index, offset
+            ),
+            ElfError::InvalidUtf8 { offset } => {
+                write!(f, "invalid UTF-8 string at offset {:#x}", offset)
+            }
+            ElfError::UnmappedAddress { address } => {
+                write!(
+                    f,
+                    "address {:#x} is not mapped by any PT_LOAD segment",
+                    address
+                )
+            }
+            ElfError::AddressNotInFile { address } => write!(
+                f,
+                "address {:#x} is in the zero-filled part of a segment",
+                address
+            ),
+            ElfError::UnloadedOffset { offset } => {
+                write!(
+                    f,
+                    "offset {:#x} is not loaded by any PT_LOAD segment",
+                    offset
+                )
+            }
+            ElfError::MissingDynamicEntry { tag } => {
+                write!(f, "dynamic entry {} not found", DynamicTag::from(*tag))
+            }
+            ElfError::MissingSection {
+                offset,
+                section_type,
+            } => write!(
+                f,
+                "section of type {} not found in section headers at offset {:#x}",
+                SectionType::from(*section_type),
+                offset
+            ),
+            ElfError::NotCoreFile { elf_type } => {
+                write!(
+                    f,
+                    "expected a core file but the type is {}",
+                    ElfType::from(*elf_type)
+                )
+            }
+            ElfError::BadArchiveHeader { offset } => {
+                write!(f, "bad archive member header at offset {:#x}", offset)
+            }
+            ElfError::ThinArchiveMember { offset } => write!(
+                f,
+                "thin archive member at offset {:#x} is stored in a separate file",
+                offset
+            ),
+            ElfError::SectionOutOfOrder { index, address } => write!(
+                f,
+                "section {} at address {:#x} cannot be placed after the sections before it",
+                index, address
+            ),
+            ElfError::BadEntrySize {
+                offset,
+                entry_size,
+                size,
+            } => write!(
+                f,
+                "header table at offset {:#x} has {} byte entries, but a header is {} bytes",
+                offset, entry_size, size
+            ),
+            ElfError::OffsetOverflow { offset, size } => write!(
+                f,
+                "range of {:#x} bytes at offset {:#x} overflows the file offsets",
+                size, offset
+            ),
+            ElfError::TooManySegments { count } => {
+                write!(f, "{} program headers do not fit in e_phnum", count)
+            }
+            ElfError::FileTooLarge { size, limit } => write!(
+                f,
+                "headers place contents up to offset {:#x}, past the {:#x} bytes the file needs",
+                size, limit
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ElfError {}
@@ -0,0 +1,510 @@
+use crate::error::ElfError;
+use crate::header::{ElfHeader, ElfIdentification, Encoding, ELF_IDENTIFICATION_SIZE};
+use crate::program::ElfProgramHeader;
+use crate::reader::Reader;
+use crate::section::{ElfSectionHeader, SectionType};
+use crate::string_table::StringTable;
+use crate::symbol::{ElfSymbol, ElfSymbolEntry, SectionIndex, SHN_XINDEX};
+use std::cell::OnceCell;
+
+// 0x7f 'E' 'L' 'F'
+pub(crate) const HEADER_MAGIC: [u8; 4] = [0x7f, 0x45, 0x4c, 0x46];
+
+/// Elf file borrowed from a byte slice.
+///
+/// The identification and the elf header are decoded once in `new`,
+/// section headers on first use, and everything else is borrowed from `data`.
+pub struct ElfFile<'a> {
+    data: &'a [u8],
+    identification: ElfIdentification,
+    encoding: Encoding,
+    header: ElfHeader,
+    section_headers: OnceCell<Vec<ElfSectionHeader>>,
+}
+
+impl<'a> ElfFile<'a> {
+    pub fn new(data: &'a [u8]) -> Result<ElfFile<'a>, ElfError> {
+        let identification = ElfIdentification::new(data)?;
+        if identification.magic != HEADER_MAGIC {
+            return Err(ElfError::BadMagic {
+                offset: 0,
+                magic: identification.magic.to_vec(),
+            });
+        }
+        let encoding = identification.encoding()?;
+        let size = encoding.header_size();
+        let binary = data
+            .get(ELF_IDENTIFICATION_SIZE..ELF_IDENTIFICATION_SIZE + size)
+            .ok_or(ElfError::TruncatedHeader {
+                offset: ELF_IDENTIFICATION_SIZE as u64,
+                size: size as u64,
+                file_size: data.len() as u64,
+            })?;
+        let header = ElfHeader::new(binary, encoding);
+        Ok(ElfFile {
+            data,
+            identification,
+            encoding,
+            header,
+            section_headers: OnceCell::new(),
+        })
+    }
+
+    /// Whole file contents.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn get_identification(&self) -> &ElfIdentification {
+        &self.identification
+    }
+
+    pub fn get_encoding(&self) -> Encoding {
+        self.encoding
+    }
+
+    pub fn get_elf_header(&self) -> &ElfHeader {
+        &self.header
+    }
+
+    pub fn get_program_headers(&self) -> Result<Vec<ElfProgramHeader>, ElfError> {
+        let size = self.encoding.program_header_size() as u64;
+        let mut headers = Vec::<ElfProgramHeader>::new();
+        if self.header.e_phnum == 0 {
+            return Ok(headers);
+        }
+        let stride = entry_size(self.header.e_phoff, self.header.e_phentsize, size)?;
+        for i in 0..self.header.e_phnum as u64 {
+            let binary = self.get_table_entry(self.header.e_phoff, i, stride, size)?;
+            headers.push(ElfProgramHeader::new(binary, self.encoding));
+        }
+        Ok(headers)
+    }
+
+    pub fn get_section_headers(&self) -> Result<&[ElfSectionHeader], ElfError> {
+        if let Some(headers) = self.section_headers.get() {
+            return Ok(headers);
+        }
+        let size = self.encoding.section_header_size() as u64;
+        let count = self.get_section_count()?;
+        let mut headers = Vec::<ElfSectionHeader>::new();
+        let stride = match count {
+            0 => size,
+            _ => entry_size(self.header.e_shoff, self.header.e_shentsize, size)?,
+        };
+        for i in 0..count {
+            let binary = self.get_table_entry(self.header.e_shoff, i, stride, size)?;
+            headers.push(ElfSectionHeader::new(binary, self.encoding));
+        }
+        Ok(self.section_headers.get_or_init(|| headers))
+    }
+
+    /// Number of section headers.
+    ///
+    /// When there are `SHN_LORESERVE` or more sections, `e_shnum` is 0
+    /// and the real number is stored in `sh_size` of the section header 0.
+    pub fn get_section_count(&self) -> Result<u64, ElfError> {
+        if self.header.e_shnum == 0 && self.header.e_shoff != 0 {
+            return Ok(self.get_initial_section_header()?.sh_size);
+        }
+        Ok(self.header.e_shnum as u64)
+    }
+
+    /// Index of `.shstrtab` in the section headers.
+    ///
+    /// When it does not fit in `e_shstrndx`, `e_shstrndx` is `SHN_XINDEX`
+    /// and the real index is stored in `sh_link` of the section header 0.
+    pub fn get_section_name_table_index(&self) -> Result<usize, ElfError> {
+        if self.header.e_shstrndx == SHN_XINDEX {
+            return Ok(self.get_initial_section_header()?.sh_link as usize);
+        }
+        Ok(self.header.e_shstrndx as usize)
+    }
+
+    fn get_initial_section_header(&self) -> Result<ElfSectionHeader, ElfError> {
+        let size = self.encoding.section_header_size() as u64;
+        let binary = self.get_bytes(self.header.e_shoff, size)?;
+        Ok(ElfSectionHeader::new(binary, self.encoding))
+    }
+
+    pub fn get_section_names(&self) -> Result<Vec<&'a str>, ElfError> {
+        let section_headers = self.get_section_headers()?;
+        if section_headers.is_empty() {
+            return Ok(Vec::new());
+        }
+        let section_names = self.get_section_name_table()?;
+        section_headers
+            .iter()
+            .map(|header| section_names.get_str(header.sh_name as u64))
+            .collect()
+    }
+
+    pub fn get_section_name(&self, header: &ElfSectionHeader) -> Result<&'a str, ElfError> {
+        self.get_section_name_table()?
+            .get_str(header.sh_name as u64)
+    }
+
+    pub fn get_section_name_bytes(&self, header: &ElfSectionHeader) -> Result<&'a [u8], ElfError> {
+        self.get_section_name_table()?
+            .get_bytes(header.sh_name as u64)
+    }
+
+    /// `.shstrtab`, which holds the names of sections.
+    pub fn get_section_name_table(&self) -> Result<StringTable<'a>, ElfError> {
+        let header = self.get_section_header(self.get_section_name_table_index()?)?;
+        self.get_string_table(header)
+    }
+
+    pub fn get_string_table(&self, header: &ElfSectionHeader) -> Result<StringTable<'a>, ElfError> {
+        let data = self.get_section_data(header)?;
+        Ok(StringTable::new(data, header.sh_offset))
+    }
+
+    /// Symbols in `.symtab`, with names resolved through the string table in `sh_link`.
+    pub fn get_symbol_table(&self) -> Result<Vec<ElfSymbol<'a>>, ElfError> {
+        self.get_symbols_in_section(self.find_section_index(SectionType::Symtab)?)
+    }
+
+    /// Symbols in `.dynsym`, with names resolved through `.dynstr`.
+    pub fn get_dynamic_symbol_table(&self) -> Result<Vec<ElfSymbol<'a>>, ElfError> {
+        self.get_symbols_in_section(self.find_section_index(SectionType::Dynsym)?)
+    }
+
+    /// `.symtab` if the file has one, otherwise `.dynsym`, so stripped files still list symbols.
+    pub fn get_symbols(&self) -> Result<Vec<ElfSymbol<'a>>, ElfError> {
+        match self.find_section_index(SectionType::Symtab) {
+            Ok(index) => self.get_symbols_in_section(index),
+            Err(ElfError::MissingSection { .. }) => self.get_dynamic_symbol_table(),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Symbols in the symbol table section at `index` of the section headers.
+    /// Symbols of `.dynsym` carry their version from `.gnu.version`.
+    pub fn get_symbols_in_section(&self, index: usize) -> Result<Vec<ElfSymbol<'a>>, ElfError> {
+        let section_headers = self.get_section_headers()?;
+        let header = self.get_section_header(index)?;
+        let names = self.get_string_table(self.get_section_header(header.sh_link as usize)?)?;
+        // Section indexes that do not fit in st_shndx live in a parallel SHT_SYMTAB_SHNDX section.
+        let extended_indexes = match section_headers.iter().find(|shndx| {
+            shndx.sh_type == u32::from(SectionType::SymtabShndx) && shndx.sh_link as usize == index
+        }) {
+            Some(shndx) => Some((shndx.sh_offset, self.get_section_data(shndx)?)),
+            None => None,
+        };
+
+        let versions = self.get_symbol_versions(index)?;
+
+        let binary = self.get_section_data(header)?;
+        let mut symbol_table = Vec::<ElfSymbol<'a>>::new();
+        for (i, chunk) in binary
+            .chunks_exact(self.encoding.symbol_entry_size())
+            .enumerate()
+        {
+            let entry = ElfSymbolEntry::new(chunk, self.encoding);
+            let mut symbol = ElfSymbol::new(names.get_str(entry.st_name as u64)?, entry);
+            if let (SectionIndex::ExtendedIndex, Some((offset, data))) =
+                (symbol.section_index, extended_indexes)
+            {
+                let field = data
+                    .get(i * 4..i * 4 + 4)
+                    .ok_or(ElfError::OffsetOutOfRange {
+                        offset: offset + i as u64 * 4,
+                        size: 4,
+                        file_size: self.data.len() as u64,
+                    })?;
+                let index = Reader::new(field, self.encoding).read_u32();
+                symbol.section_index = SectionIndex::Index(index);
+            }
+            symbol.version = versions.get(i).copied().flatten();
+            symbol_table.push(symbol);
+        }
+        Ok(symbol_table)
+    }
+
+    pub(crate) fn find_section_index(&self, section_type: SectionType) -> Result<usize, ElfError> {
+        self.get_section_headers()?
+            .iter()
+            .position(|header| header.sh_type == u32::from(section_type))
+            .ok_or(ElfError::MissingSection {
+                offset: self.header.e_shoff,
+                section_type: u32::from(section_type),
+            })
+    }
+
+    pub fn get_section_header(&self, index: usize) -> Result<&ElfSectionHeader, ElfError> {
+        let headers = self.get_section_headers()?;
+        headers.get(index).ok_or(ElfError::BadSectionIndex {
+            offset: self.header.e_shoff,
+            index: index as u64,
+            count: headers.len() as u64,
+        })
+    }
+
+    /// Contents of the section, borrowed from the file.
+    /// `SHT_NOBITS` sections occupy no space in the file and yield an empty slice.
+    pub fn get_section_data(&self, header: &ElfSectionHeader) -> Result<&'a [u8], ElfError> {
+        if header.sh_type == u32::from(SectionType::Nobits) {
+            return Ok(&[]);
+        }
+        self.get_bytes(header.sh_offset, header.sh_size)
+    }
+
+    /// The `size` bytes of entry `index` in the header table at `offset`.
+    fn get_table_entry(
+        &self,
+        offset: u64,
+        index: u64,
+        stride: u64,
+        size: u64,
+    ) -> Result<&'a [u8], ElfError> {
+        let start = index
+            .checked_mul(stride)
+            .and_then(|distance| offset.checked_add(distance))
+            .ok_or(ElfError::OffsetOutOfRange {
+                offset,
+                size: index.saturating_mul(stride).saturating_add(size),
+                file_size: self.data.len() as u64,
+            })?;
+        self.get_bytes(start, size)
+    }
+
+    /// Bytes in `offset..offset + size` of the file.
+    pub fn get_bytes(&self, offset: u64, size: u64) -> Result<&'a [u8], ElfError> {
+        let out_of_range = ElfError::OffsetOutOfRange {
+            offset,
+            size,
+            file_size: self.data.len() as u64,
+        };
+        let end = match offset.checked_add(size) {
+            Some(end) if end <= self.data.len() as u64 => end,
+            _ => return Err(out_of_range),
+        };
+        Ok(&self.data[offset as usize..end as usize])
+    }
+}
+
+/// Distance between the entries of the header table at `offset`, which may be padded past
+/// the `size` bytes of a header but not cut short.
+fn entry_size(offset: u64, entry_size: u16, size: u64) -> Result<u64, ElfError> {
+    let entry_size = entry_size as u64;
+    if entry_size < size {
+        return Err(ElfError::BadEntrySize {
+            offset,
+            entry_size,
+            size,
+        });
+    }
+    Ok(entry_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::{Class, ElfType, Endian, Machine};
+    use crate::program::ProgramType;
+    use crate::testing::{file_with_segment, section_header, ELF64_LE};
+    use crate::writer::{ElfWriter, WriterSymbol};
+
+    const E_PHENTSIZE: usize = 42;
+    const E_SHENTSIZE: usize = 46;
+
+    fn half(binary: &mut Vec<u8>, value: u16) {
+        binary.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn words(binary: &mut Vec<u8>, values: &[u32]) {
+        for value in values.iter() {
+            binary.extend_from_slice(&value.to_be_bytes());
+        }
+    }
+
+    /// A big-endian ELF32 executable, written out by hand, with 4 bytes of padding after its
+    /// program header and 8 bytes after each section header.
+    fn padded_elf32_big_endian() -> Vec<u8> {
+        let mut binary = vec![0x7f, b'E', b'L', b'F', 1, 2, 1];
+        binary.resize(16, 0);
+        half(&mut binary, 2); // e_type
+        half(&mut binary, 8); // e_machine
+        words(&mut binary, &[1, 0x1_006c, 0x34, 0x70, 0]);
+        for value in [52, 36, 1, 48, 3, 1].iter() {
+            half(&mut binary, *value);
+        }
+        // 0x34: PT_LOAD covering the whole file.
+        words(
+            &mut binary,
+            &[1, 0, 0x1_0000, 0x1_0000, 0x70, 0x70, 5, 0x1_0000],
+        );
+        binary.extend_from_slice(&[0xee; 4]);
+        // 0x58: .shstrtab, then .text at 0x6c.
+        binary.extend_from_slice(b"\0.shstrtab\0.text\0\0\0\0");
+        binary.extend_from_slice(&[0x24, 0x02, 0x00, 0x2a]);
+        // 0x70: section headers.
+        for header in [
+            [0; 10],
+            [1, 3, 0, 0, 0x58, 17, 0, 0, 1, 0],
+            [11, 1, 6, 0x1_006c, 0x6c, 4, 0, 0, 4, 0],
+        ]
+        .iter()
+        {
+            words(&mut binary, header);
+            binary.extend_from_slice(&[0xee; 8]);
+        }
+        binary
+    }
+
+    #[test]
+    fn reads_header_tables_with_padded_entries() {
+        let binary = padded_elf32_big_endian();
+        let file = ElfFile::new(&binary).unwrap();
+        let encoding = file.get_encoding();
+        assert_eq!(encoding.class, Class::Elf32);
+        assert_eq!(encoding.endian, Endian::Big);
+        assert_eq!(file.get_elf_header().elf_type(), ElfType::Exec);
+        assert_eq!(file.get_elf_header().machine(), Machine::Mips);
+
+        let segments = file.get_program_headers().unwrap();
+        assert_eq!(segments.len(), 1);
+        assert_eq!(ProgramType::from(segments[0].p_type), ProgramType::Load);
+        assert_eq!(segments[0].p_vaddr, 0x1_0000);
+        assert_eq!(segments[0].p_flags, 5);
+        assert_eq!(segments[0].to_bytes(encoding), &binary[0x34..0x54]);
+
+        assert_eq!(
+            file.get_section_names().unwrap(),
+            ["", ".shstrtab", ".text"]
+        );
+        let sections = file.get_section_headers().unwrap();
+        assert_eq!(sections[2].sh_addr, 0x1_006c);
+        assert_eq!(
+            file.get_section_data(&sections[2]).unwrap(),
+            &[0x24, 0x02, 0x00, 0x2a]
+        );
+        for (i, section) in sections.iter().enumerate() {
+            let offset = 0x70 + i * 48;
+            assert_eq!(section.to_bytes(encoding), &binary[offset..offset + 40]);
+        }
+        let output = ElfWriter::from_file(&file).unwrap().to_bytes().unwrap();
+        assert_eq!(output, binary);
+    }
+
+    #[test]
+    fn rejects_entries_smaller_than_a_header() {
+        let mut binary = padded_elf32_big_endian();
+        binary[E_PHENTSIZE + 1] = 31;
+        binary[E_SHENTSIZE + 1] = 39;
+        let file = ElfFile::new(&binary).unwrap();
+        assert!(matches!(
+            file.get_program_headers(),
+            Err(ElfError::BadEntrySize {
+                offset: 0x34,
+                entry_size: 31,
+                size: 32,
+            })
+        ));
+        assert!(matches!(
+            file.get_section_headers(),
+            Err(ElfError::BadEntrySize {
+                offset: 0x70,
+                entry_size: 39,
+                size: 40,
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_tables_past_the_largest_offset() {
+        let mut binary = file_with_segment(ElfType::Exec, 0x1000, b"abcd", 4);
+        // Both tables end past the last 64-bit offset.
+        binary[32..40].copy_from_slice(&(u64::MAX - 0x10).to_le_bytes());
+        binary[40..48].copy_from_slice(&(u64::MAX - 0x10).to_le_bytes());
+        binary[56..58].copy_from_slice(&2u16.to_le_bytes());
+        let file = ElfFile::new(&binary).unwrap();
+        assert!(file.get_section_count().unwrap() > 1);
+        assert!(matches!(
+            file.get_program_headers(),
+            Err(ElfError::OffsetOutOfRange { .. })
+        ));
+        assert!(matches!(
+            file.get_section_headers(),
+            Err(ElfError::OffsetOutOfRange { .. })
+        ));
+        assert!(matches!(
+            file.get_table_entry(u64::MAX - 0x10, 2, 56, 56),
+            Err(ElfError::OffsetOutOfRange { .. })
+        ));
+        assert!(matches!(
+            file.get_table_entry(0x40, u64::MAX / 2, 56, 56),
+            Err(ElfError::OffsetOutOfRange { .. })
+        ));
+    }
+
+    /// A relocatable file that keeps its section count and `.shstrtab` index in section 0,
+    /// as if they did not fit in the elf header, with `big` defined through
+    /// `SHT_SYMTAB_SHNDX`.
+    fn extended_indexes() -> Vec<u8> {
+        let mut writer = ElfWriter::new(ELF64_LE, ElfType::Rel, Machine::X86_64);
+        let data = writer.add_section(".data", section_header(SectionType::Progbits), vec![7; 8]);
+        writer.symbols.push(WriterSymbol {
+            name: "big".into(),
+            entry: ElfSymbolEntry {
+                st_name: 0,
+                st_info: 0x11,
+                st_other: 0,
+                st_shndx: SHN_XINDEX,
+                st_value: 4,
+                st_size: 4,
+            },
+        });
+        writer.to_bytes().unwrap();
+        let symtab = writer.find_section(".symtab").unwrap();
+        let mut indexes = vec![0; 4];
+        indexes.extend_from_slice(&(data as u32).to_le_bytes());
+        writer.add_section(
+            ".symtab_shndx",
+            ElfSectionHeader {
+                sh_link: symtab as u32,
+                sh_entsize: 4,
+                ..section_header(SectionType::SymtabShndx)
+            },
+            indexes,
+        );
+        writer.to_bytes().unwrap();
+
+        writer.keep_layout = true;
+        let count = writer.sections.len();
+        let null = &mut writer.sections[0].header;
+        null.sh_size = count as u64;
+        null.sh_link = writer.header.e_shstrndx as u32;
+        writer.header.e_shnum = 0;
+        writer.header.e_shstrndx = SHN_XINDEX;
+        writer.to_bytes().unwrap()
+    }
+
+    #[test]
+    fn reads_extended_section_indexes() {
+        let binary = extended_indexes();
+        let file = ElfFile::new(&binary).unwrap();
+        assert_eq!(file.get_elf_header().e_shnum, 0);
+        assert_eq!(file.get_section_count().unwrap(), 6);
+        let names = file.get_section_names().unwrap();
+        assert_eq!(
+            names[file.get_section_name_table_index().unwrap()],
+            ".shstrtab"
+        );
+        assert_eq!(
+            names,
+            [
+                "",
+                ".data",
+                ".symtab",
+                ".strtab",
+                ".shstrtab",
+                ".symtab_shndx"
+            ]
+        );
+        let symbols = file.get_symbol_table().unwrap();
+        assert_eq!(symbols[1].name, "big");
+        assert_eq!(symbols[1].section_index, SectionIndex::Index(1));
+    }
+}
@@ -0,0 +1,454 @@
+use crate::dynamic::DynamicTag;
+use crate::error::ElfError;
+use crate::file::ElfFile;
+use crate::header::Encoding;
+use crate::reader::Reader;
+use crate::section::{ElfSectionHeader, SectionType};
+use crate::string_table::StringTable;
+use crate::symbol::{ElfSymbol, ElfSymbolEntry, SectionIndex};
+
+/// Hash of `name` used in `SHT_HASH`.
+pub fn sysv_hash(name: &[u8]) -> u32 {
+    let mut hash: u32 = 0;
+    for b in name.iter() {
+        hash = (hash << 4).wrapping_add(*b as u32);
+        let high = hash & 0xf000_0000;
+        if high != 0 {
+            hash ^= high >> 24;
+        }
+        hash &= !high;
+    }
+    hash
+}
+
+/// Hash of `name` used in `SHT_GNU_HASH`.
+pub fn gnu_hash(name: &[u8]) -> u32 {
+    name.iter().fold(5381u32, |hash, b| {
+        hash.wrapping_mul(33).wrapping_add(*b as u32)
+    })
+}
+
+/// Part of the file read as an array, with the file offset kept for errors.
+#[derive(Debug, Clone, Copy)]
+struct Table<'a> {
+    data: &'a [u8],
+    offset: u64,
+}
+
+impl<'a> Table<'a> {
+    fn get(&self, start: usize, size: usize) -> Result<&'a [u8], ElfError> {
+        self.data
+            .get(start..start + size)
+            .ok_or(ElfError::OffsetOutOfRange {
+                offset: self.offset + start as u64,
+                size: size as u64,
+                file_size: self.offset + self.data.len() as u64,
+            })
+    }
+
+    fn split_at(&self, start: usize) -> Result<(Table<'a>, Table<'a>), ElfError> {
+        let head = self.get(0, start)?;
+        Ok((
+            Table {
+                data: head,
+                offset: self.offset,
+            },
+            Table {
+                data: &self.data[start..],
+                offset: self.offset + start as u64,
+            },
+        ))
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum HashKind<'a> {
+    Sysv {
+        nchain: u32,
+        buckets: Table<'a>,
+        chains: Table<'a>,
+    },
+    Gnu {
+        symoffset: u32,
+        bloom_shift: u32,
+        bloom: Table<'a>,
+        buckets: Table<'a>,
+        chains: Table<'a>,
+    },
+}
+
+/// `SHT_GNU_HASH` or `SHT_HASH` together with the symbol and string tables it indexes.
+///
+/// Looks up symbols by name without reading the whole symbol table.
+#[derive(Debug, Clone, Copy)]
+pub struct SymbolHashTable<'a> {
+    kind: HashKind<'a>,
+    symbols: Table<'a>,
+    names: StringTable<'a>,
+    encoding: Encoding,
+}
+
+impl<'a> SymbolHashTable<'a> {
+    pub fn is_gnu(&self) -> bool {
+        matches!(self.kind, HashKind::Gnu { .. })
+    }
+
+    /// Defined symbol called `name`, as a dynamic loader would resolve it.
+    /// Versions are not compared, so the first definition in the chain wins.
+    pub fn lookup(&self, name: &str) -> Result<Option<ElfSymbol<'a>>, ElfError> {
+        let name = name.as_bytes();
+        match self.kind {
+            HashKind::Sysv {
+                nchain,
+                buckets,
+                chains,
+            } => {
+                let nbucket = buckets.data.len() / 4;
+                let hash = sysv_hash(name) as usize;
+                let mut index = self.read_u32(buckets, hash % nbucket)?;
+                // Every symbol is in one chain, so a longer walk means the chains loop.
+                for _ in 0..nchain {
+                    if index == 0 {
+                        break;
+                    }
+                    if let Some(symbol) = self.match_symbol(index, name)? {
+                        return Ok(Some(symbol));
+                    }
+                    index = self.read_u32(chains, index as usize)?;
+                }
+                Ok(None)
+            }
+            HashKind::Gnu {
+                symoffset,
+                bloom_shift,
+                bloom,
+                buckets,
+                chains,
+            } => {
+                let hash = gnu_hash(name);
+                if !self.bloom_contains(bloom, bloom_shift, hash)? {
+                    return Ok(None);
+                }
+                let nbuckets = buckets.data.len() / 4;
+                let mut index = self.read_u32(buckets, hash as usize % nbuckets)?;
+                if index < symoffset {
+                    return Ok(None);
+                }
+                loop {
+                    let chain_hash = self.read_u32(chains, (index - symoffset) as usize)?;
+                    if chain_hash | 1 == hash | 1 {
+                        if let Some(symbol) = self.match_symbol(index, name)? {
+                            return Ok(Some(symbol));
+                        }
+                    }
+                    if chain_hash & 1 != 0 {
+                        return Ok(None);
+                    }
+                    index += 1;
+                }
+            }
+        }
+    }
+
+    /// Number of entries in the symbol table, which `SHT_GNU_HASH` only gives by walking its chains.
+    pub fn symbol_count(&self) -> Result<usize, ElfError> {
+        match self.kind {
+            HashKind::Sysv { nchain, .. } => Ok(nchain as usize),
+            HashKind::Gnu {
+                symoffset,
+                buckets,
+                chains,
+                ..
+            } => {
+                let mut last = 0;
+                for i in 0..buckets.data.len() / 4 {
+                    last = last.max(self.read_u32(buckets, i)?);
+                }
+                if last < symoffset {
+                    return Ok(symoffset as usize);
+                }
+                while self.read_u32(chains, (last - symoffset) as usize)? & 1 == 0 {
+                    last += 1;
+                }
+                Ok(last as usize + 1)
+            }
+        }
+    }
+
+    /// Symbol at `index`, read on its own.
+    pub fn get_symbol(&self, index: u32) -> Result<ElfSymbol<'a>, ElfError> {
+        let size = self.encoding.symbol_entry_size();
+        let binary = self.symbols.get(index as usize * size, size)?;
+        let entry = ElfSymbolEntry::new(binary, self.encoding);
+        Ok(ElfSymbol::new(
+            self.names.get_str(entry.st_name as u64)?,
+            entry,
+        ))
+    }
+
+    fn match_symbol(&self, index: u32, name: &[u8]) -> Result<Option<ElfSymbol<'a>>, ElfError> {
+        let size = self.encoding.symbol_entry_size();
+        let binary = self.symbols.get(index as usize * size, size)?;
+        let entry = ElfSymbolEntry::new(binary, self.encoding);
+        if entry.section_index() == SectionIndex::Undefined
+            || self.names.get_bytes(entry.st_name as u64)? != name
+        {
+            return Ok(None);
+        }
+        Ok(Some(ElfSymbol::new(
+            self.names.get_str(entry.st_name as u64)?,
+            entry,
+        )))
+    }
+
+    /// The bloom filter sets two bits per symbol in one word. Missing either means no match.
+    fn bloom_contains(&self, bloom: Table, bloom_shift: u32, hash: u32) -> Result<bool, ElfError> {
+        let word_size = self.encoding.word_size();
+        let word_bits = word_size as u32 * 8;
+        let count = bloom.data.len() / word_size;
+        let index = (hash / word_bits) as usize % count;
+        let word = Reader::new(bloom.get(index * word_size, word_size)?, self.encoding).read_word();
+        let mut mask = 1u64 << (hash % word_bits);
+        // A shift as wide as the hash leaves no second bit to test, so it may be present.
+        if let Some(second) = hash.checked_shr(bloom_shift) {
+            mask |= 1u64 << (second % word_bits);
+        }
+        Ok(word & mask == mask)
+    }
+
+    fn read_u32(&self, table: Table, index: usize) -> Result<u32, ElfError> {
+        Ok(Reader::new(table.get(index * 4, 4)?, self.encoding).read_u32())
+    }
+}
+
+impl<'a> ElfFile<'a> {
+    /// Hash table of the dynamic symbols, `SHT_GNU_HASH` preferred over `SHT_HASH`.
+    ///
+    /// Found through `DT_GNU_HASH` or `DT_HASH` when the file has no section headers.
+    pub fn get_hash_table(&self) -> Result<SymbolHashTable<'a>, ElfError> {
+        for section_type in [SectionType::GnuHash, SectionType::Hash].iter() {
+            match self.find_section_index(*section_type) {
+                Ok(index) => {
+                    return self.get_hash_table_in_section(self.get_section_header(index)?)
+                }
+                Err(ElfError::MissingSection { .. }) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        let dynamic = match self.get_dynamic() {
+            Ok(dynamic) => dynamic,
+            Err(ElfError::MissingSection { .. }) => {
+                return Err(ElfError::MissingSection {
+                    offset: self.get_elf_header().e_shoff,
+                    section_type: u32::from(SectionType::Hash),
+                })
+            }
+            Err(error) => return Err(error),
+        };
+        let (hash_type, address) = match (
+            dynamic.get_value(DynamicTag::GnuHash),
+            dynamic.get_value(DynamicTag::Hash),
+        ) {
+            (Some(address), _) => (SectionType::GnuHash, address),
+            (None, Some(address)) => (SectionType::Hash, address),
+            (None, None) => {
+                return Err(ElfError::MissingDynamicEntry {
+                    tag: i64::from(DynamicTag::Hash),
+                })
+            }
+        };
+        let symbols_address =
+            dynamic
+                .get_value(DynamicTag::Symtab)
+                .ok_or(ElfError::MissingDynamicEntry {
+                    tag: i64::from(DynamicTag::Symtab),
+                })?;
+        let names = dynamic.strings.ok_or(ElfError::MissingDynamicEntry {
+            tag: i64::from(DynamicTag::Strtab),
+        })?;
+        self.new_hash_table(
+            hash_type,
+            self.get_table_at(address)?,
+            self.get_table_at(symbols_address)?,
+            names,
+        )
+    }
+
+    /// Defined dynamic symbol called `name`, found through the hash table.
+    pub fn lookup_symbol(&self, name: &str) -> Result<Option<ElfSymbol<'a>>, ElfError> {
+        self.get_hash_table()?.lookup(name)
+    }
+
+    fn get_hash_table_in_section(
+        &self,
+        header: &ElfSectionHeader,
+    ) -> Result<SymbolHashTable<'a>, ElfError> {
+        let symbols = self.get_section_header(header.sh_link as usize)?;
+        let names = self.get_string_table(self.get_section_header(symbols.sh_link as usize)?)?;
+        self.new_hash_table(
+            SectionType::from(header.sh_type),
+            Table {
+                data: self.get_section_data(header)?,
+                offset: header.sh_offset,
+            },
+            Table {
+                data: self.get_section_data(symbols)?,
+                offset: symbols.sh_offset,
+            },
+            names,
+        )
+    }
+
+    /// Everything from `address` to the end of the file, for tables whose size is only known
+    /// after reading them.
+    fn get_table_at(&self, address: u64) -> Result<Table<'a>, ElfError> {
+        let offset = self.vaddr_to_offset(address)?;
+        Ok(Table {
+            data: self.get_bytes(offset, self.data().len() as u64 - offset)?,
+            offset,
+        })
+    }
+
+    fn new_hash_table(
+        &self,
+        hash_type: SectionType,
+        table: Table<'a>,
+        symbols: Table<'a>,
+        names: StringTable<'a>,
+    ) -> Result<SymbolHashTable<'a>, ElfError> {
+        let encoding = self.get_encoding();
+        let kind = if hash_type == SectionType::GnuHash {
+            let mut reader = Reader::new(table.get(0, 16)?, encoding);
+            let nbuckets = reader.read_u32() as usize;
+            let symoffset = reader.read_u32();
+            let bloom_size = reader.read_u32() as usize;
+            let bloom_shift = reader.read_u32();
+            let (_, rest) = table.split_at(16)?;
+            let (bloom, rest) = rest.split_at(bloom_size * encoding.word_size())?;
+            let (buckets, chains) = rest.split_at(nbuckets * 4)?;
+            if bloom_size == 0 || nbuckets == 0 {
+                return Err(ElfError::OffsetOutOfRange {
+                    offset: table.offset,
+                    size: 16,
+                    file_size: self.data().len() as u64,
+                });
+            }
+            HashKind::Gnu {
+                symoffset,
+                bloom_shift,
+                bloom,
+                buckets,
+                chains,
+            }
+        } else {
+            let mut reader = Reader::new(table.get(0, 8)?, encoding);
+            let nbucket = reader.read_u32() as usize;
+            let nchain = reader.read_u32();
+            let (_, rest) = table.split_at(8)?;
+            let (buckets, rest) = rest.split_at(nbucket * 4)?;
+            let (chains, _) = rest.split_at(nchain as usize * 4)?;
+            if nbucket == 0 {
+                return Err(ElfError::OffsetOutOfRange {
+                    offset: table.offset,
+                    size: 8,
+                    file_size: self.data().len() as u64,
+                });
+            }
+            HashKind::Sysv {
+                nchain,
+                buckets,
+                chains,
+            }
+        };
+        Ok(SymbolHashTable {
+            kind,
+            symbols,
+            names,
+            encoding,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::{ElfType, Machine};
+    use crate::symbol::SHN_ABS;
+    use crate::testing::{section_header, ELF64_LE as ENCODING};
+    use crate::writer::ElfWriter;
+
+    fn header(sh_type: SectionType, sh_link: u32, sh_entsize: u64) -> ElfSectionHeader {
+        ElfSectionHeader {
+            sh_link,
+            sh_addralign: 8,
+            sh_entsize,
+            ..section_header(sh_type)
+        }
+    }
+
+    /// A file defining `foo` and `bar` in `.dynsym`, with a `.gnu.hash` of one bucket whose
+    /// bloom filter has the bits of the hashes shifted by `bloom_shift`.
+    fn file_with_gnu_hash(bloom_shift: u32) -> Vec<u8> {
+        let names = ["foo", "bar"];
+        let mut strings = vec![0u8];
+        let mut symbols = vec![0u8; ENCODING.symbol_entry_size()];
+        let mut bloom = 0u64;
+        let mut chains = Vec::new();
+        for (i, name) in names.iter().enumerate() {
+            let entry = ElfSymbolEntry {
+                st_name: strings.len() as u32,
+                st_info: 0x11,
+                st_other: 0,
+                st_shndx: SHN_ABS,
+                st_value: 0x1000 * (i as u64 + 1),
+                st_size: 0,
+            };
+            strings.extend_from_slice(name.as_bytes());
+            strings.push(0);
+            symbols.extend_from_slice(&entry.to_bytes(ENCODING));
+            let hash = gnu_hash(name.as_bytes());
+            bloom |= 1 << (hash % 64);
+            if let Some(second) = hash.checked_shr(bloom_shift) {
+                bloom |= 1 << (second % 64);
+            }
+            let last = i == names.len() - 1;
+            chains.push(if last { hash | 1 } else { hash & !1 });
+        }
+        let mut table = Vec::new();
+        for value in [1u32, 1, 1, bloom_shift].iter() {
+            table.extend_from_slice(&value.to_le_bytes());
+        }
+        table.extend_from_slice(&bloom.to_le_bytes());
+        table.extend_from_slice(&1u32.to_le_bytes());
+        for chain in chains.iter() {
+            table.extend_from_slice(&chain.to_le_bytes());
+        }
+
+        let mut writer = ElfWriter::new(ENCODING, ElfType::Dyn, Machine::X86_64);
+        writer.add_section(".dynstr", header(SectionType::Strtab, 0, 0), strings);
+        writer.add_section(".dynsym", header(SectionType::Dynsym, 1, 24), symbols);
+        writer.add_section(".gnu.hash", header(SectionType::GnuHash, 2, 0), table);
+        writer.to_bytes().unwrap()
+    }
+
+    #[test]
+    fn looks_up_through_the_bloom_filter() {
+        for bloom_shift in [0, 5, 26, 31, 32, 63, 64, u32::MAX].iter() {
+            let binary = file_with_gnu_hash(*bloom_shift);
+            let file = ElfFile::new(&binary).unwrap();
+            let table = file.get_hash_table().unwrap();
+            assert!(table.is_gnu());
+            for (name, value) in [("foo", 0x1000), ("bar", 0x2000)].iter() {
+                let symbol = table.lookup(name).unwrap();
+                assert_eq!(
+                    symbol.map(|symbol| symbol.entry.st_value),
+                    Some(*value),
+                    "{} with shift {}",
+                    name,
+                    bloom_shift
+                );
+            }
+            assert!(table.lookup("baz").unwrap().is_none());
+        }
+    }
+}
@@ -0,0 +1,733 @@
+use crate::error::ElfError;
+use crate::file::HEADER_MAGIC;
+use crate::reader::Reader;
+use crate::writer::Writer;
+use std::fmt;
+
+/// File identification in elf header.
+#[derive(Debug, Clone)]
+pub struct ElfIdentification {
+    pub magic: [u8; 4],
+    pub class: u8,
+    pub endianess: u8,
+    pub version: u8,
+    pub os_abi: u8,
+    pub os_abi_version: u8,
+    pub reserved: [u8; 7], // zero filled.
+}
+
+impl fmt::Display for ElfIdentification {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "---ElfIdentification---
+Class       = {}
+Data        = {}
+Version     = {}
+OS/ABI      = {}
+ABI Version = {}",
+            self.class, self.endianess, self.version, self.os_abi, self.os_abi_version
+        )
+    }
+}
+
+impl ElfIdentification {
+    /// Word size of the file, decoded from `EI_CLASS`.
+    pub fn elf_class(&self) -> Result<Class, ElfError> {
+        match self.class {
+            ELFCLASS32 => Ok(Class::Elf32),
+            ELFCLASS64 => Ok(Class::Elf64),
+            value => Err(ElfError::BadClass { offset: 4, value }),
+        }
+    }
+
+    /// Byte order of the rest of the file, decoded from `EI_DATA`.
+    pub fn endian(&self) -> Result<Endian, ElfError> {
+        match self.endianess {
+            ELFDATA2LSB => Ok(Endian::Little),
+            ELFDATA2MSB => Ok(Endian::Big),
+            value => Err(ElfError::BadEncoding { offset: 5, value }),
+        }
+    }
+
+    pub fn encoding(&self) -> Result<Encoding, ElfError> {
+        Ok(Encoding {
+            class: self.elf_class()?,
+            endian: self.endian()?,
+        })
+    }
+
+    pub fn new(binary: &[u8]) -> Result<ElfIdentification, ElfError> {
+        if binary.len() < ELF_IDENTIFICATION_SIZE {
+            return Err(ElfError::TruncatedHeader {
+                offset: 0,
+                size: ELF_IDENTIFICATION_SIZE as u64,
+                file_size: binary.len() as u64,
+            });
+        }
+        let mut magic: [u8; 4] = [0; 4];
+        magic.copy_from_slice(&binary[0..4]);
+        let mut reserved: [u8; 7] = [0; 7];
+        reserved.copy_from_slice(&binary[9..ELF_IDENTIFICATION_SIZE]);
+        Ok(ElfIdentification {
+            magic,
+            class: binary[4],
+            endianess: binary[5],
+            version: binary[6],
+            os_abi: binary[7],
+            os_abi_version: binary[8],
+            reserved,
+        })
+    }
+
+    /// Identification for a new file of `encoding`, with the current version and the System V ABI.
+    pub fn for_encoding(encoding: Encoding) -> ElfIdentification {
+        ElfIdentification {
+            magic: HEADER_MAGIC,
+            class: match encoding.class {
+                Class::Elf32 => ELFCLASS32,
+                Class::Elf64 => ELFCLASS64,
+            },
+            endianess: match encoding.endian {
+                Endian::Little => ELFDATA2LSB,
+                Endian::Big => ELFDATA2MSB,
+            },
+            version: EV_CURRENT,
+            os_abi: 0,
+            os_abi_version: 0,
+            reserved: [0; 7],
+        }
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut binary = Vec::with_capacity(ELF_IDENTIFICATION_SIZE);
+        binary.extend_from_slice(&self.magic);
+        binary.extend_from_slice(&[
+            self.class,
+            self.endianess,
+            self.version,
+            self.os_abi,
+            self.os_abi_version,
+        ]);
+        binary.extend_from_slice(&self.reserved);
+        binary
+    }
+}
+
+const ELFCLASS32: u8 = 1;
+const ELFCLASS64: u8 = 2;
+const ELFDATA2LSB: u8 = 1;
+const ELFDATA2MSB: u8 = 2;
+const EV_CURRENT: u8 = 1;
+
+/// Byte order of multi-byte fields in an elf file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endian {
+    Little,
+    Big,
+}
+
+/// Word size of an elf file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Class {
+    Elf32,
+    Elf64,
+}
+
+/// Class and byte order, which together decide the on-disk layout of every structure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Encoding {
+    pub class: Class,
+    pub endian: Endian,
+}
+
+impl Encoding {
+    /// Size of an address or `long`: 4 in ELF32 and 8 in ELF64.
+    pub fn word_size(&self) -> usize {
+        match self.class {
+            Class::Elf32 => 4,
+            Class::Elf64 => 8,
+        }
+    }
+
+    pub fn header_size(&self) -> usize {
+        match self.class {
+            Class::Elf32 => ELF32_HEADER_SIZE,
+            Class::Elf64 => ELF64_HEADER_SIZE,
+        }
+    }
+
+    pub fn program_header_size(&self) -> usize {
+        match self.class {
+            Class::Elf32 => ELF32_PROGRAM_HEADER_SIZE,
+            Class::Elf64 => ELF64_PROGRAM_HEADER_SIZE,
+        }
+    }
+
+    pub fn section_header_size(&self) -> usize {
+        match self.class {
+            Class::Elf32 => ELF32_SECTION_HEADER_SIZE,
+            Class::Elf64 => ELF64_SECTION_HEADER_SIZE,
+        }
+    }
+
+    pub fn symbol_entry_size(&self) -> usize {
+        match self.class {
+            Class::Elf32 => ELF32_SYMBOL_ENTRY_SIZE,
+            Class::Elf64 => ELF64_SYMBOL_ENTRY_SIZE,
+        }
+    }
+
+    pub fn rel_entry_size(&self) -> usize {
+        match self.class {
+            Class::Elf32 => ELF32_REL_ENTRY_SIZE,
+            Class::Elf64 => ELF64_REL_ENTRY_SIZE,
+        }
+    }
+
+    pub fn rela_entry_size(&self) -> usize {
+        match self.class {
+            Class::Elf32 => ELF32_RELA_ENTRY_SIZE,
+            Class::Elf64 => ELF64_RELA_ENTRY_SIZE,
+        }
+    }
+
+    pub fn dynamic_entry_size(&self) -> usize {
+        match self.class {
+            Class::Elf32 => ELF32_DYNAMIC_ENTRY_SIZE,
+            Class::Elf64 => ELF64_DYNAMIC_ENTRY_SIZE,
+        }
+    }
+}
+
+/// File identification in elf header.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfHeader {
+    pub e_type: u16,
+    pub e_machine: u16,
+    pub e_version: u32,
+    pub e_entry: u64,
+    pub e_phoff: u64,
+    pub e_shoff: u64,
+    pub e_flags: u32,
+    pub e_ehsize: u16,
+    pub e_phentsize: u16,
+    pub e_phnum: u16,
+    pub e_shentsize: u16,
+    pub e_shnum: u16,
+    pub e_shstrndx: u16,
+}
+
+impl fmt::Display for ElfHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "---ElfHeader---
+Type       = {} ({:#x})
+Machine    = {} ({:#x})
+Version    = {}
+Entry      = {}
+P Offset   = {}
+S Offset   = {}
+Flags      = {}
+Entry Size = {}
+P Size     = {}
+P Number   = {}
+S Size     = {}
+S Number   = {}
+Index      = {}",
+            self.elf_type(),
+            self.e_type,
+            self.machine(),
+            self.e_machine,
+            self.e_version,
+            self.e_entry,
+            self.e_phoff,
+            self.e_shoff,
+            self.e_flags,
+            self.e_ehsize,
+            self.e_phentsize,
+            self.e_phnum,
+            self.e_shentsize,
+            self.e_shnum,
+            self.e_shstrndx,
+        )
+    }
+}
+
+impl ElfHeader {
+    pub fn elf_type(&self) -> ElfType {
+        ElfType::from(self.e_type)
+    }
+
+    pub fn machine(&self) -> Machine {
+        Machine::from(self.e_machine)
+    }
+
+    pub fn new(binary: &[u8], encoding: Encoding) -> ElfHeader {
+        let mut reader = Reader::new(binary, encoding);
+        ElfHeader {
+            e_type: reader.read_u16(),
+            e_machine: reader.read_u16(),
+            e_version: reader.read_u32(),
+            e_entry: reader.read_word(),
+            e_phoff: reader.read_word(),
+            e_shoff: reader.read_word(),
+            e_flags: reader.read_u32(),
+            e_ehsize: reader.read_u16(),
+            e_phentsize: reader.read_u16(),
+            e_phnum: reader.read_u16(),
+            e_shentsize: reader.read_u16(),
+            e_shnum: reader.read_u16(),
+            e_shstrndx: reader.read_u16(),
+        }
+    }
+
+    /// Fields after the identification, laid out as `new` reads them.
+    pub fn to_bytes(&self, encoding: Encoding) -> Vec<u8> {
+        let mut writer = Writer::new(encoding);
+        writer.write_u16(self.e_type);
+        writer.write_u16(self.e_machine);
+        writer.write_u32(self.e_version);
+        writer.write_word(self.e_entry);
+        writer.write_word(self.e_phoff);
+        writer.write_word(self.e_shoff);
+        writer.write_u32(self.e_flags);
+        writer.write_u16(self.e_ehsize);
+        writer.write_u16(self.e_phentsize);
+        writer.write_u16(self.e_phnum);
+        writer.write_u16(self.e_shentsize);
+        writer.write_u16(self.e_shnum);
+        writer.write_u16(self.e_shstrndx);
+        writer.into_bytes()
+    }
+}
+
+/// Object file type in `e_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfType {
+    None,
+    Rel,
+    Exec,
+    Dyn,
+    Core,
+    /// Operating system specific range `ET_LOOS..=ET_HIOS`.
+    Os(u16),
+    /// Processor specific range `ET_LOPROC..=ET_HIPROC`.
+    Processor(u16),
+    Unknown(u16),
+}
+
+impl From<u16> for ElfType {
+    fn from(value: u16) -> ElfType {
+        match value {
+            0 => ElfType::None,
+            1 => ElfType::Rel,
+            2 => ElfType::Exec,
+            3 => ElfType::Dyn,
+            4 => ElfType::Core,
+            0xfe00..=0xfeff => ElfType::Os(value),
+            0xff00..=0xffff => ElfType::Processor(value),
+            value => ElfType::Unknown(value),
+        }
+    }
+}
+
+impl From<ElfType> for u16 {
+    fn from(value: ElfType) -> u16 {
+        match value {
+            ElfType::None => 0,
+            ElfType::Rel => 1,
+            ElfType::Exec => 2,
+            ElfType::Dyn => 3,
+            ElfType::Core => 4,
+            ElfType::Os(value) | ElfType::Processor(value) | ElfType::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for ElfType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ElfType::None => write!(f, "NONE"),
+            ElfType::Rel => write!(f, "REL"),
+            ElfType::Exec => write!(f, "EXEC"),
+            ElfType::Dyn => write!(f, "DYN"),
+            ElfType::Core => write!(f, "CORE"),
+            ElfType::Os(value) => write!(f, "LOOS+{:#x}", value - 0xfe00),
+            ElfType::Processor(value) => write!(f, "LOPROC+{:#x}", value - 0xff00),
+            ElfType::Unknown(value) => write!(f, "<unknown>: {:#x}", value),
+        }
+    }
+}
+
+/// Target architecture in `e_machine`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Machine {
+    None,
+    M32,
+    Sparc,
+    I386,
+    M68k,
+    M88k,
+    Iamcu,
+    I860,
+    Mips,
+    S370,
+    MipsRs3Le,
+    Parisc,
+    Sparc32Plus,
+    I960,
+    Ppc,
+    Ppc64,
+    S390,
+    Spu,
+    V800,
+    Arm,
+    Alpha,
+    Sh,
+    SparcV9,
+    Tricore,
+    Arc,
+    H8300,
+    Ia64,
+    Coldfire,
+    M68hc12,
+    X86_64,
+    Vax,
+    Avr,
+    V850,
+    M32r,
+    Openrisc,
+    Xtensa,
+    Msp430,
+    Blackfin,
+    AlteraNios2,
+    TiC6000,
+    Hexagon,
+    Aarch64,
+    Avr32,
+    Tilepro,
+    Microblaze,
+    Cuda,
+    Tilegx,
+    Z80,
+    Riscv,
+    Bpf,
+    Csky,
+    Loongarch,
+    Unknown(u16),
+}
+
+impl From<u16> for Machine {
+    fn from(value: u16) -> Machine {
+        match value {
+            0 => Machine::None,
+            1 => Machine::M32,
+            2 => Machine::Sparc,
+            3 => Machine::I386,
+            4 => Machine::M68k,
+            5 => Machine::M88k,
+            6 => Machine::Iamcu,
+            7 => Machine::I860,
+            8 => Machine::Mips,
+            9 => Machine::S370,
+            10 => Machine::MipsRs3Le,
+            15 => Machine::Parisc,
+            18 => Machine::Sparc32Plus,
+            19 => Machine::I960,
+            20 => Machine::Ppc,
+            21 => Machine::Ppc64,
+            22 => Machine::S390,
+            23 => Machine::Spu,
+            36 => Machine::V800,
+            40 => Machine::Arm,
+            41 => Machine::Alpha,
+            42 => Machine::Sh,
+            43 => Machine::SparcV9,
+            44 => Machine::Tricore,
+            45 => Machine::Arc,
+            46 => Machine::H8300,
+            50 => Machine::Ia64,
+            52 => Machine::Coldfire,
+            53 => Machine::M68hc12,
+            62 => Machine::X86_64,
+            75 => Machine::Vax,
+            83 => Machine::Avr,
+            87 => Machine::V850,
+            88 => Machine::M32r,
+            92 => Machine::Openrisc,
+            94 => Machine::Xtensa,
+            105 => Machine::Msp430,
+            106 => Machine::Blackfin,
+            113 => Machine::AlteraNios2,
+            140 => Machine::TiC6000,
+            164 => Machine::Hexagon,
+            183 => Machine::Aarch64,
+            185 => Machine::Avr32,
+            188 => Machine::Tilepro,
+            189 => Machine::Microblaze,
+            190 => Machine::Cuda,
+            191 => Machine::Tilegx,
+            220 => Machine::Z80,
+            243 => Machine::Riscv,
+            247 => Machine::Bpf,
+            252 => Machine::Csky,
+            258 => Machine::Loongarch,
+            value => Machine::Unknown(value),
+        }
+    }
+}
+
+impl From<Machine> for u16 {
+    fn from(value: Machine) -> u16 {
+        match value {
+            Machine::None => 0,
+            Machine::M32 => 1,
+            Machine::Sparc => 2,
+            Machine::I386 => 3,
+            Machine::M68k => 4,
+            Machine::M88k => 5,
+            Machine::Iamcu => 6,
+            Machine::I860 => 7,
+            Machine::Mips => 8,
+            Machine::S370 => 9,
+            Machine::MipsRs3Le => 10,
+            Machine::Parisc => 15,
+            Machine::Sparc32Plus => 18,
+            Machine::I960 => 19,
+            Machine::Ppc => 20,
+            Machine::Ppc64 => 21,
+            Machine::S390 => 22,
+            Machine::Spu => 23,
+            Machine::V800 => 36,
+            Machine::Arm => 40,
+            Machine::Alpha => 41,
+            Machine::Sh => 42,
+            Machine::SparcV9 => 43,
+            Machine::Tricore => 44,
+            Machine::Arc => 45,
+            Machine::H8300 => 46,
+            Machine::Ia64 => 50,
+            Machine::Coldfire => 52,
+            Machine::M68hc12 => 53,
+            Machine::X86_64 => 62,
+            Machine::Vax => 75,
+            Machine::Avr => 83,
+            Machine::V850 => 87,
+            Machine::M32r => 88,
+            Machine::Openrisc => 92,
+            Machine::Xtensa => 94,
+            Machine::Msp430 => 105,
+            Machine::Blackfin => 106,
+            Machine::AlteraNios2 => 113,
+            Machine::TiC6000 => 140,
+            Machine::Hexagon => 164,
+            Machine::Aarch64 => 183,
+            Machine::Avr32 => 185,
+            Machine::Tilepro => 188,
+            Machine::Microblaze => 189,
+            Machine::Cuda => 190,
+            Machine::Tilegx => 191,
+            Machine::Z80 => 220,
+            Machine::Riscv => 243,
+            Machine::Bpf => 247,
+            Machine::Csky => 252,
+            Machine::Loongarch => 258,
+            Machine::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for Machine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Machine::None => write!(f, "None"),
+            Machine::M32 => write!(f, "WE32100"),
+            Machine::Sparc => write!(f, "Sparc"),
+            Machine::I386 => write!(f, "Intel 80386"),
+            Machine::M68k => write!(f, "MC68000"),
+            Machine::M88k => write!(f, "MC88000"),
+            Machine::Iamcu => write!(f, "Intel MCU"),
+            Machine::I860 => write!(f, "Intel 80860"),
+            Machine::Mips => write!(f, "MIPS R3000"),
+            Machine::S370 => write!(f, "IBM System/370"),
+            Machine::MipsRs3Le => write!(f, "MIPS R4000 big-endian"),
+            Machine::Parisc => write!(f, "HPPA"),
+            Machine::Sparc32Plus => write!(f, "Sparc v8+"),
+            Machine::I960 => write!(f, "Intel 80960"),
+            Machine::Ppc => write!(f, "PowerPC"),
+            Machine::Ppc64 => write!(f, "PowerPC64"),
+            Machine::S390 => write!(f, "IBM S/390"),
+            Machine::Spu => write!(f, "SPU"),
+            Machine::V800 => write!(f, "Renesas V850 (using RH850 ABI)"),
+            Machine::Arm => write!(f, "ARM"),
+            Machine::Alpha => write!(f, "Alpha"),
+            Machine::Sh => write!(f, "Renesas / SuperH SH"),
+            Machine::SparcV9 => write!(f, "Sparc v9"),
+            Machine::Tricore => write!(f, "Siemens Tricore"),
+            Machine::Arc => write!(f, "ARC"),
+            Machine::H8300 => write!(f, "Renesas H8/300"),
+            Machine::Ia64 => write!(f, "Intel IA-64"),
+            Machine::Coldfire => write!(f, "Motorola Coldfire"),
+            Machine::M68hc12 => write!(f, "Motorola M68HC12"),
+            Machine::X86_64 => write!(f, "Advanced Micro Devices X86-64"),
+            Machine::Vax => write!(f, "Digital VAX"),
+            Machine::Avr => write!(f, "Atmel AVR 8-bit microcontroller"),
+            Machine::V850 => write!(f, "Renesas V850"),
+            Machine::M32r => write!(f, "Renesas M32R"),
+            Machine::Openrisc => write!(f, "OpenRISC 1000"),
+            Machine::Xtensa => write!(f, "Tensilica Xtensa Processor"),
+            Machine::Msp430 => write!(f, "Texas Instruments msp430 microcontroller"),
+            Machine::Blackfin => write!(f, "Analog Devices Blackfin"),
+            Machine::AlteraNios2 => write!(f, "Altera Nios II"),
+            Machine::TiC6000 => write!(f, "Texas Instruments TMS320C6000 DSP family"),
+            Machine::Hexagon => write!(f, "QUALCOMM Hexagon"),
+            Machine::Aarch64 => write!(f, "AArch64"),
+            Machine::Avr32 => write!(f, "Atmel AVR 32-bit microprocessor"),
+            Machine::Tilepro => write!(f, "Tilera TILEPro multicore architecture family"),
+            Machine::Microblaze => write!(f, "Xilinx MicroBlaze"),
+            Machine::Cuda => write!(f, "NVIDIA CUDA architecture"),
+            Machine::Tilegx => write!(f, "Tilera TILE-Gx multicore architecture family"),
+            Machine::Z80 => write!(f, "Zilog Z80"),
+            Machine::Riscv => write!(f, "RISC-V"),
+            Machine::Bpf => write!(f, "Linux BPF"),
+            Machine::Csky => write!(f, "C-SKY"),
+            Machine::Loongarch => write!(f, "LoongArch"),
+            Machine::Unknown(value) => write!(f, "<unknown>: {:#x}", value),
+        }
+    }
+}
+
+pub const ELF_IDENTIFICATION_SIZE: usize = 16;
+
+// Sizes of the on-disk structures, excluding the identification bytes for the header.
+pub const ELF64_HEADER_SIZE: usize = 48;
+pub const ELF64_PROGRAM_HEADER_SIZE: usize = 56;
+pub const ELF64_SECTION_HEADER_SIZE: usize = 64;
+pub const ELF64_SYMBOL_ENTRY_SIZE: usize = 24;
+pub const ELF64_REL_ENTRY_SIZE: usize = 16;
+pub const ELF64_RELA_ENTRY_SIZE: usize = 24;
+pub const ELF64_DYNAMIC_ENTRY_SIZE: usize = 16;
+pub const ELF32_HEADER_SIZE: usize = 36;
+pub const ELF32_PROGRAM_HEADER_SIZE: usize = 32;
+pub const ELF32_SECTION_HEADER_SIZE: usize = 40;
+pub const ELF32_SYMBOL_ENTRY_SIZE: usize = 16;
+pub const ELF32_REL_ENTRY_SIZE: usize = 8;
+pub const ELF32_RELA_ENTRY_SIZE: usize = 12;
+pub const ELF32_DYNAMIC_ENTRY_SIZE: usize = 8;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::ElfFile;
+
+    /// `e_ident` of a file in the class and byte order `class` and `data`.
+    fn identification(class: u8, data: u8) -> Vec<u8> {
+        let mut binary = vec![0x7f, b'E', b'L', b'F', class, data, EV_CURRENT];
+        binary.resize(ELF_IDENTIFICATION_SIZE, 0);
+        binary
+    }
+
+    fn fields(fields: &[&[u8]]) -> Vec<u8> {
+        fields.concat()
+    }
+
+    /// Fields the executable headers below share in every class and byte order.
+    fn check_header(header: &ElfHeader, entry: u64) {
+        assert_eq!(header.elf_type(), ElfType::Exec);
+        assert_eq!(header.e_version, 1);
+        assert_eq!(header.e_entry, entry);
+        assert_eq!(header.e_shoff, 0x1234);
+        assert_eq!(header.e_phnum, 2);
+        assert_eq!(header.e_shnum, 5);
+        assert_eq!(header.e_shstrndx, 4);
+    }
+
+    #[test]
+    fn decodes_little_endian_elf64_headers() {
+        let mut binary = identification(ELFCLASS64, ELFDATA2LSB);
+        binary.extend_from_slice(&fields(&[
+            &[0x02, 0x00],                                     // e_type
+            &[0x3e, 0x00],                                     // e_machine
+            &[0x01, 0x00, 0x00, 0x00],                         // e_version
+            &[0x00, 0x10, 0x40, 0x00, 0x00, 0x00, 0x00, 0x00], // e_entry
+            &[0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // e_phoff
+            &[0x34, 0x12, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00], // e_shoff
+            &[0x00, 0x00, 0x00, 0x00],                         // e_flags
+            &[0x40, 0x00],                                     // e_ehsize
+            &[0x38, 0x00],                                     // e_phentsize
+            &[0x02, 0x00],                                     // e_phnum
+            &[0x40, 0x00],                                     // e_shentsize
+            &[0x05, 0x00],                                     // e_shnum
+            &[0x04, 0x00],                                     // e_shstrndx
+        ]));
+        let file = ElfFile::new(&binary).unwrap();
+        let encoding = file.get_encoding();
+        assert_eq!(encoding.class, Class::Elf64);
+        assert_eq!(encoding.endian, Endian::Little);
+        let header = file.get_elf_header();
+        check_header(header, 0x40_1000);
+        assert_eq!(header.machine(), Machine::X86_64);
+        assert_eq!(header.e_phoff, 0x40);
+        assert_eq!(header.e_phentsize, 56);
+        assert_eq!(header.e_shentsize, 64);
+        assert_eq!(file.get_identification().to_bytes(), &binary[..16]);
+        assert_eq!(header.to_bytes(encoding), &binary[16..]);
+    }
+
+    #[test]
+    fn decodes_big_endian_elf32_headers() {
+        let mut binary = identification(ELFCLASS32, ELFDATA2MSB);
+        binary.extend_from_slice(&fields(&[
+            &[0x00, 0x02],             // e_type
+            &[0x00, 0x08],             // e_machine
+            &[0x00, 0x00, 0x00, 0x01], // e_version
+            &[0x00, 0x40, 0x10, 0x00], // e_entry
+            &[0x00, 0x00, 0x00, 0x34], // e_phoff
+            &[0x00, 0x00, 0x12, 0x34], // e_shoff
+            &[0x70, 0x00, 0x10, 0x07], // e_flags
+            &[0x00, 0x34],             // e_ehsize
+            &[0x00, 0x20],             // e_phentsize
+            &[0x00, 0x02],             // e_phnum
+            &[0x00, 0x28],             // e_shentsize
+            &[0x00, 0x05],             // e_shnum
+            &[0x00, 0x04],             // e_shstrndx
+        ]));
+        let file = ElfFile::new(&binary).unwrap();
+        let encoding = file.get_encoding();
+        assert_eq!(encoding.class, Class::Elf32);
+        assert_eq!(encoding.endian, Endian::Big);
+        let header = file.get_elf_header();
+        check_header(header, 0x40_1000);
+        assert_eq!(header.machine(), Machine::Mips);
+        assert_eq!(header.e_phoff, 0x34);
+        assert_eq!(header.e_flags, 0x7000_1007);
+        assert_eq!(header.e_phentsize, 32);
+        assert_eq!(header.e_shentsize, 40);
+        assert_eq!(header.to_bytes(encoding), &binary[16..]);
+    }
+
+    #[test]
+    fn rejects_unknown_identifications() {
+        let mut binary = identification(3, ELFDATA2LSB);
+        binary.resize(ELF_IDENTIFICATION_SIZE + ELF64_HEADER_SIZE, 0);
+        assert!(matches!(
+            ElfFile::new(&binary),
+            Err(ElfError::BadClass { value: 3, .. })
+        ));
+        binary[4] = ELFCLASS64;
+        binary[5] = 0;
+        assert!(matches!(
+            ElfFile::new(&binary),
+            Err(ElfError::BadEncoding { value: 0, .. })
+        ));
+        // The header of an ELF64 file is longer than one of an ELF32 file.
+        binary[5] = ELFDATA2LSB;
+        binary.truncate(ELF_IDENTIFICATION_SIZE + ELF32_HEADER_SIZE);
+        assert!(matches!(
+            ElfFile::new(&binary),
+            Err(ElfError::TruncatedHeader { .. })
+        ));
+    }
+}
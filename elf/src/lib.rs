@@ -0,0 +1,70 @@
+//! Elf file parser shared by the chapter binaries.
+
+mod address;
+mod archive;
+mod core_dump;
+mod dynamic;
+mod error;
+mod file;
+mod hash;
+mod header;
+mod loader;
+mod mapping;
+mod note;
+mod program;
+mod reader;
+mod relocation;
+mod section;
+mod string_table;
+mod symbol;
+#[cfg(test)]
+mod testing;
+mod validate;
+mod version;
+mod writer;
+
+pub use archive::{
+    ArchiveMember, ArchiveSymbol, ElfArchive, ARCHIVE_MAGIC, ARCHIVE_MEMBER_HEADER_SIZE,
+    THIN_ARCHIVE_MAGIC,
+};
+pub use core_dump::{
+    register_names, signal_name, AuxvEntry, AuxvType, CoreThread, ElfCore, MappedFile, PrPsInfo,
+    PrStatus, PrStatusDisplay, SigInfo, NT_AUXV, NT_FILE, NT_PRPSINFO, NT_PRSTATUS, NT_SIGINFO,
+};
+pub use dynamic::{DynamicFlags, DynamicFlags1, DynamicTag, ElfDynamic, ElfDynamicEntry};
+pub use error::ElfError;
+pub use file::ElfFile;
+pub use hash::{gnu_hash, sysv_hash, SymbolHashTable};
+pub use header::{
+    Class, ElfHeader, ElfIdentification, ElfType, Encoding, Endian, Machine,
+    ELF32_DYNAMIC_ENTRY_SIZE, ELF32_HEADER_SIZE, ELF32_PROGRAM_HEADER_SIZE, ELF32_RELA_ENTRY_SIZE,
+    ELF32_REL_ENTRY_SIZE, ELF32_SECTION_HEADER_SIZE, ELF32_SYMBOL_ENTRY_SIZE,
+    ELF64_DYNAMIC_ENTRY_SIZE, ELF64_HEADER_SIZE, ELF64_PROGRAM_HEADER_SIZE, ELF64_RELA_ENTRY_SIZE,
+    ELF64_REL_ENTRY_SIZE, ELF64_SECTION_HEADER_SIZE, ELF64_SYMBOL_ENTRY_SIZE,
+    ELF_IDENTIFICATION_SIZE,
+};
+pub use loader::ElfLoader;
+pub use mapping::{section_in_segment, SegmentMapping};
+pub use note::{
+    Aarch64Feature1, ElfNote, GnuAbiTag, GnuProperty, NoteContent, NoteIterator, StapsdtProbe,
+    X86Feature1, GNU_PROPERTY_AARCH64_FEATURE_1_AND, GNU_PROPERTY_NO_COPY_ON_PROTECTED,
+    GNU_PROPERTY_STACK_SIZE, GNU_PROPERTY_X86_FEATURE_1_AND, NT_GNU_ABI_TAG, NT_GNU_BUILD_ID,
+    NT_GNU_PROPERTY_TYPE_0, NT_STAPSDT,
+};
+pub use program::{ElfProgramHeader, ProgramFlags, ProgramHeaderDisplay, ProgramType};
+pub use relocation::{
+    Aarch64RelocationType, ElfRelocation, ElfRelocationEntry, RelocationIterator,
+    RelocationSection, RelocationType, RiscvRelocationType, X86_64RelocationType,
+};
+pub use section::{ElfSectionHeader, SectionFlags, SectionHeaderDisplay, SectionType};
+pub use string_table::StringTable;
+pub use symbol::{
+    ElfSymbol, ElfSymbolEntry, SectionIndex, SymbolBinding, SymbolType, SymbolVisibility, SHN_ABS,
+    SHN_COMMON, SHN_LORESERVE, SHN_UNDEF, SHN_XINDEX,
+};
+pub use validate::ValidationIssue;
+pub use version::{
+    SymbolVersion, VersionDefinition, VersionNeed, VersionNeedEntry, VER_FLG_BASE, VER_FLG_WEAK,
+    VER_NDX_GLOBAL, VER_NDX_LOCAL,
+};
+pub use writer::{ElfWriter, WriterSection, WriterSymbol};
@@ -0,0 +1,36 @@
+use crate::archive::{ElfArchive, ARCHIVE_MAGIC, THIN_ARCHIVE_MAGIC};
+use crate::error::ElfError;
+use crate::file::{ElfFile, HEADER_MAGIC};
+use memmap::Mmap;
+use std::fs::File;
+
+/// Owns a memory mapped elf file or archive. Use `elf_file` or `archive` to parse it.
+pub struct ElfLoader {
+    pub mapped_file: Mmap,
+}
+
+impl ElfLoader {
+    pub fn try_new(file_path: &str) -> std::io::Result<ElfLoader> {
+        let file = File::open(file_path)?;
+        Ok(ElfLoader {
+            mapped_file: unsafe { Mmap::map(&file)? },
+        })
+    }
+
+    pub fn is_elf(&self) -> bool {
+        self.mapped_file.get(0..4) == Some(&HEADER_MAGIC[..])
+    }
+
+    pub fn is_archive(&self) -> bool {
+        let magic = self.mapped_file.get(0..ARCHIVE_MAGIC.len());
+        magic == Some(&ARCHIVE_MAGIC[..]) || magic == Some(&THIN_ARCHIVE_MAGIC[..])
+    }
+
+    pub fn archive(&self) -> Result<ElfArchive<'_>, ElfError> {
+        ElfArchive::new(&self.mapped_file)
+    }
+
+    pub fn elf_file(&self) -> Result<ElfFile<'_>, ElfError> {
+        ElfFile::new(&self.mapped_file)
+    }
+}
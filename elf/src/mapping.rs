@@ -0,0 +1,219 @@
+use crate::error::ElfError;
+use crate::file::ElfFile;
+use crate::program::{ElfProgramHeader, ProgramType};
+use crate::section::{ElfSectionHeader, SectionFlags, SectionType};
+
+const PT_GNU_MBIND_LO: u32 = 0x6474_e555;
+const PT_GNU_MBIND_HI: u32 = 0x6474_f554;
+
+/// Which sections each segment contains, as `readelf -l` prints under
+/// "Section to Segment mapping".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentMapping {
+    /// Section indexes in each segment, in program header order.
+    pub segments: Vec<Vec<usize>>,
+    /// Sections in no segment. The null section 0 is left out.
+    pub unmapped: Vec<usize>,
+    /// Allocated sections outside of every `PT_LOAD`, so they are never loaded.
+    pub uncovered: Vec<usize>,
+    /// `(section, segment)` pairs where an allocated section lies partly inside a `PT_LOAD`.
+    pub straddling: Vec<(usize, usize)>,
+}
+
+impl<'a> ElfFile<'a> {
+    pub fn get_segment_mapping(&self) -> Result<SegmentMapping, ElfError> {
+        let program_headers = self.get_program_headers()?;
+        let section_headers = self.get_section_headers()?;
+        let mut mapping = SegmentMapping {
+            segments: vec![Vec::new(); program_headers.len()],
+            unmapped: Vec::new(),
+            uncovered: Vec::new(),
+            straddling: Vec::new(),
+        };
+        for (index, section) in section_headers.iter().enumerate().skip(1) {
+            let mut mapped = false;
+            let mut loaded = false;
+            let mut straddling = false;
+            for (segment_index, segment) in program_headers.iter().enumerate() {
+                let load = ProgramType::from(segment.p_type) == ProgramType::Load;
+                if section_in_segment(section, segment) {
+                    mapping.segments[segment_index].push(index);
+                    mapped = true;
+                    loaded |= load;
+                } else if load && overlaps_segment(section, segment) {
+                    mapping.straddling.push((index, segment_index));
+                    straddling = true;
+                }
+            }
+            if !mapped {
+                mapping.unmapped.push(index);
+            }
+            if !loaded
+                && !straddling
+                && section.flags().contains(SectionFlags::ALLOC)
+                && !is_tbss(section)
+            {
+                mapping.uncovered.push(index);
+            }
+        }
+        Ok(mapping)
+    }
+}
+
+/// Whether `section` belongs to `segment`, following the rules of binutils
+/// `ELF_SECTION_IN_SEGMENT_STRICT`.
+pub fn section_in_segment(section: &ElfSectionHeader, segment: &ElfProgramHeader) -> bool {
+    let segment_type = ProgramType::from(segment.p_type);
+    let flags = section.flags();
+    let tls = flags.contains(SectionFlags::TLS);
+    let alloc = flags.contains(SectionFlags::ALLOC);
+    let nobits = section.sh_type == u32::from(SectionType::Nobits);
+
+    // .tbss takes no memory in the segments around the TLS template.
+    if is_tbss(section) && segment_type != ProgramType::Tls {
+        return false;
+    }
+    let type_matches = if tls {
+        matches!(
+            segment_type,
+            ProgramType::Tls | ProgramType::GnuRelro | ProgramType::Load
+        )
+    } else {
+        segment_type != ProgramType::Tls && segment_type != ProgramType::Phdr
+    };
+    if !type_matches {
+        return false;
+    }
+    // Loaded segments only hold allocated sections.
+    let loaded_segment = match segment_type {
+        ProgramType::Load
+        | ProgramType::Dynamic
+        | ProgramType::GnuEhFrame
+        | ProgramType::GnuStack
+        | ProgramType::GnuRelro
+        | ProgramType::GnuSframe => true,
+        _ => (PT_GNU_MBIND_LO..=PT_GNU_MBIND_HI).contains(&segment.p_type),
+    };
+    if !alloc && loaded_segment {
+        return false;
+    }
+    let size = section.sh_size;
+    let in_file = nobits || fits(section.sh_offset, size, segment.p_offset, segment.p_filesz);
+    let in_memory = !alloc || fits(section.sh_addr, size, segment.p_vaddr, segment.p_memsz);
+    if !in_file || !in_memory {
+        return false;
+    }
+    // Empty sections at either end of PT_DYNAMIC or PT_NOTE are left out.
+    if (segment_type == ProgramType::Dynamic || segment_type == ProgramType::Note)
+        && size == 0
+        && segment.p_memsz != 0
+    {
+        let inside_file = nobits
+            || (section.sh_offset > segment.p_offset
+                && section.sh_offset - segment.p_offset < segment.p_filesz);
+        let inside_memory = !alloc
+            || (section.sh_addr > segment.p_vaddr
+                && section.sh_addr - segment.p_vaddr < segment.p_memsz);
+        return inside_file && inside_memory;
+    }
+    true
+}
+
+/// Whether `size` bytes at `start` lie within the `length` bytes at `base`. An empty range
+/// must start before the end, except in an empty segment. Ranges that wrap around never fit.
+fn fits(start: u64, size: u64, base: u64, length: u64) -> bool {
+    match start.checked_sub(base) {
+        Some(distance) => {
+            distance <= length.wrapping_sub(1)
+                && distance.checked_add(size).is_some_and(|end| end <= length)
+        }
+        None => false,
+    }
+}
+
+/// Whether an allocated, non empty section shares some addresses with `segment`.
+fn overlaps_segment(section: &ElfSectionHeader, segment: &ElfProgramHeader) -> bool {
+    if !section.flags().contains(SectionFlags::ALLOC) || section.sh_size == 0 || is_tbss(section) {
+        return false;
+    }
+    let section_end = section.sh_addr.saturating_add(section.sh_size);
+    let segment_end = segment.p_vaddr.saturating_add(segment.p_memsz);
+    section.sh_addr < segment_end && segment.p_vaddr < section_end
+}
+
+fn is_tbss(section: &ElfSectionHeader) -> bool {
+    section.flags().contains(SectionFlags::TLS) && section.sh_type == u32::from(SectionType::Nobits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data_section(sh_offset: u64, sh_addr: u64, sh_size: u64) -> ElfSectionHeader {
+        ElfSectionHeader {
+            sh_name: 0,
+            sh_type: u32::from(SectionType::Progbits),
+            sh_flags: SectionFlags::ALLOC.0 | SectionFlags::WRITE.0,
+            sh_addr,
+            sh_offset,
+            sh_size,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 8,
+            sh_entsize: 0,
+        }
+    }
+
+    fn load_segment() -> ElfProgramHeader {
+        ElfProgramHeader {
+            p_type: u32::from(ProgramType::Load),
+            p_flags: 6,
+            p_offset: 0x1000,
+            p_vaddr: 0x40_1000,
+            p_paddr: 0x40_1000,
+            p_filesz: 0x100,
+            p_memsz: 0x200,
+            p_align: 0x1000,
+        }
+    }
+
+    #[test]
+    fn finds_sections_inside_segments() {
+        let segment = load_segment();
+        assert!(section_in_segment(
+            &data_section(0x1000, 0x40_1000, 0x100),
+            &segment
+        ));
+        assert!(section_in_segment(
+            &data_section(0x10f8, 0x40_10f8, 8),
+            &segment
+        ));
+        assert!(!section_in_segment(
+            &data_section(0x10f8, 0x40_10f8, 9),
+            &segment
+        ));
+        assert!(!section_in_segment(
+            &data_section(0x0ff8, 0x40_0ff8, 8),
+            &segment
+        ));
+    }
+
+    #[test]
+    fn sizes_that_wrap_around_are_not_contained() {
+        let segment = load_segment();
+        for size in [u64::MAX, u64::MAX - 0xff, 1 << 63].iter() {
+            let section = data_section(0x1010, 0x40_1010, *size);
+            assert!(!section_in_segment(&section, &segment), "{:#x}", size);
+            assert!(overlaps_segment(&section, &segment), "{:#x}", size);
+        }
+        let section = data_section(u64::MAX, u64::MAX, u64::MAX);
+        let segment = ElfProgramHeader {
+            p_offset: u64::MAX - 1,
+            p_vaddr: u64::MAX - 1,
+            p_filesz: u64::MAX,
+            p_memsz: u64::MAX,
+            ..load_segment()
+        };
+        assert!(!section_in_segment(&section, &segment));
+    }
+}
@@ -0,0 +1,622 @@
+use crate::core_dump::{
+    AuxvEntry, MappedFile, PrPsInfo, PrStatus, SigInfo, NT_AUXV, NT_FILE, NT_PRPSINFO, NT_PRSTATUS,
+    NT_SIGINFO,
+};
+use crate::error::ElfError;
+use crate::file::ElfFile;
+use crate::header::Encoding;
+use crate::program::{ElfProgramHeader, ProgramType};
+use crate::reader::Reader;
+use crate::section::{ElfSectionHeader, SectionType};
+use std::fmt;
+
+const NOTE_HEADER_SIZE: u64 = 12;
+
+pub const NT_GNU_ABI_TAG: u32 = 1;
+pub const NT_GNU_BUILD_ID: u32 = 3;
+pub const NT_GNU_PROPERTY_TYPE_0: u32 = 5;
+pub const NT_STAPSDT: u32 = 3;
+
+/// One entry of a `SHT_NOTE` section or `PT_NOTE` segment.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfNote<'a> {
+    /// Owner of the note, without the terminating NUL.
+    pub name: &'a [u8],
+    pub n_type: u32,
+    pub desc: &'a [u8],
+    /// File offset of the note header.
+    pub offset: u64,
+    /// File offset of `desc`.
+    pub(crate) desc_offset: u64,
+    pub(crate) encoding: Encoding,
+}
+
+impl<'a> ElfNote<'a> {
+    /// Decodes the descriptor of the notes this crate knows about.
+    pub fn content(&self) -> Result<Option<NoteContent<'a>>, ElfError> {
+        let content = match (self.name, self.n_type) {
+            (b"GNU", NT_GNU_BUILD_ID) => NoteContent::GnuBuildId(self.desc),
+            (b"GNU", NT_GNU_ABI_TAG) => NoteContent::GnuAbiTag(self.decode_abi_tag()?),
+            (b"GNU", NT_GNU_PROPERTY_TYPE_0) => {
+                NoteContent::GnuProperties(self.decode_properties()?)
+            }
+            (b"stapsdt", NT_STAPSDT) => NoteContent::StapsdtProbe(self.decode_probe()?),
+            (b"CORE", NT_PRSTATUS) => NoteContent::CorePrStatus(PrStatus::new(self)?),
+            (b"CORE", NT_PRPSINFO) => NoteContent::CorePrPsInfo(PrPsInfo::new(self)?),
+            (b"CORE", NT_AUXV) => NoteContent::CoreAuxv(AuxvEntry::new_list(self)),
+            (b"CORE", NT_FILE) => NoteContent::CoreFile(MappedFile::new_list(self)?),
+            (b"CORE", NT_SIGINFO) => NoteContent::CoreSigInfo(SigInfo::new(self)?),
+            _ => return Ok(None),
+        };
+        Ok(Some(content))
+    }
+
+    fn decode_abi_tag(&self) -> Result<GnuAbiTag, ElfError> {
+        let mut reader = Reader::new(self.get_desc(0, 16)?, self.encoding);
+        Ok(GnuAbiTag {
+            os: reader.read_u32(),
+            major: reader.read_u32(),
+            minor: reader.read_u32(),
+            subminor: reader.read_u32(),
+        })
+    }
+
+    fn decode_properties(&self) -> Result<Vec<GnuProperty<'a>>, ElfError> {
+        let align = self.encoding.word_size();
+        let mut properties = Vec::<GnuProperty<'a>>::new();
+        let mut position = 0;
+        while position < self.desc.len() {
+            let mut reader = Reader::new(self.get_desc(position, 8)?, self.encoding);
+            let pr_type = reader.read_u32();
+            let pr_datasz = reader.read_u32() as usize;
+            let data = self.get_desc(position + 8, pr_datasz)?;
+            properties.push(GnuProperty::new(pr_type, data, self.encoding));
+            position = align_up(position + 8 + pr_datasz, align);
+        }
+        Ok(properties)
+    }
+
+    fn decode_probe(&self) -> Result<StapsdtProbe<'a>, ElfError> {
+        let word_size = self.encoding.word_size();
+        let mut reader = Reader::new(self.get_desc(0, word_size * 3)?, self.encoding);
+        let pc = reader.read_word();
+        let base = reader.read_word();
+        let semaphore = reader.read_word();
+        let mut strings = self.desc[word_size * 3..].split(|b| *b == 0);
+        let mut next_string = || -> Result<&'a str, ElfError> {
+            let bytes = strings.next().unwrap_or(&[]);
+            std::str::from_utf8(bytes).map_err(|_| ElfError::InvalidUtf8 {
+                offset: self.offset,
+            })
+        };
+        Ok(StapsdtProbe {
+            pc,
+            base,
+            semaphore,
+            provider: next_string()?,
+            name: next_string()?,
+            arguments: next_string()?,
+        })
+    }
+
+    /// `size` bytes at `start` in the descriptor. Errors give file offsets, with the end of
+    /// the descriptor in place of the file size.
+    pub(crate) fn get_desc(&self, start: usize, size: usize) -> Result<&'a [u8], ElfError> {
+        start
+            .checked_add(size)
+            .and_then(|end| self.desc.get(start..end))
+            .ok_or(ElfError::OffsetOutOfRange {
+                offset: self.desc_offset.saturating_add(start as u64),
+                size: size as u64,
+                file_size: self.desc_offset + self.desc.len() as u64,
+            })
+    }
+}
+
+impl<'a> fmt::Display for ElfNote<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "---ElfNote---
+Owner     = {}
+Type      = {:#x}
+Data Size = {:#x}",
+            String::from_utf8_lossy(self.name),
+            self.n_type,
+            self.desc.len(),
+        )?;
+        if let Ok(Some(content)) = self.content() {
+            write!(f, "\n{}", content)?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterates over the notes packed in a note section or segment.
+pub struct NoteIterator<'a> {
+    data: &'a [u8],
+    offset: u64,
+    align: usize,
+    position: usize,
+    encoding: Encoding,
+}
+
+impl<'a> NoteIterator<'a> {
+    /// `offset` is the file offset of `data`. `align` is 8 for notes that are 8-byte aligned
+    /// such as `.note.gnu.property` in ELF64, and 4 otherwise.
+    pub fn new(data: &'a [u8], offset: u64, align: u64, encoding: Encoding) -> NoteIterator<'a> {
+        NoteIterator {
+            data,
+            offset,
+            align: if align == 8 { 8 } else { 4 },
+            position: 0,
+            encoding,
+        }
+    }
+
+    fn get(&self, start: usize, size: usize) -> Result<&'a [u8], ElfError> {
+        self.data
+            .get(start..start + size)
+            .ok_or(ElfError::OffsetOutOfRange {
+                offset: self.offset + start as u64,
+                size: size as u64,
+                file_size: self.offset + self.data.len() as u64,
+            })
+    }
+}
+
+impl<'a> Iterator for NoteIterator<'a> {
+    type Item = Result<ElfNote<'a>, ElfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.position >= self.data.len() {
+            return None;
+        }
+        let start = self.position;
+        let header = match self.get(start, NOTE_HEADER_SIZE as usize) {
+            Ok(header) => header,
+            Err(error) => {
+                self.position = self.data.len();
+                return Some(Err(error));
+            }
+        };
+        let mut reader = Reader::new(header, self.encoding);
+        let namesz = reader.read_u32() as usize;
+        let descsz = reader.read_u32() as usize;
+        let n_type = reader.read_u32();
+
+        let name_start = start + NOTE_HEADER_SIZE as usize;
+        let desc_start = align_up(name_start + namesz, self.align);
+        let note = self.get(name_start, namesz).and_then(|name| {
+            let desc = self.get(desc_start, descsz)?;
+            let length = name.iter().position(|b| *b == 0).unwrap_or(name.len());
+            Ok(ElfNote {
+                name: &name[..length],
+                n_type,
+                desc,
+                offset: self.offset + start as u64,
+                desc_offset: self.offset + desc_start as u64,
+                encoding: self.encoding,
+            })
+        });
+        self.position = match note {
+            Ok(_) => align_up(desc_start + descsz, self.align),
+            Err(_) => self.data.len(),
+        };
+        Some(note)
+    }
+}
+
+fn align_up(value: usize, align: usize) -> usize {
+    value.div_ceil(align) * align
+}
+
+impl<'a> ElfFile<'a> {
+    /// Every note of the file.
+    ///
+    /// Read from the `SHT_NOTE` sections, or from the `PT_NOTE` segments
+    /// when the file has no note sections.
+    pub fn get_notes(&self) -> Result<Vec<ElfNote<'a>>, ElfError> {
+        let mut notes = Vec::<ElfNote<'a>>::new();
+        for header in self.get_section_headers()?.iter() {
+            if header.sh_type == u32::from(SectionType::Note) {
+                for note in self.get_section_notes(header)? {
+                    notes.push(note?);
+                }
+            }
+        }
+        if !notes.is_empty() {
+            return Ok(notes);
+        }
+        for header in self.get_program_headers()?.iter() {
+            if ProgramType::from(header.p_type) == ProgramType::Note {
+                for note in self.get_segment_notes(header)? {
+                    notes.push(note?);
+                }
+            }
+        }
+        Ok(notes)
+    }
+
+    pub fn get_section_notes(
+        &self,
+        header: &ElfSectionHeader,
+    ) -> Result<NoteIterator<'a>, ElfError> {
+        let data = self.get_section_data(header)?;
+        Ok(NoteIterator::new(
+            data,
+            header.sh_offset,
+            header.sh_addralign,
+            self.get_encoding(),
+        ))
+    }
+
+    pub fn get_segment_notes(
+        &self,
+        header: &ElfProgramHeader,
+    ) -> Result<NoteIterator<'a>, ElfError> {
+        let data = self.get_bytes(header.p_offset, header.p_filesz)?;
+        Ok(NoteIterator::new(
+            data,
+            header.p_offset,
+            header.p_align,
+            self.get_encoding(),
+        ))
+    }
+
+    /// Descriptor of the `NT_GNU_BUILD_ID` note, if the file has one.
+    pub fn get_build_id(&self) -> Result<Option<&'a [u8]>, ElfError> {
+        for note in self.get_notes()?.iter() {
+            if let Some(NoteContent::GnuBuildId(build_id)) = note.content()? {
+                return Ok(Some(build_id));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Decoded descriptor of a known note.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NoteContent<'a> {
+    GnuBuildId(&'a [u8]),
+    GnuAbiTag(GnuAbiTag),
+    GnuProperties(Vec<GnuProperty<'a>>),
+    StapsdtProbe(StapsdtProbe<'a>),
+    CorePrStatus(PrStatus),
+    CorePrPsInfo(PrPsInfo<'a>),
+    CoreAuxv(Vec<AuxvEntry>),
+    CoreFile(Vec<MappedFile<'a>>),
+    CoreSigInfo(SigInfo),
+}
+
+impl<'a> fmt::Display for NoteContent<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NoteContent::GnuBuildId(build_id) => {
+                write!(f, "Build ID  = ")?;
+                for b in build_id.iter() {
+                    write!(f, "{:02x}", b)?;
+                }
+                Ok(())
+            }
+            NoteContent::GnuAbiTag(tag) => write!(f, "ABI Tag   = {}", tag),
+            NoteContent::GnuProperties(properties) => {
+                write!(f, "Properties =")?;
+                for property in properties.iter() {
+                    write!(f, " {};", property)?;
+                }
+                Ok(())
+            }
+            NoteContent::StapsdtProbe(probe) => write!(f, "{}", probe),
+            NoteContent::CorePrStatus(status) => write!(f, "Status    = {}", status),
+            NoteContent::CorePrPsInfo(process) => {
+                write!(f, "Process   = {} ({})", process.fname, process.psargs)
+            }
+            NoteContent::CoreAuxv(auxv) => write!(f, "Auxv      = {} entries", auxv.len()),
+            NoteContent::CoreFile(mapped_files) => {
+                write!(f, "Files     = {} mappings", mapped_files.len())
+            }
+            NoteContent::CoreSigInfo(signal) => write!(f, "Signal    = {}", signal),
+        }
+    }
+}
+
+/// `NT_GNU_ABI_TAG`: the oldest kernel the binary runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GnuAbiTag {
+    pub os: u32,
+    pub major: u32,
+    pub minor: u32,
+    pub subminor: u32,
+}
+
+impl fmt::Display for GnuAbiTag {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let os = match self.os {
+            0 => "Linux",
+            1 => "Hurd",
+            2 => "Solaris",
+            3 => "FreeBSD",
+            _ => "Unknown",
+        };
+        write!(f, "{} {}.{}.{}", os, self.major, self.minor, self.subminor)
+    }
+}
+
+pub const GNU_PROPERTY_STACK_SIZE: u32 = 1;
+pub const GNU_PROPERTY_NO_COPY_ON_PROTECTED: u32 = 2;
+pub const GNU_PROPERTY_AARCH64_FEATURE_1_AND: u32 = 0xc000_0000;
+pub const GNU_PROPERTY_X86_FEATURE_1_AND: u32 = 0xc000_0002;
+
+/// One property of `NT_GNU_PROPERTY_TYPE_0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GnuProperty<'a> {
+    StackSize(u64),
+    NoCopyOnProtected,
+    X86Feature1And(X86Feature1),
+    Aarch64Feature1And(Aarch64Feature1),
+    Other { pr_type: u32, data: &'a [u8] },
+}
+
+impl<'a> GnuProperty<'a> {
+    fn new(pr_type: u32, data: &'a [u8], encoding: Encoding) -> GnuProperty<'a> {
+        let word_size = encoding.word_size();
+        match pr_type {
+            GNU_PROPERTY_STACK_SIZE if data.len() == word_size => {
+                GnuProperty::StackSize(Reader::new(data, encoding).read_word())
+            }
+            GNU_PROPERTY_NO_COPY_ON_PROTECTED => GnuProperty::NoCopyOnProtected,
+            GNU_PROPERTY_X86_FEATURE_1_AND if data.len() == 4 => {
+                GnuProperty::X86Feature1And(X86Feature1(Reader::new(data, encoding).read_u32()))
+            }
+            GNU_PROPERTY_AARCH64_FEATURE_1_AND if data.len() == 4 => {
+                GnuProperty::Aarch64Feature1And(Aarch64Feature1(
+                    Reader::new(data, encoding).read_u32(),
+                ))
+            }
+            pr_type => GnuProperty::Other { pr_type, data },
+        }
+    }
+}
+
+impl<'a> fmt::Display for GnuProperty<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GnuProperty::StackSize(size) => write!(f, "stack size: {:#x}", size),
+            GnuProperty::NoCopyOnProtected => write!(f, "no copy on protected"),
+            GnuProperty::X86Feature1And(features) => write!(f, "x86 feature: {}", features),
+            GnuProperty::Aarch64Feature1And(features) => {
+                write!(f, "AArch64 feature: {}", features)
+            }
+            GnuProperty::Other { pr_type, data } => {
+                write!(f, "<type {:#x}, {} bytes>", pr_type, data.len())
+            }
+        }
+    }
+}
+
+/// Bits of `GNU_PROPERTY_X86_FEATURE_1_AND`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct X86Feature1(pub u32);
+
+impl X86Feature1 {
+    /// Indirect branch tracking.
+    pub const IBT: X86Feature1 = X86Feature1(0x1);
+    /// Shadow stack.
+    pub const SHSTK: X86Feature1 = X86Feature1(0x2);
+
+    pub fn contains(&self, flags: X86Feature1) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+}
+
+impl fmt::Display for X86Feature1 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ibt = if self.contains(X86Feature1::IBT) {
+            "IBT"
+        } else {
+            ""
+        };
+        let shstk = if self.contains(X86Feature1::SHSTK) {
+            "SHSTK"
+        } else {
+            ""
+        };
+        let separator = if ibt.is_empty() || shstk.is_empty() {
+            ""
+        } else {
+            ", "
+        };
+        write!(f, "{}{}{} ({:#x})", ibt, separator, shstk, self.0)
+    }
+}
+
+/// Bits of `GNU_PROPERTY_AARCH64_FEATURE_1_AND`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Aarch64Feature1(pub u32);
+
+impl Aarch64Feature1 {
+    /// Branch target identification.
+    pub const BTI: Aarch64Feature1 = Aarch64Feature1(0x1);
+    /// Pointer authentication.
+    pub const PAC: Aarch64Feature1 = Aarch64Feature1(0x2);
+
+    pub fn contains(&self, flags: Aarch64Feature1) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+}
+
+impl fmt::Display for Aarch64Feature1 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bti = if self.contains(Aarch64Feature1::BTI) {
+            "BTI"
+        } else {
+            ""
+        };
+        let pac = if self.contains(Aarch64Feature1::PAC) {
+            "PAC"
+        } else {
+            ""
+        };
+        let separator = if bti.is_empty() || pac.is_empty() {
+            ""
+        } else {
+            ", "
+        };
+        write!(f, "{}{}{} ({:#x})", bti, separator, pac, self.0)
+    }
+}
+
+/// SystemTap probe from `.note.stapsdt`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StapsdtProbe<'a> {
+    /// Address of the probe site.
+    pub pc: u64,
+    /// Link-time address of `.stapsdt.base`, used to adjust `pc` for prelinked files.
+    pub base: u64,
+    /// Address of the semaphore, 0 if the probe has none.
+    pub semaphore: u64,
+    pub provider: &'a str,
+    pub name: &'a str,
+    pub arguments: &'a str,
+}
+
+impl<'a> fmt::Display for StapsdtProbe<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Provider  = {}
+Name      = {}
+Location  = {:#x}, Base: {:#x}, Semaphore: {:#x}
+Arguments = {}",
+            self.provider, self.name, self.pc, self.base, self.semaphore, self.arguments
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::{ElfType, Machine};
+    use crate::testing::{note, section_header, ELF64_LE};
+    use crate::writer::ElfWriter;
+
+    fn words(values: &[u32]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|value| value.to_le_bytes())
+            .collect()
+    }
+
+    fn notes(data: &[u8], align: u64) -> Vec<ElfNote<'_>> {
+        NoteIterator::new(data, 0x100, align, ELF64_LE)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn pads_names_and_descriptors_to_the_alignment() {
+        for (align, second) in [(4, 24), (8, 32)].iter() {
+            let mut data = note("abcde", 7, &[1, 2, 3], *align);
+            data.extend_from_slice(&note("GNU", NT_GNU_BUILD_ID, &[0xab; 20], *align));
+            let notes = notes(&data, *align as u64);
+            assert_eq!(notes.len(), 2);
+            assert_eq!(notes[0].name, b"abcde");
+            assert_eq!(notes[0].n_type, 7);
+            assert_eq!(notes[0].desc, &[1, 2, 3]);
+            assert_eq!(notes[0].offset, 0x100);
+            assert_eq!(notes[1].offset, 0x100 + second);
+            assert_eq!(
+                notes[1].content().unwrap(),
+                Some(NoteContent::GnuBuildId(&[0xab; 20]))
+            );
+        }
+        // Any alignment but 8 is read as 4.
+        let data = note("abcde", 7, &[1, 2, 3], 4);
+        assert_eq!(notes(&data, 0)[0].desc, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn decodes_gnu_and_stapsdt_notes() {
+        let abi_tag = note("GNU", NT_GNU_ABI_TAG, &words(&[0, 3, 2, 0]), 4);
+        let content = notes(&abi_tag, 4)[0].content().unwrap().unwrap();
+        assert_eq!(content.to_string(), "ABI Tag   = Linux 3.2.0");
+
+        let mut properties = words(&[GNU_PROPERTY_X86_FEATURE_1_AND, 4, 3, 0]);
+        properties.extend_from_slice(&words(&[GNU_PROPERTY_STACK_SIZE, 8]));
+        properties.extend_from_slice(&0x80_0000u64.to_le_bytes());
+        properties.extend_from_slice(&words(&[0x1234, 2, 0xffff, 0]));
+        let data = note("GNU", NT_GNU_PROPERTY_TYPE_0, &properties, 8);
+        assert_eq!(
+            notes(&data, 8)[0].content().unwrap(),
+            Some(NoteContent::GnuProperties(vec![
+                GnuProperty::X86Feature1And(X86Feature1(3)),
+                GnuProperty::StackSize(0x80_0000),
+                GnuProperty::Other {
+                    pr_type: 0x1234,
+                    data: &[0xff, 0xff],
+                },
+            ]))
+        );
+
+        let mut probe = Vec::new();
+        for value in [0x1000u64, 0x2000, 0].iter() {
+            probe.extend_from_slice(&value.to_le_bytes());
+        }
+        probe.extend_from_slice(b"libc\0setjmp\08@%rdi -4@%esi\0");
+        let data = note("stapsdt", NT_STAPSDT, &probe, 4);
+        match notes(&data, 4)[0].content().unwrap() {
+            Some(NoteContent::StapsdtProbe(probe)) => {
+                assert_eq!((probe.pc, probe.base, probe.semaphore), (0x1000, 0x2000, 0));
+                assert_eq!(probe.provider, "libc");
+                assert_eq!(probe.name, "setjmp");
+                assert_eq!(probe.arguments, "8@%rdi -4@%esi");
+            }
+            other => panic!("{:?}", other),
+        }
+        let unknown = note("Go", 4, b"id", 4);
+        assert_eq!(notes(&unknown, 4)[0].content().unwrap(), None);
+    }
+
+    #[test]
+    fn stops_at_a_truncated_note() {
+        let mut data = note("GNU", NT_GNU_BUILD_ID, &[1; 8], 4);
+        data.extend_from_slice(&words(&[4, 0x100, NT_GNU_BUILD_ID]));
+        data.extend_from_slice(b"GNU\0");
+        let mut iterator = NoteIterator::new(&data, 0, 4, ELF64_LE);
+        assert!(iterator.next().unwrap().is_ok());
+        assert!(matches!(
+            iterator.next(),
+            Some(Err(ElfError::OffsetOutOfRange { .. }))
+        ));
+        assert!(iterator.next().is_none());
+
+        let short = note("GNU", NT_GNU_ABI_TAG, &words(&[0, 3]), 4);
+        assert!(notes(&short, 4)[0].content().is_err());
+    }
+
+    #[test]
+    fn finds_the_build_id_in_note_sections() {
+        let mut writer = ElfWriter::new(ELF64_LE, ElfType::Exec, Machine::X86_64);
+        writer.add_section(
+            ".note.ABI-tag",
+            ElfSectionHeader {
+                sh_addralign: 4,
+                ..section_header(SectionType::Note)
+            },
+            note("GNU", NT_GNU_ABI_TAG, &words(&[0, 3, 2, 0]), 4),
+        );
+        writer.add_section(
+            ".note.gnu.build-id",
+            ElfSectionHeader {
+                sh_addralign: 4,
+                ..section_header(SectionType::Note)
+            },
+            note("GNU", NT_GNU_BUILD_ID, &[0x5a; 20], 4),
+        );
+        let binary = writer.to_bytes().unwrap();
+        let file = ElfFile::new(&binary).unwrap();
+        assert_eq!(file.get_notes().unwrap().len(), 2);
+        assert_eq!(file.get_build_id().unwrap(), Some(&[0x5a; 20][..]));
+    }
+}
@@ -0,0 +1,281 @@
+use crate::header::{Class, Encoding, Machine};
+use crate::reader::Reader;
+use crate::writer::Writer;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ElfProgramHeader {
+    pub p_type: u32,
+    pub p_flags: u32,
+    pub p_offset: u64,
+    pub p_vaddr: u64,
+    pub p_paddr: u64,
+    pub p_filesz: u64,
+    pub p_memsz: u64,
+    pub p_align: u64,
+}
+
+impl fmt::Display for ElfProgramHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display(Machine::None).fmt(f)
+    }
+}
+
+/// Displays a program header with the processor specific types of `machine`.
+pub struct ProgramHeaderDisplay<'a> {
+    header: &'a ElfProgramHeader,
+    machine: Machine,
+}
+
+impl<'a> fmt::Display for ProgramHeaderDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let header = self.header;
+        write!(
+            f,
+            "---ElfProgramHeader---
+Type      = {} ({:#x})
+Flags     = {} ({:#x})
+Offset    = {:x}
+V_Addr    = {:x}
+P_Addr    = {:x}
+File_Size = {:x}
+Mem       = {:x}
+Align     = {}",
+            header.program_type(self.machine),
+            header.p_type,
+            header.flags(),
+            header.p_flags,
+            header.p_offset,
+            header.p_vaddr,
+            header.p_paddr,
+            header.p_filesz,
+            header.p_memsz,
+            header.p_align,
+        )
+    }
+}
+
+impl ElfProgramHeader {
+    pub fn program_type(&self, machine: Machine) -> ProgramType {
+        ProgramType::new(self.p_type, machine)
+    }
+
+    pub fn flags(&self) -> ProgramFlags {
+        ProgramFlags(self.p_flags)
+    }
+
+    pub fn display(&self, machine: Machine) -> ProgramHeaderDisplay<'_> {
+        ProgramHeaderDisplay {
+            header: self,
+            machine,
+        }
+    }
+
+    pub fn new(binary: &[u8], encoding: Encoding) -> ElfProgramHeader {
+        let mut reader = Reader::new(binary, encoding);
+        match encoding.class {
+            // p_flags moves after p_memsz in ELF32 to keep the structure aligned.
+            Class::Elf32 => {
+                let p_type = reader.read_u32();
+                let p_offset = reader.read_word();
+                let p_vaddr = reader.read_word();
+                let p_paddr = reader.read_word();
+                let p_filesz = reader.read_word();
+                let p_memsz = reader.read_word();
+                let p_flags = reader.read_u32();
+                let p_align = reader.read_word();
+                ElfProgramHeader {
+                    p_type,
+                    p_flags,
+                    p_offset,
+                    p_vaddr,
+                    p_paddr,
+                    p_filesz,
+                    p_memsz,
+                    p_align,
+                }
+            }
+            Class::Elf64 => ElfProgramHeader {
+                p_type: reader.read_u32(),
+                p_flags: reader.read_u32(),
+                p_offset: reader.read_word(),
+                p_vaddr: reader.read_word(),
+                p_paddr: reader.read_word(),
+                p_filesz: reader.read_word(),
+                p_memsz: reader.read_word(),
+                p_align: reader.read_word(),
+            },
+        }
+    }
+
+    pub fn to_bytes(&self, encoding: Encoding) -> Vec<u8> {
+        let mut writer = Writer::new(encoding);
+        writer.write_u32(self.p_type);
+        if encoding.class == Class::Elf64 {
+            writer.write_u32(self.p_flags);
+        }
+        writer.write_word(self.p_offset);
+        writer.write_word(self.p_vaddr);
+        writer.write_word(self.p_paddr);
+        writer.write_word(self.p_filesz);
+        writer.write_word(self.p_memsz);
+        if encoding.class == Class::Elf32 {
+            writer.write_u32(self.p_flags);
+        }
+        writer.write_word(self.p_align);
+        writer.into_bytes()
+    }
+}
+
+/// Segment type in `p_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgramType {
+    Null,
+    Load,
+    Dynamic,
+    Interp,
+    Note,
+    Shlib,
+    Phdr,
+    Tls,
+    GnuEhFrame,
+    GnuStack,
+    GnuRelro,
+    GnuProperty,
+    GnuSframe,
+    ArmArchext,
+    ArmExidx,
+    Aarch64MemtagMte,
+    RiscvAttributes,
+    /// Operating system specific range `PT_LOOS..=PT_HIOS`.
+    Os(u32),
+    /// Processor specific range `PT_LOPROC..=PT_HIPROC`.
+    Processor(u32),
+    Unknown(u32),
+}
+
+impl ProgramType {
+    /// Decodes the raw value, using `machine` for the processor specific range.
+    pub fn new(value: u32, machine: Machine) -> ProgramType {
+        match (value, machine) {
+            (0, _) => ProgramType::Null,
+            (1, _) => ProgramType::Load,
+            (2, _) => ProgramType::Dynamic,
+            (3, _) => ProgramType::Interp,
+            (4, _) => ProgramType::Note,
+            (5, _) => ProgramType::Shlib,
+            (6, _) => ProgramType::Phdr,
+            (7, _) => ProgramType::Tls,
+            (0x6474_e550, _) => ProgramType::GnuEhFrame,
+            (0x6474_e551, _) => ProgramType::GnuStack,
+            (0x6474_e552, _) => ProgramType::GnuRelro,
+            (0x6474_e553, _) => ProgramType::GnuProperty,
+            (0x6474_e554, _) => ProgramType::GnuSframe,
+            (0x7000_0000, Machine::Arm) => ProgramType::ArmArchext,
+            (0x7000_0001, Machine::Arm) => ProgramType::ArmExidx,
+            (0x7000_0002, Machine::Aarch64) => ProgramType::Aarch64MemtagMte,
+            (0x7000_0003, Machine::Riscv) => ProgramType::RiscvAttributes,
+            (0x6000_0000..=0x6fff_ffff, _) => ProgramType::Os(value),
+            (0x7000_0000..=0x7fff_ffff, _) => ProgramType::Processor(value),
+            _ => ProgramType::Unknown(value),
+        }
+    }
+}
+
+impl From<u32> for ProgramType {
+    fn from(value: u32) -> ProgramType {
+        ProgramType::new(value, Machine::None)
+    }
+}
+
+impl From<ProgramType> for u32 {
+    fn from(value: ProgramType) -> u32 {
+        match value {
+            ProgramType::Null => 0,
+            ProgramType::Load => 1,
+            ProgramType::Dynamic => 2,
+            ProgramType::Interp => 3,
+            ProgramType::Note => 4,
+            ProgramType::Shlib => 5,
+            ProgramType::Phdr => 6,
+            ProgramType::Tls => 7,
+            ProgramType::GnuEhFrame => 0x6474_e550,
+            ProgramType::GnuStack => 0x6474_e551,
+            ProgramType::GnuRelro => 0x6474_e552,
+            ProgramType::GnuProperty => 0x6474_e553,
+            ProgramType::GnuSframe => 0x6474_e554,
+            ProgramType::ArmArchext => 0x7000_0000,
+            ProgramType::ArmExidx => 0x7000_0001,
+            ProgramType::Aarch64MemtagMte => 0x7000_0002,
+            ProgramType::RiscvAttributes => 0x7000_0003,
+            ProgramType::Os(value)
+            | ProgramType::Processor(value)
+            | ProgramType::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for ProgramType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ProgramType::Null => write!(f, "NULL"),
+            ProgramType::Load => write!(f, "LOAD"),
+            ProgramType::Dynamic => write!(f, "DYNAMIC"),
+            ProgramType::Interp => write!(f, "INTERP"),
+            ProgramType::Note => write!(f, "NOTE"),
+            ProgramType::Shlib => write!(f, "SHLIB"),
+            ProgramType::Phdr => write!(f, "PHDR"),
+            ProgramType::Tls => write!(f, "TLS"),
+            ProgramType::GnuEhFrame => write!(f, "GNU_EH_FRAME"),
+            ProgramType::GnuStack => write!(f, "GNU_STACK"),
+            ProgramType::GnuRelro => write!(f, "GNU_RELRO"),
+            ProgramType::GnuProperty => write!(f, "GNU_PROPERTY"),
+            ProgramType::GnuSframe => write!(f, "GNU_SFRAME"),
+            ProgramType::ArmArchext => write!(f, "ARM_ARCHEXT"),
+            ProgramType::ArmExidx => write!(f, "ARM_EXIDX"),
+            ProgramType::Aarch64MemtagMte => write!(f, "AARCH64_MEMTAG_MTE"),
+            ProgramType::RiscvAttributes => write!(f, "RISCV_ATTRIBUTES"),
+            ProgramType::Os(value) => write!(f, "LOOS+{:#x}", value - 0x6000_0000),
+            ProgramType::Processor(value) => write!(f, "LOPROC+{:#x}", value - 0x7000_0000),
+            ProgramType::Unknown(value) => write!(f, "<unknown>: {:#x}", value),
+        }
+    }
+}
+
+/// Permission bits in `p_flags`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProgramFlags(pub u32);
+
+impl ProgramFlags {
+    pub const X: ProgramFlags = ProgramFlags(0x1);
+    pub const W: ProgramFlags = ProgramFlags(0x2);
+    pub const R: ProgramFlags = ProgramFlags(0x4);
+    pub const MASKOS: ProgramFlags = ProgramFlags(0x0ff0_0000);
+    pub const MASKPROC: ProgramFlags = ProgramFlags(0xf000_0000);
+
+    pub fn contains(&self, flags: ProgramFlags) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+}
+
+impl fmt::Display for ProgramFlags {
+    /// Same layout as the `Flg` column of `readelf -l`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let r = if self.contains(ProgramFlags::R) {
+            'R'
+        } else {
+            ' '
+        };
+        let w = if self.contains(ProgramFlags::W) {
+            'W'
+        } else {
+            ' '
+        };
+        let e = if self.contains(ProgramFlags::X) {
+            'E'
+        } else {
+            ' '
+        };
+        write!(f, "{}{}{}", r, w, e)
+    }
+}
@@ -0,0 +1,70 @@
+use crate::header::{Class, Encoding, Endian};
+
+/// Reads fixed-size fields one after another from a header binary.
+pub(crate) struct Reader<'a> {
+    binary: &'a [u8],
+    encoding: Encoding,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    // assumption: `binary` has enough length to read every field of the header.
+    pub(crate) fn new(binary: &'a [u8], encoding: Encoding) -> Reader<'a> {
+        Reader {
+            binary,
+            encoding,
+            position: 0,
+        }
+    }
+
+    pub(crate) fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let mut bytes = [0; N];
+        bytes.copy_from_slice(&self.binary[self.position..self.position + N]);
+        self.position += N;
+        bytes
+    }
+
+    pub(crate) fn read_u8(&mut self) -> u8 {
+        self.bytes::<1>()[0]
+    }
+
+    pub(crate) fn read_u16(&mut self) -> u16 {
+        let bytes = self.bytes();
+        match self.encoding.endian {
+            Endian::Little => u16::from_le_bytes(bytes),
+            Endian::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn read_u32(&mut self) -> u32 {
+        let bytes = self.bytes();
+        match self.encoding.endian {
+            Endian::Little => u32::from_le_bytes(bytes),
+            Endian::Big => u32::from_be_bytes(bytes),
+        }
+    }
+
+    pub(crate) fn read_u64(&mut self) -> u64 {
+        let bytes = self.bytes();
+        match self.encoding.endian {
+            Endian::Little => u64::from_le_bytes(bytes),
+            Endian::Big => u64::from_be_bytes(bytes),
+        }
+    }
+
+    /// Reads an address, offset or size, which is 4 bytes in ELF32 and 8 bytes in ELF64.
+    pub(crate) fn read_word(&mut self) -> u64 {
+        match self.encoding.class {
+            Class::Elf32 => self.read_u32() as u64,
+            Class::Elf64 => self.read_u64(),
+        }
+    }
+
+    /// Reads a signed word such as `r_addend`, sign-extending it in ELF32.
+    pub(crate) fn read_sword(&mut self) -> i64 {
+        match self.encoding.class {
+            Class::Elf32 => self.read_u32() as i32 as i64,
+            Class::Elf64 => self.read_u64() as i64,
+        }
+    }
+}
@@ -0,0 +1,1088 @@
+use crate::error::ElfError;
+use crate::file::ElfFile;
+use crate::header::{Class, ElfType, Encoding, Machine};
+use crate::reader::Reader;
+use crate::section::{ElfSectionHeader, SectionFlags, SectionType};
+use crate::symbol::ElfSymbol;
+use crate::writer::Writer;
+use std::fmt;
+
+/// Raw `Elf_Rel` or `Elf_Rela` entry.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfRelocationEntry {
+    pub r_offset: u64,
+    pub r_info: u64,
+    /// `None` for `SHT_REL`, whose addend is stored in the relocated field.
+    pub r_addend: Option<i64>,
+}
+
+impl ElfRelocationEntry {
+    pub fn new_rel(binary: &[u8], encoding: Encoding) -> ElfRelocationEntry {
+        let mut reader = Reader::new(binary, encoding);
+        ElfRelocationEntry {
+            r_offset: reader.read_word(),
+            r_info: reader.read_word(),
+            r_addend: None,
+        }
+    }
+
+    pub fn new_rela(binary: &[u8], encoding: Encoding) -> ElfRelocationEntry {
+        let mut reader = Reader::new(binary, encoding);
+        ElfRelocationEntry {
+            r_offset: reader.read_word(),
+            r_info: reader.read_word(),
+            r_addend: Some(reader.read_sword()),
+        }
+    }
+
+    /// `Elf_Rela` when there is an addend, `Elf_Rel` otherwise.
+    pub fn to_bytes(&self, encoding: Encoding) -> Vec<u8> {
+        let mut writer = Writer::new(encoding);
+        writer.write_word(self.r_offset);
+        writer.write_word(self.r_info);
+        if let Some(addend) = self.r_addend {
+            writer.write_sword(addend);
+        }
+        writer.into_bytes()
+    }
+
+    /// Index in the linked symbol table, the upper part of `r_info`.
+    pub fn symbol_index(&self, class: Class) -> u32 {
+        match class {
+            Class::Elf32 => (self.r_info >> 8) as u32,
+            Class::Elf64 => (self.r_info >> 32) as u32,
+        }
+    }
+
+    /// Architecture specific type, the lower part of `r_info`.
+    pub fn relocation_type(&self, class: Class) -> u32 {
+        match class {
+            Class::Elf32 => (self.r_info & 0xff) as u32,
+            Class::Elf64 => (self.r_info & 0xffff_ffff) as u32,
+        }
+    }
+}
+
+/// Relocation entry with its symbol and type decoded.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfRelocation<'a> {
+    pub entry: ElfRelocationEntry,
+    pub offset: u64,
+    /// `None` when the symbol index is `STN_UNDEF`.
+    pub symbol: Option<ElfSymbol<'a>>,
+    pub addend: Option<i64>,
+    pub relocation_type: RelocationType,
+}
+
+impl<'a> fmt::Display for ElfRelocation<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "---ElfRelocation---
+Offset = {:x}
+Type   = {}
+Symbol = {}
+Addend = {}",
+            self.offset,
+            self.relocation_type,
+            self.symbol.map(|symbol| symbol.name).unwrap_or(""),
+            self.addend.unwrap_or(0),
+        )
+    }
+}
+
+/// A `SHT_REL` or `SHT_RELA` section, together with the symbols its entries refer to.
+pub struct RelocationSection<'a> {
+    pub index: usize,
+    pub name: &'a str,
+    pub header: ElfSectionHeader,
+    /// Section the relocations apply to, from `sh_info` in relocatable files or with
+    /// `SHF_INFO_LINK`. `None` for dynamic relocations.
+    pub target_section: Option<usize>,
+    pub target_name: Option<&'a str>,
+    data: &'a [u8],
+    encoding: Encoding,
+    machine: Machine,
+    symbols: Vec<ElfSymbol<'a>>,
+}
+
+impl<'a> RelocationSection<'a> {
+    pub fn is_rela(&self) -> bool {
+        self.header.sh_type == u32::from(SectionType::Rela)
+    }
+
+    pub fn relocations(&self) -> RelocationIterator<'_, 'a> {
+        let entry_size = if self.is_rela() {
+            self.encoding.rela_entry_size()
+        } else {
+            self.encoding.rel_entry_size()
+        };
+        RelocationIterator {
+            section: self,
+            chunks: self.data.chunks_exact(entry_size),
+            position: 0,
+        }
+    }
+}
+
+/// Iterates over the entries of a `RelocationSection`.
+pub struct RelocationIterator<'s, 'a> {
+    section: &'s RelocationSection<'a>,
+    chunks: std::slice::ChunksExact<'a, u8>,
+    position: u64,
+}
+
+impl<'s, 'a> Iterator for RelocationIterator<'s, 'a> {
+    type Item = Result<ElfRelocation<'a>, ElfError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let section = self.section;
+        let chunk = self.chunks.next()?;
+        let entry_offset = section.header.sh_offset + self.position;
+        self.position += chunk.len() as u64;
+
+        let entry = if section.is_rela() {
+            ElfRelocationEntry::new_rela(chunk, section.encoding)
+        } else {
+            ElfRelocationEntry::new_rel(chunk, section.encoding)
+        };
+        let symbol = match entry.symbol_index(section.encoding.class) as usize {
+            0 => None,
+            index => match section.symbols.get(index) {
+                Some(symbol) => Some(*symbol),
+                None => {
+                    return Some(Err(ElfError::BadSymbolIndex {
+                        offset: entry_offset,
+                        index: index as u64,
+                        count: section.symbols.len() as u64,
+                    }))
+                }
+            },
+        };
+        Some(Ok(ElfRelocation {
+            entry,
+            offset: entry.r_offset,
+            symbol,
+            addend: entry.r_addend,
+            relocation_type: RelocationType::new(
+                entry.relocation_type(section.encoding.class),
+                section.machine,
+            ),
+        }))
+    }
+}
+
+impl<'a> ElfFile<'a> {
+    /// Every `SHT_REL` and `SHT_RELA` section, such as `.rela.text` or `.rel.dyn`.
+    pub fn get_relocation_sections(&self) -> Result<Vec<RelocationSection<'a>>, ElfError> {
+        let machine = self.get_elf_header().machine();
+        let relocatable = self.get_elf_header().elf_type() == ElfType::Rel;
+        let mut sections = Vec::<RelocationSection<'a>>::new();
+        for (index, header) in self.get_section_headers()?.iter().enumerate() {
+            let section_type = SectionType::from(header.sh_type);
+            if section_type != SectionType::Rel && section_type != SectionType::Rela {
+                continue;
+            }
+            let symbols = match header.sh_link {
+                0 => Vec::new(),
+                link => self.get_symbols_in_section(link as usize)?,
+            };
+            // Dynamic relocations apply to the whole image and may use sh_info for
+            // something else, so it only names a section in objects or with SHF_INFO_LINK.
+            let linked = relocatable || header.flags().contains(SectionFlags::INFO_LINK);
+            let (target_section, target_name) = match header.sh_info {
+                info if info != 0 && linked => {
+                    let target = self.get_section_header(info as usize)?;
+                    (Some(info as usize), Some(self.get_section_name(target)?))
+                }
+                _ => (None, None),
+            };
+            sections.push(RelocationSection {
+                index,
+                name: self.get_section_name(header)?,
+                header: *header,
+                target_section,
+                target_name,
+                data: self.get_section_data(header)?,
+                encoding: self.get_encoding(),
+                machine,
+                symbols,
+            });
+        }
+        Ok(sections)
+    }
+}
+
+/// Relocation type in `r_info`, interpreted for the machine of the file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelocationType {
+    X86_64(X86_64RelocationType),
+    Aarch64(Aarch64RelocationType),
+    Riscv(RiscvRelocationType),
+    /// Type of a machine without a decoder.
+    Unknown(u32),
+}
+
+impl RelocationType {
+    pub fn new(value: u32, machine: Machine) -> RelocationType {
+        match machine {
+            Machine::X86_64 => RelocationType::X86_64(X86_64RelocationType::from(value)),
+            Machine::Aarch64 => RelocationType::Aarch64(Aarch64RelocationType::from(value)),
+            Machine::Riscv => RelocationType::Riscv(RiscvRelocationType::from(value)),
+            _ => RelocationType::Unknown(value),
+        }
+    }
+}
+
+impl From<RelocationType> for u32 {
+    fn from(value: RelocationType) -> u32 {
+        match value {
+            RelocationType::X86_64(value) => u32::from(value),
+            RelocationType::Aarch64(value) => u32::from(value),
+            RelocationType::Riscv(value) => u32::from(value),
+            RelocationType::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for RelocationType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RelocationType::X86_64(value) => value.fmt(f),
+            RelocationType::Aarch64(value) => value.fmt(f),
+            RelocationType::Riscv(value) => value.fmt(f),
+            RelocationType::Unknown(value) => write!(f, "<unknown>: {:#x}", value),
+        }
+    }
+}
+
+/// Relocation types of `EM_X86_64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum X86_64RelocationType {
+    None,
+    R64,
+    Pc32,
+    Got32,
+    Plt32,
+    Copy,
+    GlobDat,
+    JumpSlot,
+    Relative,
+    Gotpcrel,
+    R32,
+    R32s,
+    R16,
+    Pc16,
+    R8,
+    Pc8,
+    Dtpmod64,
+    Dtpoff64,
+    Tpoff64,
+    Tlsgd,
+    Tlsld,
+    Dtpoff32,
+    Gottpoff,
+    Tpoff32,
+    Pc64,
+    Gotoff64,
+    Gotpc32,
+    Got64,
+    Gotpcrel64,
+    Gotpc64,
+    Gotplt64,
+    Pltoff64,
+    Size32,
+    Size64,
+    Gotpc32Tlsdesc,
+    TlsdescCall,
+    Tlsdesc,
+    Irelative,
+    Relative64,
+    Gotpcrelx,
+    RexGotpcrelx,
+    Unknown(u32),
+}
+
+impl From<u32> for X86_64RelocationType {
+    fn from(value: u32) -> X86_64RelocationType {
+        match value {
+            0 => X86_64RelocationType::None,
+            1 => X86_64RelocationType::R64,
+            2 => X86_64RelocationType::Pc32,
+            3 => X86_64RelocationType::Got32,
+            4 => X86_64RelocationType::Plt32,
+            5 => X86_64RelocationType::Copy,
+            6 => X86_64RelocationType::GlobDat,
+            7 => X86_64RelocationType::JumpSlot,
+            8 => X86_64RelocationType::Relative,
+            9 => X86_64RelocationType::Gotpcrel,
+            10 => X86_64RelocationType::R32,
+            11 => X86_64RelocationType::R32s,
+            12 => X86_64RelocationType::R16,
+            13 => X86_64RelocationType::Pc16,
+            14 => X86_64RelocationType::R8,
+            15 => X86_64RelocationType::Pc8,
+            16 => X86_64RelocationType::Dtpmod64,
+            17 => X86_64RelocationType::Dtpoff64,
+            18 => X86_64RelocationType::Tpoff64,
+            19 => X86_64RelocationType::Tlsgd,
+            20 => X86_64RelocationType::Tlsld,
+            21 => X86_64RelocationType::Dtpoff32,
+            22 => X86_64RelocationType::Gottpoff,
+            23 => X86_64RelocationType::Tpoff32,
+            24 => X86_64RelocationType::Pc64,
+            25 => X86_64RelocationType::Gotoff64,
+            26 => X86_64RelocationType::Gotpc32,
+            27 => X86_64RelocationType::Got64,
+            28 => X86_64RelocationType::Gotpcrel64,
+            29 => X86_64RelocationType::Gotpc64,
+            30 => X86_64RelocationType::Gotplt64,
+            31 => X86_64RelocationType::Pltoff64,
+            32 => X86_64RelocationType::Size32,
+            33 => X86_64RelocationType::Size64,
+            34 => X86_64RelocationType::Gotpc32Tlsdesc,
+            35 => X86_64RelocationType::TlsdescCall,
+            36 => X86_64RelocationType::Tlsdesc,
+            37 => X86_64RelocationType::Irelative,
+            38 => X86_64RelocationType::Relative64,
+            41 => X86_64RelocationType::Gotpcrelx,
+            42 => X86_64RelocationType::RexGotpcrelx,
+            value => X86_64RelocationType::Unknown(value),
+        }
+    }
+}
+
+impl From<X86_64RelocationType> for u32 {
+    fn from(value: X86_64RelocationType) -> u32 {
+        match value {
+            X86_64RelocationType::None => 0,
+            X86_64RelocationType::R64 => 1,
+            X86_64RelocationType::Pc32 => 2,
+            X86_64RelocationType::Got32 => 3,
+            X86_64RelocationType::Plt32 => 4,
+            X86_64RelocationType::Copy => 5,
+            X86_64RelocationType::GlobDat => 6,
+            X86_64RelocationType::JumpSlot => 7,
+            X86_64RelocationType::Relative => 8,
+            X86_64RelocationType::Gotpcrel => 9,
+            X86_64RelocationType::R32 => 10,
+            X86_64RelocationType::R32s => 11,
+            X86_64RelocationType::R16 => 12,
+            X86_64RelocationType::Pc16 => 13,
+            X86_64RelocationType::R8 => 14,
+            X86_64RelocationType::Pc8 => 15,
+            X86_64RelocationType::Dtpmod64 => 16,
+            X86_64RelocationType::Dtpoff64 => 17,
+            X86_64RelocationType::Tpoff64 => 18,
+            X86_64RelocationType::Tlsgd => 19,
+            X86_64RelocationType::Tlsld => 20,
+            X86_64RelocationType::Dtpoff32 => 21,
+            X86_64RelocationType::Gottpoff => 22,
+            X86_64RelocationType::Tpoff32 => 23,
+            X86_64RelocationType::Pc64 => 24,
+            X86_64RelocationType::Gotoff64 => 25,
+            X86_64RelocationType::Gotpc32 => 26,
+            X86_64RelocationType::Got64 => 27,
+            X86_64RelocationType::Gotpcrel64 => 28,
+            X86_64RelocationType::Gotpc64 => 29,
+            X86_64RelocationType::Gotplt64 => 30,
+            X86_64RelocationType::Pltoff64 => 31,
+            X86_64RelocationType::Size32 => 32,
+            X86_64RelocationType::Size64 => 33,
+            X86_64RelocationType::Gotpc32Tlsdesc => 34,
+            X86_64RelocationType::TlsdescCall => 35,
+            X86_64RelocationType::Tlsdesc => 36,
+            X86_64RelocationType::Irelative => 37,
+            X86_64RelocationType::Relative64 => 38,
+            X86_64RelocationType::Gotpcrelx => 41,
+            X86_64RelocationType::RexGotpcrelx => 42,
+            X86_64RelocationType::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for X86_64RelocationType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            X86_64RelocationType::None => write!(f, "R_X86_64_NONE"),
+            X86_64RelocationType::R64 => write!(f, "R_X86_64_64"),
+            X86_64RelocationType::Pc32 => write!(f, "R_X86_64_PC32"),
+            X86_64RelocationType::Got32 => write!(f, "R_X86_64_GOT32"),
+            X86_64RelocationType::Plt32 => write!(f, "R_X86_64_PLT32"),
+            X86_64RelocationType::Copy => write!(f, "R_X86_64_COPY"),
+            X86_64RelocationType::GlobDat => write!(f, "R_X86_64_GLOB_DAT"),
+            X86_64RelocationType::JumpSlot => write!(f, "R_X86_64_JUMP_SLOT"),
+            X86_64RelocationType::Relative => write!(f, "R_X86_64_RELATIVE"),
+            X86_64RelocationType::Gotpcrel => write!(f, "R_X86_64_GOTPCREL"),
+            X86_64RelocationType::R32 => write!(f, "R_X86_64_32"),
+            X86_64RelocationType::R32s => write!(f, "R_X86_64_32S"),
+            X86_64RelocationType::R16 => write!(f, "R_X86_64_16"),
+            X86_64RelocationType::Pc16 => write!(f, "R_X86_64_PC16"),
+            X86_64RelocationType::R8 => write!(f, "R_X86_64_8"),
+            X86_64RelocationType::Pc8 => write!(f, "R_X86_64_PC8"),
+            X86_64RelocationType::Dtpmod64 => write!(f, "R_X86_64_DTPMOD64"),
+            X86_64RelocationType::Dtpoff64 => write!(f, "R_X86_64_DTPOFF64"),
+            X86_64RelocationType::Tpoff64 => write!(f, "R_X86_64_TPOFF64"),
+            X86_64RelocationType::Tlsgd => write!(f, "R_X86_64_TLSGD"),
+            X86_64RelocationType::Tlsld => write!(f, "R_X86_64_TLSLD"),
+            X86_64RelocationType::Dtpoff32 => write!(f, "R_X86_64_DTPOFF32"),
+            X86_64RelocationType::Gottpoff => write!(f, "R_X86_64_GOTTPOFF"),
+            X86_64RelocationType::Tpoff32 => write!(f, "R_X86_64_TPOFF32"),
+            X86_64RelocationType::Pc64 => write!(f, "R_X86_64_PC64"),
+            X86_64RelocationType::Gotoff64 => write!(f, "R_X86_64_GOTOFF64"),
+            X86_64RelocationType::Gotpc32 => write!(f, "R_X86_64_GOTPC32"),
+            X86_64RelocationType::Got64 => write!(f, "R_X86_64_GOT64"),
+            X86_64RelocationType::Gotpcrel64 => write!(f, "R_X86_64_GOTPCREL64"),
+            X86_64RelocationType::Gotpc64 => write!(f, "R_X86_64_GOTPC64"),
+            X86_64RelocationType::Gotplt64 => write!(f, "R_X86_64_GOTPLT64"),
+            X86_64RelocationType::Pltoff64 => write!(f, "R_X86_64_PLTOFF64"),
+            X86_64RelocationType::Size32 => write!(f, "R_X86_64_SIZE32"),
+            X86_64RelocationType::Size64 => write!(f, "R_X86_64_SIZE64"),
+            X86_64RelocationType::Gotpc32Tlsdesc => write!(f, "R_X86_64_GOTPC32_TLSDESC"),
+            X86_64RelocationType::TlsdescCall => write!(f, "R_X86_64_TLSDESC_CALL"),
+            X86_64RelocationType::Tlsdesc => write!(f, "R_X86_64_TLSDESC"),
+            X86_64RelocationType::Irelative => write!(f, "R_X86_64_IRELATIVE"),
+            X86_64RelocationType::Relative64 => write!(f, "R_X86_64_RELATIVE64"),
+            X86_64RelocationType::Gotpcrelx => write!(f, "R_X86_64_GOTPCRELX"),
+            X86_64RelocationType::RexGotpcrelx => write!(f, "R_X86_64_REX_GOTPCRELX"),
+            X86_64RelocationType::Unknown(value) => write!(f, "<unknown>: {:#x}", value),
+        }
+    }
+}
+
+/// Relocation types of `EM_AARCH64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aarch64RelocationType {
+    None,
+    Abs64,
+    Abs32,
+    Abs16,
+    Prel64,
+    Prel32,
+    Prel16,
+    MovwUabsG0,
+    MovwUabsG0Nc,
+    MovwUabsG1,
+    MovwUabsG1Nc,
+    MovwUabsG2,
+    MovwUabsG2Nc,
+    MovwUabsG3,
+    MovwSabsG0,
+    MovwSabsG1,
+    MovwSabsG2,
+    LdPrelLo19,
+    AdrPrelLo21,
+    AdrPrelPgHi21,
+    AdrPrelPgHi21Nc,
+    AddAbsLo12Nc,
+    Ldst8AbsLo12Nc,
+    Tstbr14,
+    Condbr19,
+    Jump26,
+    Call26,
+    Ldst16AbsLo12Nc,
+    Ldst32AbsLo12Nc,
+    Ldst64AbsLo12Nc,
+    Ldst128AbsLo12Nc,
+    AdrGotPage,
+    Ld64GotLo12Nc,
+    TlsieAdrGottprelPage21,
+    TlsieLd64GottprelLo12Nc,
+    TlsleAddTprelHi12,
+    TlsleAddTprelLo12,
+    TlsleAddTprelLo12Nc,
+    TlsdescAdrPage21,
+    TlsdescLd64Lo12,
+    TlsdescAddLo12,
+    TlsdescCall,
+    Copy,
+    GlobDat,
+    JumpSlot,
+    Relative,
+    TlsDtpmod,
+    TlsDtprel,
+    TlsTprel,
+    Tlsdesc,
+    Irelative,
+    Unknown(u32),
+}
+
+impl From<u32> for Aarch64RelocationType {
+    fn from(value: u32) -> Aarch64RelocationType {
+        match value {
+            0 => Aarch64RelocationType::None,
+            257 => Aarch64RelocationType::Abs64,
+            258 => Aarch64RelocationType::Abs32,
+            259 => Aarch64RelocationType::Abs16,
+            260 => Aarch64RelocationType::Prel64,
+            261 => Aarch64RelocationType::Prel32,
+            262 => Aarch64RelocationType::Prel16,
+            263 => Aarch64RelocationType::MovwUabsG0,
+            264 => Aarch64RelocationType::MovwUabsG0Nc,
+            265 => Aarch64RelocationType::MovwUabsG1,
+            266 => Aarch64RelocationType::MovwUabsG1Nc,
+            267 => Aarch64RelocationType::MovwUabsG2,
+            268 => Aarch64RelocationType::MovwUabsG2Nc,
+            269 => Aarch64RelocationType::MovwUabsG3,
+            270 => Aarch64RelocationType::MovwSabsG0,
+            271 => Aarch64RelocationType::MovwSabsG1,
+            272 => Aarch64RelocationType::MovwSabsG2,
+            273 => Aarch64RelocationType::LdPrelLo19,
+            274 => Aarch64RelocationType::AdrPrelLo21,
+            275 => Aarch64RelocationType::AdrPrelPgHi21,
+            276 => Aarch64RelocationType::AdrPrelPgHi21Nc,
+            277 => Aarch64RelocationType::AddAbsLo12Nc,
+            278 => Aarch64RelocationType::Ldst8AbsLo12Nc,
+            279 => Aarch64RelocationType::Tstbr14,
+            280 => Aarch64RelocationType::Condbr19,
+            282 => Aarch64RelocationType::Jump26,
+            283 => Aarch64RelocationType::Call26,
+            284 => Aarch64RelocationType::Ldst16AbsLo12Nc,
+            285 => Aarch64RelocationType::Ldst32AbsLo12Nc,
+            286 => Aarch64RelocationType::Ldst64AbsLo12Nc,
+            299 => Aarch64RelocationType::Ldst128AbsLo12Nc,
+            311 => Aarch64RelocationType::AdrGotPage,
+            312 => Aarch64RelocationType::Ld64GotLo12Nc,
+            541 => Aarch64RelocationType::TlsieAdrGottprelPage21,
+            542 => Aarch64RelocationType::TlsieLd64GottprelLo12Nc,
+            549 => Aarch64RelocationType::TlsleAddTprelHi12,
+            550 => Aarch64RelocationType::TlsleAddTprelLo12,
+            551 => Aarch64RelocationType::TlsleAddTprelLo12Nc,
+            562 => Aarch64RelocationType::TlsdescAdrPage21,
+            563 => Aarch64RelocationType::TlsdescLd64Lo12,
+            564 => Aarch64RelocationType::TlsdescAddLo12,
+            569 => Aarch64RelocationType::TlsdescCall,
+            1024 => Aarch64RelocationType::Copy,
+            1025 => Aarch64RelocationType::GlobDat,
+            1026 => Aarch64RelocationType::JumpSlot,
+            1027 => Aarch64RelocationType::Relative,
+            1028 => Aarch64RelocationType::TlsDtpmod,
+            1029 => Aarch64RelocationType::TlsDtprel,
+            1030 => Aarch64RelocationType::TlsTprel,
+            1031 => Aarch64RelocationType::Tlsdesc,
+            1032 => Aarch64RelocationType::Irelative,
+            value => Aarch64RelocationType::Unknown(value),
+        }
+    }
+}
+
+impl From<Aarch64RelocationType> for u32 {
+    fn from(value: Aarch64RelocationType) -> u32 {
+        match value {
+            Aarch64RelocationType::None => 0,
+            Aarch64RelocationType::Abs64 => 257,
+            Aarch64RelocationType::Abs32 => 258,
+            Aarch64RelocationType::Abs16 => 259,
+            Aarch64RelocationType::Prel64 => 260,
+            Aarch64RelocationType::Prel32 => 261,
+            Aarch64RelocationType::Prel16 => 262,
+            Aarch64RelocationType::MovwUabsG0 => 263,
+            Aarch64RelocationType::MovwUabsG0Nc => 264,
+            Aarch64RelocationType::MovwUabsG1 => 265,
+            Aarch64RelocationType::MovwUabsG1Nc => 266,
+            Aarch64RelocationType::MovwUabsG2 => 267,
+            Aarch64RelocationType::MovwUabsG2Nc => 268,
+            Aarch64RelocationType::MovwUabsG3 => 269,
+            Aarch64RelocationType::MovwSabsG0 => 270,
+            Aarch64RelocationType::MovwSabsG1 => 271,
+            Aarch64RelocationType::MovwSabsG2 => 272,
+            Aarch64RelocationType::LdPrelLo19 => 273,
+            Aarch64RelocationType::AdrPrelLo21 => 274,
+            Aarch64RelocationType::AdrPrelPgHi21 => 275,
+            Aarch64RelocationType::AdrPrelPgHi21Nc => 276,
+            Aarch64RelocationType::AddAbsLo12Nc => 277,
+            Aarch64RelocationType::Ldst8AbsLo12Nc => 278,
+            Aarch64RelocationType::Tstbr14 => 279,
+            Aarch64RelocationType::Condbr19 => 280,
+            Aarch64RelocationType::Jump26 => 282,
+            Aarch64RelocationType::Call26 => 283,
+            Aarch64RelocationType::Ldst16AbsLo12Nc => 284,
+            Aarch64RelocationType::Ldst32AbsLo12Nc => 285,
+            Aarch64RelocationType::Ldst64AbsLo12Nc => 286,
+            Aarch64RelocationType::Ldst128AbsLo12Nc => 299,
+            Aarch64RelocationType::AdrGotPage => 311,
+            Aarch64RelocationType::Ld64GotLo12Nc => 312,
+            Aarch64RelocationType::TlsieAdrGottprelPage21 => 541,
+            Aarch64RelocationType::TlsieLd64GottprelLo12Nc => 542,
+            Aarch64RelocationType::TlsleAddTprelHi12 => 549,
+            Aarch64RelocationType::TlsleAddTprelLo12 => 550,
+            Aarch64RelocationType::TlsleAddTprelLo12Nc => 551,
+            Aarch64RelocationType::TlsdescAdrPage21 => 562,
+            Aarch64RelocationType::TlsdescLd64Lo12 => 563,
+            Aarch64RelocationType::TlsdescAddLo12 => 564,
+            Aarch64RelocationType::TlsdescCall => 569,
+            Aarch64RelocationType::Copy => 1024,
+            Aarch64RelocationType::GlobDat => 1025,
+            Aarch64RelocationType::JumpSlot => 1026,
+            Aarch64RelocationType::Relative => 1027,
+            Aarch64RelocationType::TlsDtpmod => 1028,
+            Aarch64RelocationType::TlsDtprel => 1029,
+            Aarch64RelocationType::TlsTprel => 1030,
+            Aarch64RelocationType::Tlsdesc => 1031,
+            Aarch64RelocationType::Irelative => 1032,
+            Aarch64RelocationType::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for Aarch64RelocationType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Aarch64RelocationType::None => write!(f, "R_AARCH64_NONE"),
+            Aarch64RelocationType::Abs64 => write!(f, "R_AARCH64_ABS64"),
+            Aarch64RelocationType::Abs32 => write!(f, "R_AARCH64_ABS32"),
+            Aarch64RelocationType::Abs16 => write!(f, "R_AARCH64_ABS16"),
+            Aarch64RelocationType::Prel64 => write!(f, "R_AARCH64_PREL64"),
+            Aarch64RelocationType::Prel32 => write!(f, "R_AARCH64_PREL32"),
+            Aarch64RelocationType::Prel16 => write!(f, "R_AARCH64_PREL16"),
+            Aarch64RelocationType::MovwUabsG0 => write!(f, "R_AARCH64_MOVW_UABS_G0"),
+            Aarch64RelocationType::MovwUabsG0Nc => write!(f, "R_AARCH64_MOVW_UABS_G0_NC"),
+            Aarch64RelocationType::MovwUabsG1 => write!(f, "R_AARCH64_MOVW_UABS_G1"),
+            Aarch64RelocationType::MovwUabsG1Nc => write!(f, "R_AARCH64_MOVW_UABS_G1_NC"),
+            Aarch64RelocationType::MovwUabsG2 => write!(f, "R_AARCH64_MOVW_UABS_G2"),
+            Aarch64RelocationType::MovwUabsG2Nc => write!(f, "R_AARCH64_MOVW_UABS_G2_NC"),
+            Aarch64RelocationType::MovwUabsG3 => write!(f, "R_AARCH64_MOVW_UABS_G3"),
+            Aarch64RelocationType::MovwSabsG0 => write!(f, "R_AARCH64_MOVW_SABS_G0"),
+            Aarch64RelocationType::MovwSabsG1 => write!(f, "R_AARCH64_MOVW_SABS_G1"),
+            Aarch64RelocationType::MovwSabsG2 => write!(f, "R_AARCH64_MOVW_SABS_G2"),
+            Aarch64RelocationType::LdPrelLo19 => write!(f, "R_AARCH64_LD_PREL_LO19"),
+            Aarch64RelocationType::AdrPrelLo21 => write!(f, "R_AARCH64_ADR_PREL_LO21"),
+            Aarch64RelocationType::AdrPrelPgHi21 => write!(f, "R_AARCH64_ADR_PREL_PG_HI21"),
+            Aarch64RelocationType::AdrPrelPgHi21Nc => write!(f, "R_AARCH64_ADR_PREL_PG_HI21_NC"),
+            Aarch64RelocationType::AddAbsLo12Nc => write!(f, "R_AARCH64_ADD_ABS_LO12_NC"),
+            Aarch64RelocationType::Ldst8AbsLo12Nc => write!(f, "R_AARCH64_LDST8_ABS_LO12_NC"),
+            Aarch64RelocationType::Tstbr14 => write!(f, "R_AARCH64_TSTBR14"),
+            Aarch64RelocationType::Condbr19 => write!(f, "R_AARCH64_CONDBR19"),
+            Aarch64RelocationType::Jump26 => write!(f, "R_AARCH64_JUMP26"),
+            Aarch64RelocationType::Call26 => write!(f, "R_AARCH64_CALL26"),
+            Aarch64RelocationType::Ldst16AbsLo12Nc => write!(f, "R_AARCH64_LDST16_ABS_LO12_NC"),
+            Aarch64RelocationType::Ldst32AbsLo12Nc => write!(f, "R_AARCH64_LDST32_ABS_LO12_NC"),
+            Aarch64RelocationType::Ldst64AbsLo12Nc => write!(f, "R_AARCH64_LDST64_ABS_LO12_NC"),
+            Aarch64RelocationType::Ldst128AbsLo12Nc => write!(f, "R_AARCH64_LDST128_ABS_LO12_NC"),
+            Aarch64RelocationType::AdrGotPage => write!(f, "R_AARCH64_ADR_GOT_PAGE"),
+            Aarch64RelocationType::Ld64GotLo12Nc => write!(f, "R_AARCH64_LD64_GOT_LO12_NC"),
+            Aarch64RelocationType::TlsieAdrGottprelPage21 => {
+                write!(f, "R_AARCH64_TLSIE_ADR_GOTTPREL_PAGE21")
+            }
+            Aarch64RelocationType::TlsieLd64GottprelLo12Nc => {
+                write!(f, "R_AARCH64_TLSIE_LD64_GOTTPREL_LO12_NC")
+            }
+            Aarch64RelocationType::TlsleAddTprelHi12 => write!(f, "R_AARCH64_TLSLE_ADD_TPREL_HI12"),
+            Aarch64RelocationType::TlsleAddTprelLo12 => write!(f, "R_AARCH64_TLSLE_ADD_TPREL_LO12"),
+            Aarch64RelocationType::TlsleAddTprelLo12Nc => {
+                write!(f, "R_AARCH64_TLSLE_ADD_TPREL_LO12_NC")
+            }
+            Aarch64RelocationType::TlsdescAdrPage21 => write!(f, "R_AARCH64_TLSDESC_ADR_PAGE21"),
+            Aarch64RelocationType::TlsdescLd64Lo12 => write!(f, "R_AARCH64_TLSDESC_LD64_LO12"),
+            Aarch64RelocationType::TlsdescAddLo12 => write!(f, "R_AARCH64_TLSDESC_ADD_LO12"),
+            Aarch64RelocationType::TlsdescCall => write!(f, "R_AARCH64_TLSDESC_CALL"),
+            Aarch64RelocationType::Copy => write!(f, "R_AARCH64_COPY"),
+            Aarch64RelocationType::GlobDat => write!(f, "R_AARCH64_GLOB_DAT"),
+            Aarch64RelocationType::JumpSlot => write!(f, "R_AARCH64_JUMP_SLOT"),
+            Aarch64RelocationType::Relative => write!(f, "R_AARCH64_RELATIVE"),
+            Aarch64RelocationType::TlsDtpmod => write!(f, "R_AARCH64_TLS_DTPMOD"),
+            Aarch64RelocationType::TlsDtprel => write!(f, "R_AARCH64_TLS_DTPREL"),
+            Aarch64RelocationType::TlsTprel => write!(f, "R_AARCH64_TLS_TPREL"),
+            Aarch64RelocationType::Tlsdesc => write!(f, "R_AARCH64_TLSDESC"),
+            Aarch64RelocationType::Irelative => write!(f, "R_AARCH64_IRELATIVE"),
+            Aarch64RelocationType::Unknown(value) => write!(f, "<unknown>: {:#x}", value),
+        }
+    }
+}
+
+/// Relocation types of `EM_RISCV`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiscvRelocationType {
+    None,
+    R32,
+    R64,
+    Relative,
+    Copy,
+    JumpSlot,
+    TlsDtpmod32,
+    TlsDtpmod64,
+    TlsDtprel32,
+    TlsDtprel64,
+    TlsTprel32,
+    TlsTprel64,
+    Tlsdesc,
+    Branch,
+    Jal,
+    Call,
+    CallPlt,
+    GotHi20,
+    TlsGotHi20,
+    TlsGdHi20,
+    PcrelHi20,
+    PcrelLo12I,
+    PcrelLo12S,
+    Hi20,
+    Lo12I,
+    Lo12S,
+    TprelHi20,
+    TprelLo12I,
+    TprelLo12S,
+    TprelAdd,
+    Add8,
+    Add16,
+    Add32,
+    Add64,
+    Sub8,
+    Sub16,
+    Sub32,
+    Sub64,
+    Align,
+    RvcBranch,
+    RvcJump,
+    Relax,
+    Sub6,
+    Set6,
+    Set8,
+    Set16,
+    Set32,
+    R32Pcrel,
+    Irelative,
+    Plt32,
+    SetUleb128,
+    SubUleb128,
+    Unknown(u32),
+}
+
+impl From<u32> for RiscvRelocationType {
+    fn from(value: u32) -> RiscvRelocationType {
+        match value {
+            0 => RiscvRelocationType::None,
+            1 => RiscvRelocationType::R32,
+            2 => RiscvRelocationType::R64,
+            3 => RiscvRelocationType::Relative,
+            4 => RiscvRelocationType::Copy,
+            5 => RiscvRelocationType::JumpSlot,
+            6 => RiscvRelocationType::TlsDtpmod32,
+            7 => RiscvRelocationType::TlsDtpmod64,
+            8 => RiscvRelocationType::TlsDtprel32,
+            9 => RiscvRelocationType::TlsDtprel64,
+            10 => RiscvRelocationType::TlsTprel32,
+            11 => RiscvRelocationType::TlsTprel64,
+            12 => RiscvRelocationType::Tlsdesc,
+            16 => RiscvRelocationType::Branch,
+            17 => RiscvRelocationType::Jal,
+            18 => RiscvRelocationType::Call,
+            19 => RiscvRelocationType::CallPlt,
+            20 => RiscvRelocationType::GotHi20,
+            21 => RiscvRelocationType::TlsGotHi20,
+            22 => RiscvRelocationType::TlsGdHi20,
+            23 => RiscvRelocationType::PcrelHi20,
+            24 => RiscvRelocationType::PcrelLo12I,
+            25 => RiscvRelocationType::PcrelLo12S,
+            26 => RiscvRelocationType::Hi20,
+            27 => RiscvRelocationType::Lo12I,
+            28 => RiscvRelocationType::Lo12S,
+            29 => RiscvRelocationType::TprelHi20,
+            30 => RiscvRelocationType::TprelLo12I,
+            31 => RiscvRelocationType::TprelLo12S,
+            32 => RiscvRelocationType::TprelAdd,
+            33 => RiscvRelocationType::Add8,
+            34 => RiscvRelocationType::Add16,
+            35 => RiscvRelocationType::Add32,
+            36 => RiscvRelocationType::Add64,
+            37 => RiscvRelocationType::Sub8,
+            38 => RiscvRelocationType::Sub16,
+            39 => RiscvRelocationType::Sub32,
+            40 => RiscvRelocationType::Sub64,
+            43 => RiscvRelocationType::Align,
+            44 => RiscvRelocationType::RvcBranch,
+            45 => RiscvRelocationType::RvcJump,
+            51 => RiscvRelocationType::Relax,
+            52 => RiscvRelocationType::Sub6,
+            53 => RiscvRelocationType::Set6,
+            54 => RiscvRelocationType::Set8,
+            55 => RiscvRelocationType::Set16,
+            56 => RiscvRelocationType::Set32,
+            57 => RiscvRelocationType::R32Pcrel,
+            58 => RiscvRelocationType::Irelative,
+            59 => RiscvRelocationType::Plt32,
+            60 => RiscvRelocationType::SetUleb128,
+            61 => RiscvRelocationType::SubUleb128,
+            value => RiscvRelocationType::Unknown(value),
+        }
+    }
+}
+
+impl From<RiscvRelocationType> for u32 {
+    fn from(value: RiscvRelocationType) -> u32 {
+        match value {
+            RiscvRelocationType::None => 0,
+            RiscvRelocationType::R32 => 1,
+            RiscvRelocationType::R64 => 2,
+            RiscvRelocationType::Relative => 3,
+            RiscvRelocationType::Copy => 4,
+            RiscvRelocationType::JumpSlot => 5,
+            RiscvRelocationType::TlsDtpmod32 => 6,
+            RiscvRelocationType::TlsDtpmod64 => 7,
+            RiscvRelocationType::TlsDtprel32 => 8,
+            RiscvRelocationType::TlsDtprel64 => 9,
+            RiscvRelocationType::TlsTprel32 => 10,
+            RiscvRelocationType::TlsTprel64 => 11,
+            RiscvRelocationType::Tlsdesc => 12,
+            RiscvRelocationType::Branch => 16,
+            RiscvRelocationType::Jal => 17,
+            RiscvRelocationType::Call => 18,
+            RiscvRelocationType::CallPlt => 19,
+            RiscvRelocationType::GotHi20 => 20,
+            RiscvRelocationType::TlsGotHi20 => 21,
+            RiscvRelocationType::TlsGdHi20 => 22,
+            RiscvRelocationType::PcrelHi20 => 23,
+            RiscvRelocationType::PcrelLo12I => 24,
+            RiscvRelocationType::PcrelLo12S => 25,
+            RiscvRelocationType::Hi20 => 26,
+            RiscvRelocationType::Lo12I => 27,
+            RiscvRelocationType::Lo12S => 28,
+            RiscvRelocationType::TprelHi20 => 29,
+            RiscvRelocationType::TprelLo12I => 30,
+            RiscvRelocationType::TprelLo12S => 31,
+            RiscvRelocationType::TprelAdd => 32,
+            RiscvRelocationType::Add8 => 33,
+            RiscvRelocationType::Add16 => 34,
+            RiscvRelocationType::Add32 => 35,
+            RiscvRelocationType::Add64 => 36,
+            RiscvRelocationType::Sub8 => 37,
+            RiscvRelocationType::Sub16 => 38,
+            RiscvRelocationType::Sub32 => 39,
+            RiscvRelocationType::Sub64 => 40,
+            RiscvRelocationType::Align => 43,
+            RiscvRelocationType::RvcBranch => 44,
+            RiscvRelocationType::RvcJump => 45,
+            RiscvRelocationType::Relax => 51,
+            RiscvRelocationType::Sub6 => 52,
+            RiscvRelocationType::Set6 => 53,
+            RiscvRelocationType::Set8 => 54,
+            RiscvRelocationType::Set16 => 55,
+            RiscvRelocationType::Set32 => 56,
+            RiscvRelocationType::R32Pcrel => 57,
+            RiscvRelocationType::Irelative => 58,
+            RiscvRelocationType::Plt32 => 59,
+            RiscvRelocationType::SetUleb128 => 60,
+            RiscvRelocationType::SubUleb128 => 61,
+            RiscvRelocationType::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for RiscvRelocationType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RiscvRelocationType::None => write!(f, "R_RISCV_NONE"),
+            RiscvRelocationType::R32 => write!(f, "R_RISCV_32"),
+            RiscvRelocationType::R64 => write!(f, "R_RISCV_64"),
+            RiscvRelocationType::Relative => write!(f, "R_RISCV_RELATIVE"),
+            RiscvRelocationType::Copy => write!(f, "R_RISCV_COPY"),
+            RiscvRelocationType::JumpSlot => write!(f, "R_RISCV_JUMP_SLOT"),
+            RiscvRelocationType::TlsDtpmod32 => write!(f, "R_RISCV_TLS_DTPMOD32"),
+            RiscvRelocationType::TlsDtpmod64 => write!(f, "R_RISCV_TLS_DTPMOD64"),
+            RiscvRelocationType::TlsDtprel32 => write!(f, "R_RISCV_TLS_DTPREL32"),
+            RiscvRelocationType::TlsDtprel64 => write!(f, "R_RISCV_TLS_DTPREL64"),
+            RiscvRelocationType::TlsTprel32 => write!(f, "R_RISCV_TLS_TPREL32"),
+            RiscvRelocationType::TlsTprel64 => write!(f, "R_RISCV_TLS_TPREL64"),
+            RiscvRelocationType::Tlsdesc => write!(f, "R_RISCV_TLSDESC"),
+            RiscvRelocationType::Branch => write!(f, "R_RISCV_BRANCH"),
+            RiscvRelocationType::Jal => write!(f, "R_RISCV_JAL"),
+            RiscvRelocationType::Call => write!(f, "R_RISCV_CALL"),
+            RiscvRelocationType::CallPlt => write!(f, "R_RISCV_CALL_PLT"),
+            RiscvRelocationType::GotHi20 => write!(f, "R_RISCV_GOT_HI20"),
+            RiscvRelocationType::TlsGotHi20 => write!(f, "R_RISCV_TLS_GOT_HI20"),
+            RiscvRelocationType::TlsGdHi20 => write!(f, "R_RISCV_TLS_GD_HI20"),
+            RiscvRelocationType::PcrelHi20 => write!(f, "R_RISCV_PCREL_HI20"),
+            RiscvRelocationType::PcrelLo12I => write!(f, "R_RISCV_PCREL_LO12_I"),
+            RiscvRelocationType::PcrelLo12S => write!(f, "R_RISCV_PCREL_LO12_S"),
+            RiscvRelocationType::Hi20 => write!(f, "R_RISCV_HI20"),
+            RiscvRelocationType::Lo12I => write!(f, "R_RISCV_LO12_I"),
+            RiscvRelocationType::Lo12S => write!(f, "R_RISCV_LO12_S"),
+            RiscvRelocationType::TprelHi20 => write!(f, "R_RISCV_TPREL_HI20"),
+            RiscvRelocationType::TprelLo12I => write!(f, "R_RISCV_TPREL_LO12_I"),
+            RiscvRelocationType::TprelLo12S => write!(f, "R_RISCV_TPREL_LO12_S"),
+            RiscvRelocationType::TprelAdd => write!(f, "R_RISCV_TPREL_ADD"),
+            RiscvRelocationType::Add8 => write!(f, "R_RISCV_ADD8"),
+            RiscvRelocationType::Add16 => write!(f, "R_RISCV_ADD16"),
+            RiscvRelocationType::Add32 => write!(f, "R_RISCV_ADD32"),
+            RiscvRelocationType::Add64 => write!(f, "R_RISCV_ADD64"),
+            RiscvRelocationType::Sub8 => write!(f, "R_RISCV_SUB8"),
+            RiscvRelocationType::Sub16 => write!(f, "R_RISCV_SUB16"),
+            RiscvRelocationType::Sub32 => write!(f, "R_RISCV_SUB32"),
+            RiscvRelocationType::Sub64 => write!(f, "R_RISCV_SUB64"),
+            RiscvRelocationType::Align => write!(f, "R_RISCV_ALIGN"),
+            RiscvRelocationType::RvcBranch => write!(f, "R_RISCV_RVC_BRANCH"),
+            RiscvRelocationType::RvcJump => write!(f, "R_RISCV_RVC_JUMP"),
+            RiscvRelocationType::Relax => write!(f, "R_RISCV_RELAX"),
+            RiscvRelocationType::Sub6 => write!(f, "R_RISCV_SUB6"),
+            RiscvRelocationType::Set6 => write!(f, "R_RISCV_SET6"),
+            RiscvRelocationType::Set8 => write!(f, "R_RISCV_SET8"),
+            RiscvRelocationType::Set16 => write!(f, "R_RISCV_SET16"),
+            RiscvRelocationType::Set32 => write!(f, "R_RISCV_SET32"),
+            RiscvRelocationType::R32Pcrel => write!(f, "R_RISCV_32_PCREL"),
+            RiscvRelocationType::Irelative => write!(f, "R_RISCV_IRELATIVE"),
+            RiscvRelocationType::Plt32 => write!(f, "R_RISCV_PLT32"),
+            RiscvRelocationType::SetUleb128 => write!(f, "R_RISCV_SET_ULEB128"),
+            RiscvRelocationType::SubUleb128 => write!(f, "R_RISCV_SUB_ULEB128"),
+            RiscvRelocationType::Unknown(value) => write!(f, "<unknown>: {:#x}", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::Endian;
+    use crate::symbol::ElfSymbolEntry;
+    use crate::testing::{section_header, ELF64_LE};
+    use crate::writer::{ElfWriter, WriterSymbol};
+
+    /// A file with `.text`, then a relocation section of `sh_type` over `entries`, whose
+    /// `sh_info` is `info`. Symbol 1 is `target`, defined in `.text`.
+    fn file_with_relocations(
+        encoding: Encoding,
+        elf_type: ElfType,
+        machine: Machine,
+        sh_type: SectionType,
+        flags: SectionFlags,
+        info: u32,
+        entries: &[ElfRelocationEntry],
+    ) -> Vec<u8> {
+        let mut writer = ElfWriter::new(encoding, elf_type, machine);
+        writer.add_section(".text", section_header(SectionType::Progbits), vec![0; 16]);
+        let mut data = Vec::new();
+        for entry in entries.iter() {
+            data.extend_from_slice(&entry.to_bytes(encoding));
+        }
+        writer.add_section(
+            ".rela.text",
+            ElfSectionHeader {
+                sh_flags: flags.0,
+                // .symtab is added after every other section.
+                sh_link: 3,
+                sh_info: info,
+                ..section_header(sh_type)
+            },
+            data,
+        );
+        writer.symbols.push(WriterSymbol {
+            name: "target".into(),
+            entry: ElfSymbolEntry {
+                st_name: 0,
+                st_info: 0x12,
+                st_other: 0,
+                st_shndx: 1,
+                st_value: 8,
+                st_size: 8,
+            },
+        });
+        writer.to_bytes().unwrap()
+    }
+
+    #[test]
+    fn decodes_relocations_of_each_machine() {
+        let cases = [
+            (Machine::X86_64, 2, "R_X86_64_PC32"),
+            (Machine::Aarch64, 283, "R_AARCH64_CALL26"),
+            (Machine::Riscv, 19, "R_RISCV_CALL_PLT"),
+            (Machine::Ppc64, 10, "<unknown>: 0xa"),
+        ];
+        for (machine, relocation_type, name) in cases.iter() {
+            let entry = ElfRelocationEntry {
+                r_offset: 4,
+                r_info: 1 << 32 | *relocation_type as u64,
+                r_addend: Some(-4),
+            };
+            let binary = file_with_relocations(
+                ELF64_LE,
+                ElfType::Rel,
+                *machine,
+                SectionType::Rela,
+                SectionFlags::INFO_LINK,
+                1,
+                &[entry],
+            );
+            let file = ElfFile::new(&binary).unwrap();
+            let sections = file.get_relocation_sections().unwrap();
+            assert_eq!(sections.len(), 1);
+            assert_eq!(sections[0].target_section, Some(1));
+            assert_eq!(sections[0].target_name, Some(".text"));
+            let relocations = sections[0]
+                .relocations()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(relocations.len(), 1);
+            let relocation = relocations[0];
+            assert_eq!(relocation.offset, 4);
+            assert_eq!(relocation.addend, Some(-4));
+            assert_eq!(relocation.symbol.unwrap().name, "target");
+            assert_eq!(u32::from(relocation.relocation_type), *relocation_type);
+            assert_eq!(relocation.relocation_type.to_string(), *name);
+        }
+    }
+
+    #[test]
+    fn decodes_elf32_rel_entries() {
+        let encoding = Encoding {
+            class: Class::Elf32,
+            endian: Endian::Big,
+        };
+        let entry = ElfRelocationEntry {
+            r_offset: 0xc,
+            r_info: 1 << 8 | 1,
+            r_addend: None,
+        };
+        let binary = file_with_relocations(
+            encoding,
+            ElfType::Rel,
+            Machine::Mips,
+            SectionType::Rel,
+            SectionFlags(0),
+            1,
+            &[entry, entry],
+        );
+        let file = ElfFile::new(&binary).unwrap();
+        let sections = file.get_relocation_sections().unwrap();
+        assert!(!sections[0].is_rela());
+        assert_eq!(sections[0].target_name, Some(".text"));
+        for relocation in sections[0].relocations() {
+            let relocation = relocation.unwrap();
+            assert_eq!(relocation.offset, 0xc);
+            assert_eq!(relocation.addend, None);
+            assert_eq!(relocation.entry.symbol_index(Class::Elf32), 1);
+            assert_eq!(relocation.relocation_type, RelocationType::Unknown(1));
+        }
+        assert_eq!(sections[0].relocations().count(), 2);
+    }
+
+    #[test]
+    fn ignores_sh_info_of_dynamic_relocations() {
+        let binary = file_with_relocations(
+            ELF64_LE,
+            ElfType::Dyn,
+            Machine::X86_64,
+            SectionType::Rela,
+            SectionFlags::ALLOC,
+            99,
+            &[],
+        );
+        let file = ElfFile::new(&binary).unwrap();
+        let sections = file.get_relocation_sections().unwrap();
+        assert_eq!(sections[0].target_section, None);
+        assert_eq!(sections[0].target_name, None);
+
+        let binary = file_with_relocations(
+            ELF64_LE,
+            ElfType::Dyn,
+            Machine::X86_64,
+            SectionType::Rela,
+            SectionFlags::INFO_LINK,
+            99,
+            &[],
+        );
+        let file = ElfFile::new(&binary).unwrap();
+        assert!(matches!(
+            file.get_relocation_sections(),
+            Err(ElfError::BadSectionIndex { index: 99, .. })
+        ));
+    }
+}
@@ -0,0 +1,366 @@
+use crate::header::{Encoding, Machine};
+use crate::reader::Reader;
+use crate::writer::Writer;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ElfSectionHeader {
+    pub sh_name: u32,
+    pub sh_type: u32,
+    pub sh_flags: u64,
+    pub sh_addr: u64,
+    pub sh_offset: u64,
+    pub sh_size: u64,
+    pub sh_link: u32,
+    pub sh_info: u32,
+    pub sh_addralign: u64,
+    pub sh_entsize: u64,
+}
+
+impl fmt::Display for ElfSectionHeader {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.display(Machine::None).fmt(f)
+    }
+}
+
+/// Displays a section header with the processor specific types of `machine`.
+pub struct SectionHeaderDisplay<'a> {
+    header: &'a ElfSectionHeader,
+    machine: Machine,
+}
+
+impl<'a> fmt::Display for SectionHeaderDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let header = self.header;
+        write!(
+            f,
+            "---ElfSectionHeader---
+Name      = {}
+Type      = {} ({:#x})
+Flags     = {} ({:#x})
+Addr      = {:x}
+Offset    = {:x}
+Size      = {:x}
+Link      = {}
+Info      = {}
+AddRalign = {}
+EntSize   = {:x}",
+            header.sh_name,
+            header.section_type(self.machine),
+            header.sh_type,
+            header.flags(),
+            header.sh_flags,
+            header.sh_addr,
+            header.sh_offset,
+            header.sh_size,
+            header.sh_link,
+            header.sh_info,
+            header.sh_addralign,
+            header.sh_entsize,
+        )
+    }
+}
+
+impl ElfSectionHeader {
+    pub fn section_type(&self, machine: Machine) -> SectionType {
+        SectionType::new(self.sh_type, machine)
+    }
+
+    pub fn flags(&self) -> SectionFlags {
+        SectionFlags(self.sh_flags)
+    }
+
+    pub fn display(&self, machine: Machine) -> SectionHeaderDisplay<'_> {
+        SectionHeaderDisplay {
+            header: self,
+            machine,
+        }
+    }
+
+    pub fn new(binary: &[u8], encoding: Encoding) -> ElfSectionHeader {
+        let mut reader = Reader::new(binary, encoding);
+        ElfSectionHeader {
+            sh_name: reader.read_u32(),
+            sh_type: reader.read_u32(),
+            sh_flags: reader.read_word(),
+            sh_addr: reader.read_word(),
+            sh_offset: reader.read_word(),
+            sh_size: reader.read_word(),
+            sh_link: reader.read_u32(),
+            sh_info: reader.read_u32(),
+            sh_addralign: reader.read_word(),
+            sh_entsize: reader.read_word(),
+        }
+    }
+
+    pub fn to_bytes(&self, encoding: Encoding) -> Vec<u8> {
+        let mut writer = Writer::new(encoding);
+        writer.write_u32(self.sh_name);
+        writer.write_u32(self.sh_type);
+        writer.write_word(self.sh_flags);
+        writer.write_word(self.sh_addr);
+        writer.write_word(self.sh_offset);
+        writer.write_word(self.sh_size);
+        writer.write_u32(self.sh_link);
+        writer.write_u32(self.sh_info);
+        writer.write_word(self.sh_addralign);
+        writer.write_word(self.sh_entsize);
+        writer.into_bytes()
+    }
+}
+
+/// Section type in `sh_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionType {
+    Null,
+    Progbits,
+    Symtab,
+    Strtab,
+    Rela,
+    Hash,
+    Dynamic,
+    Note,
+    Nobits,
+    Rel,
+    Shlib,
+    Dynsym,
+    InitArray,
+    FiniArray,
+    PreinitArray,
+    Group,
+    SymtabShndx,
+    Relr,
+    GnuIncrementalInputs,
+    LlvmAddrsig,
+    GnuAttributes,
+    GnuHash,
+    GnuLiblist,
+    Checksum,
+    GnuVerdef,
+    GnuVerneed,
+    GnuVersym,
+    ArmExidx,
+    ArmPreemptmap,
+    ArmAttributes,
+    ArmDebugoverlay,
+    ArmOverlaysection,
+    X86_64Unwind,
+    Aarch64Attributes,
+    RiscvAttributes,
+    /// Operating system specific range `SHT_LOOS..=SHT_HIOS`.
+    Os(u32),
+    /// Processor specific range `SHT_LOPROC..=SHT_HIPROC`.
+    Processor(u32),
+    /// Application specific range `SHT_LOUSER..=SHT_HIUSER`.
+    User(u32),
+    Unknown(u32),
+}
+
+impl SectionType {
+    /// Decodes the raw value, using `machine` for the processor specific range.
+    pub fn new(value: u32, machine: Machine) -> SectionType {
+        match (value, machine) {
+            (0, _) => SectionType::Null,
+            (1, _) => SectionType::Progbits,
+            (2, _) => SectionType::Symtab,
+            (3, _) => SectionType::Strtab,
+            (4, _) => SectionType::Rela,
+            (5, _) => SectionType::Hash,
+            (6, _) => SectionType::Dynamic,
+            (7, _) => SectionType::Note,
+            (8, _) => SectionType::Nobits,
+            (9, _) => SectionType::Rel,
+            (10, _) => SectionType::Shlib,
+            (11, _) => SectionType::Dynsym,
+            (14, _) => SectionType::InitArray,
+            (15, _) => SectionType::FiniArray,
+            (16, _) => SectionType::PreinitArray,
+            (17, _) => SectionType::Group,
+            (18, _) => SectionType::SymtabShndx,
+            (19, _) => SectionType::Relr,
+            (0x6fff_4700, _) => SectionType::GnuIncrementalInputs,
+            (0x6fff_4c03, _) => SectionType::LlvmAddrsig,
+            (0x6fff_fff5, _) => SectionType::GnuAttributes,
+            (0x6fff_fff6, _) => SectionType::GnuHash,
+            (0x6fff_fff7, _) => SectionType::GnuLiblist,
+            (0x6fff_fff8, _) => SectionType::Checksum,
+            (0x6fff_fffd, _) => SectionType::GnuVerdef,
+            (0x6fff_fffe, _) => SectionType::GnuVerneed,
+            (0x6fff_ffff, _) => SectionType::GnuVersym,
+            (0x7000_0001, Machine::Arm) => SectionType::ArmExidx,
+            (0x7000_0002, Machine::Arm) => SectionType::ArmPreemptmap,
+            (0x7000_0003, Machine::Arm) => SectionType::ArmAttributes,
+            (0x7000_0004, Machine::Arm) => SectionType::ArmDebugoverlay,
+            (0x7000_0005, Machine::Arm) => SectionType::ArmOverlaysection,
+            (0x7000_0001, Machine::X86_64) => SectionType::X86_64Unwind,
+            (0x7000_0003, Machine::Aarch64) => SectionType::Aarch64Attributes,
+            (0x7000_0003, Machine::Riscv) => SectionType::RiscvAttributes,
+            (0x6000_0000..=0x6fff_ffff, _) => SectionType::Os(value),
+            (0x7000_0000..=0x7fff_ffff, _) => SectionType::Processor(value),
+            (0x8000_0000..=0xffff_ffff, _) => SectionType::User(value),
+            _ => SectionType::Unknown(value),
+        }
+    }
+}
+
+impl From<u32> for SectionType {
+    fn from(value: u32) -> SectionType {
+        SectionType::new(value, Machine::None)
+    }
+}
+
+impl From<SectionType> for u32 {
+    fn from(value: SectionType) -> u32 {
+        match value {
+            SectionType::Null => 0,
+            SectionType::Progbits => 1,
+            SectionType::Symtab => 2,
+            SectionType::Strtab => 3,
+            SectionType::Rela => 4,
+            SectionType::Hash => 5,
+            SectionType::Dynamic => 6,
+            SectionType::Note => 7,
+            SectionType::Nobits => 8,
+            SectionType::Rel => 9,
+            SectionType::Shlib => 10,
+            SectionType::Dynsym => 11,
+            SectionType::InitArray => 14,
+            SectionType::FiniArray => 15,
+            SectionType::PreinitArray => 16,
+            SectionType::Group => 17,
+            SectionType::SymtabShndx => 18,
+            SectionType::Relr => 19,
+            SectionType::GnuIncrementalInputs => 0x6fff_4700,
+            SectionType::LlvmAddrsig => 0x6fff_4c03,
+            SectionType::GnuAttributes => 0x6fff_fff5,
+            SectionType::GnuHash => 0x6fff_fff6,
+            SectionType::GnuLiblist => 0x6fff_fff7,
+            SectionType::Checksum => 0x6fff_fff8,
+            SectionType::GnuVerdef => 0x6fff_fffd,
+            SectionType::GnuVerneed => 0x6fff_fffe,
+            SectionType::GnuVersym => 0x6fff_ffff,
+            SectionType::ArmExidx => 0x7000_0001,
+            SectionType::ArmPreemptmap => 0x7000_0002,
+            SectionType::ArmAttributes => 0x7000_0003,
+            SectionType::ArmDebugoverlay => 0x7000_0004,
+            SectionType::ArmOverlaysection => 0x7000_0005,
+            SectionType::X86_64Unwind => 0x7000_0001,
+            SectionType::Aarch64Attributes => 0x7000_0003,
+            SectionType::RiscvAttributes => 0x7000_0003,
+            SectionType::Os(value)
+            | SectionType::Processor(value)
+            | SectionType::User(value)
+            | SectionType::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for SectionType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SectionType::Null => write!(f, "NULL"),
+            SectionType::Progbits => write!(f, "PROGBITS"),
+            SectionType::Symtab => write!(f, "SYMTAB"),
+            SectionType::Strtab => write!(f, "STRTAB"),
+            SectionType::Rela => write!(f, "RELA"),
+            SectionType::Hash => write!(f, "HASH"),
+            SectionType::Dynamic => write!(f, "DYNAMIC"),
+            SectionType::Note => write!(f, "NOTE"),
+            SectionType::Nobits => write!(f, "NOBITS"),
+            SectionType::Rel => write!(f, "REL"),
+            SectionType::Shlib => write!(f, "SHLIB"),
+            SectionType::Dynsym => write!(f, "DYNSYM"),
+            SectionType::InitArray => write!(f, "INIT_ARRAY"),
+            SectionType::FiniArray => write!(f, "FINI_ARRAY"),
+            SectionType::PreinitArray => write!(f, "PREINIT_ARRAY"),
+            SectionType::Group => write!(f, "GROUP"),
+            SectionType::SymtabShndx => write!(f, "SYMTAB_SHNDX"),
+            SectionType::Relr => write!(f, "RELR"),
+            SectionType::GnuIncrementalInputs => write!(f, "GNU_INCREMENTAL_INPUTS"),
+            SectionType::LlvmAddrsig => write!(f, "LLVM_ADDRSIG"),
+            SectionType::GnuAttributes => write!(f, "GNU_ATTRIBUTES"),
+            SectionType::GnuHash => write!(f, "GNU_HASH"),
+            SectionType::GnuLiblist => write!(f, "GNU_LIBLIST"),
+            SectionType::Checksum => write!(f, "CHECKSUM"),
+            SectionType::GnuVerdef => write!(f, "VERDEF"),
+            SectionType::GnuVerneed => write!(f, "VERNEED"),
+            SectionType::GnuVersym => write!(f, "VERSYM"),
+            SectionType::ArmExidx => write!(f, "ARM_EXIDX"),
+            SectionType::ArmPreemptmap => write!(f, "ARM_PREEMPTMAP"),
+            SectionType::ArmAttributes => write!(f, "ARM_ATTRIBUTES"),
+            SectionType::ArmDebugoverlay => write!(f, "ARM_DEBUGOVERLAY"),
+            SectionType::ArmOverlaysection => write!(f, "ARM_OVERLAYSECTION"),
+            SectionType::X86_64Unwind => write!(f, "X86_64_UNWIND"),
+            SectionType::Aarch64Attributes => write!(f, "AARCH64_ATTRIBUTES"),
+            SectionType::RiscvAttributes => write!(f, "RISCV_ATTRIBUTES"),
+            SectionType::Os(value) => write!(f, "LOOS+{:#x}", value - 0x6000_0000),
+            SectionType::Processor(value) => write!(f, "LOPROC+{:#x}", value - 0x7000_0000),
+            SectionType::User(value) => write!(f, "LOUSER+{:#x}", value - 0x8000_0000),
+            SectionType::Unknown(value) => write!(f, "<unknown>: {:#x}", value),
+        }
+    }
+}
+
+/// Attribute bits in `sh_flags`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SectionFlags(pub u64);
+
+impl SectionFlags {
+    pub const WRITE: SectionFlags = SectionFlags(0x1);
+    pub const ALLOC: SectionFlags = SectionFlags(0x2);
+    pub const EXECINSTR: SectionFlags = SectionFlags(0x4);
+    pub const MERGE: SectionFlags = SectionFlags(0x10);
+    pub const STRINGS: SectionFlags = SectionFlags(0x20);
+    pub const INFO_LINK: SectionFlags = SectionFlags(0x40);
+    pub const LINK_ORDER: SectionFlags = SectionFlags(0x80);
+    pub const OS_NONCONFORMING: SectionFlags = SectionFlags(0x100);
+    pub const GROUP: SectionFlags = SectionFlags(0x200);
+    pub const TLS: SectionFlags = SectionFlags(0x400);
+    pub const COMPRESSED: SectionFlags = SectionFlags(0x800);
+    pub const GNU_RETAIN: SectionFlags = SectionFlags(0x20_0000);
+    pub const MASKOS: SectionFlags = SectionFlags(0x0ff0_0000);
+    pub const EXCLUDE: SectionFlags = SectionFlags(0x8000_0000);
+    pub const MASKPROC: SectionFlags = SectionFlags(0xf000_0000);
+
+    pub fn contains(&self, flags: SectionFlags) -> bool {
+        self.0 & flags.0 == flags.0
+    }
+}
+
+impl fmt::Display for SectionFlags {
+    /// Same letters as the `Flg` column of `readelf -S`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let letters = [
+            (SectionFlags::WRITE, 'W'),
+            (SectionFlags::ALLOC, 'A'),
+            (SectionFlags::EXECINSTR, 'X'),
+            (SectionFlags::MERGE, 'M'),
+            (SectionFlags::STRINGS, 'S'),
+            (SectionFlags::INFO_LINK, 'I'),
+            (SectionFlags::LINK_ORDER, 'L'),
+            (SectionFlags::OS_NONCONFORMING, 'O'),
+            (SectionFlags::GROUP, 'G'),
+            (SectionFlags::TLS, 'T'),
+            (SectionFlags::COMPRESSED, 'C'),
+            (SectionFlags::GNU_RETAIN, 'R'),
+            (SectionFlags::EXCLUDE, 'E'),
+        ];
+        let mut rest = self.0;
+        for (flag, letter) in letters.iter() {
+            if self.contains(*flag) {
+                write!(f, "{}", letter)?;
+                rest &= !flag.0;
+            }
+        }
+        if rest & SectionFlags::MASKOS.0 != 0 {
+            write!(f, "o")?;
+        }
+        if rest & SectionFlags::MASKPROC.0 != 0 {
+            write!(f, "p")?;
+        }
+        if rest & !(SectionFlags::MASKOS.0 | SectionFlags::MASKPROC.0) != 0 {
+            write!(f, "x")?;
+        }
+        Ok(())
+    }
+}
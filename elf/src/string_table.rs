@@ -0,0 +1,32 @@
+use crate::error::ElfError;
+
+/// NUL terminated strings packed in a `SHT_STRTAB` section.
+#[derive(Debug, Clone, Copy)]
+pub struct StringTable<'a> {
+    data: &'a [u8],
+    offset: u64,
+}
+
+impl<'a> StringTable<'a> {
+    /// `offset` is the file offset of `data`, used for error reporting.
+    pub fn new(data: &'a [u8], offset: u64) -> StringTable<'a> {
+        StringTable { data, offset }
+    }
+
+    /// Bytes of the string starting at `index`, without the terminating NUL.
+    pub fn get_bytes(&self, index: u64) -> Result<&'a [u8], ElfError> {
+        let bad_index = || ElfError::BadStringIndex {
+            offset: self.offset,
+            index,
+        };
+        let rest = self.data.get(index as usize..).ok_or_else(bad_index)?;
+        let length = rest.iter().position(|b| *b == 0x00).ok_or_else(bad_index)?;
+        Ok(&rest[..length])
+    }
+
+    pub fn get_str(&self, index: u64) -> Result<&'a str, ElfError> {
+        std::str::from_utf8(self.get_bytes(index)?).map_err(|_| ElfError::InvalidUtf8 {
+            offset: self.offset + index,
+        })
+    }
+}
@@ -0,0 +1,382 @@
+use crate::header::{Class, Encoding};
+use crate::reader::Reader;
+use crate::version::SymbolVersion;
+use crate::writer::Writer;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ElfSymbolEntry {
+    pub st_name: u32,
+    pub st_info: u8,
+    pub st_other: u8,
+    pub st_shndx: u16,
+    pub st_value: u64,
+    pub st_size: u64,
+}
+
+impl ElfSymbolEntry {
+    pub fn binding(&self) -> SymbolBinding {
+        SymbolBinding::from(self.st_info >> 4)
+    }
+
+    pub fn symbol_type(&self) -> SymbolType {
+        SymbolType::from(self.st_info & 0xf)
+    }
+
+    pub fn visibility(&self) -> SymbolVisibility {
+        SymbolVisibility::from(self.st_other & 0x3)
+    }
+
+    pub fn section_index(&self) -> SectionIndex {
+        SectionIndex::from(self.st_shndx)
+    }
+
+    pub fn new(binary: &[u8], encoding: Encoding) -> ElfSymbolEntry {
+        let mut reader = Reader::new(binary, encoding);
+        match encoding.class {
+            // st_value and st_size come before st_info in ELF32.
+            Class::Elf32 => {
+                let st_name = reader.read_u32();
+                let st_value = reader.read_word();
+                let st_size = reader.read_word();
+                ElfSymbolEntry {
+                    st_name,
+                    st_info: reader.read_u8(),
+                    st_other: reader.read_u8(),
+                    st_shndx: reader.read_u16(),
+                    st_value,
+                    st_size,
+                }
+            }
+            Class::Elf64 => ElfSymbolEntry {
+                st_name: reader.read_u32(),
+                st_info: reader.read_u8(),
+                st_other: reader.read_u8(),
+                st_shndx: reader.read_u16(),
+                st_value: reader.read_word(),
+                st_size: reader.read_word(),
+            },
+        }
+    }
+
+    pub fn to_bytes(&self, encoding: Encoding) -> Vec<u8> {
+        let mut writer = Writer::new(encoding);
+        writer.write_u32(self.st_name);
+        match encoding.class {
+            Class::Elf32 => {
+                writer.write_word(self.st_value);
+                writer.write_word(self.st_size);
+                writer.write_u8(self.st_info);
+                writer.write_u8(self.st_other);
+                writer.write_u16(self.st_shndx);
+            }
+            Class::Elf64 => {
+                writer.write_u8(self.st_info);
+                writer.write_u8(self.st_other);
+                writer.write_u16(self.st_shndx);
+                writer.write_word(self.st_value);
+                writer.write_word(self.st_size);
+            }
+        }
+        writer.into_bytes()
+    }
+}
+
+impl fmt::Display for ElfSymbolEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "---ElfSymbolEntry---
+Name      = {}
+Info      = {}
+Other     = {}
+Ndx       = {}
+Value     = {:x}
+Size      = {}",
+            self.st_name, self.st_info, self.st_other, self.st_shndx, self.st_value, self.st_size,
+        )
+    }
+}
+
+/// Symbol table entry with its name resolved and its fields decoded.
+#[derive(Debug, Clone, Copy)]
+pub struct ElfSymbol<'a> {
+    pub name: &'a str,
+    pub entry: ElfSymbolEntry,
+    pub binding: SymbolBinding,
+    pub symbol_type: SymbolType,
+    pub visibility: SymbolVisibility,
+    /// `SHN_XINDEX` is already replaced with the index from `SHT_SYMTAB_SHNDX` if it exists.
+    pub section_index: SectionIndex,
+    pub version: Option<SymbolVersion<'a>>,
+}
+
+impl<'a> ElfSymbol<'a> {
+    pub fn new(name: &'a str, entry: ElfSymbolEntry) -> ElfSymbol<'a> {
+        ElfSymbol {
+            name,
+            entry,
+            binding: entry.binding(),
+            symbol_type: entry.symbol_type(),
+            visibility: entry.visibility(),
+            section_index: entry.section_index(),
+            version: None,
+        }
+    }
+
+    /// Name with the version appended as `readelf` does, such as `memcpy@GLIBC_2.14`.
+    /// Defined default versions use `@@`, and the symbols naming a version definition are left as is.
+    pub fn versioned_name(&self) -> String {
+        match self.version {
+            Some(version) if version.file.is_none() && version.name == self.name => {
+                self.name.to_string()
+            }
+            Some(version) if version.file.is_none() && !version.hidden => {
+                format!("{}@@{}", self.name, version.name)
+            }
+            Some(version) => format!("{}@{}", self.name, version.name),
+            None => self.name.to_string(),
+        }
+    }
+}
+
+impl<'a> fmt::Display for ElfSymbol<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "---ElfSymbol---
+Name       = {}
+Value      = {:x}
+Size       = {}
+Type       = {}
+Bind       = {}
+Visibility = {}
+Ndx        = {}",
+            self.name,
+            self.entry.st_value,
+            self.entry.st_size,
+            self.symbol_type,
+            self.binding,
+            self.visibility,
+            self.section_index,
+        )?;
+        if let Some(version) = self.version {
+            write!(f, "\nVersion    = {}", version)?;
+        }
+        Ok(())
+    }
+}
+
+const STB_LOCAL: u8 = 0;
+const STB_GLOBAL: u8 = 1;
+const STB_WEAK: u8 = 2;
+const STB_GNU_UNIQUE: u8 = 10;
+
+/// Upper 4 bits of `st_info`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolBinding {
+    Local,
+    Global,
+    Weak,
+    GnuUnique,
+    Unknown(u8),
+}
+
+impl From<u8> for SymbolBinding {
+    fn from(value: u8) -> SymbolBinding {
+        match value {
+            STB_LOCAL => SymbolBinding::Local,
+            STB_GLOBAL => SymbolBinding::Global,
+            STB_WEAK => SymbolBinding::Weak,
+            STB_GNU_UNIQUE => SymbolBinding::GnuUnique,
+            value => SymbolBinding::Unknown(value),
+        }
+    }
+}
+
+impl From<SymbolBinding> for u8 {
+    fn from(value: SymbolBinding) -> u8 {
+        match value {
+            SymbolBinding::Local => STB_LOCAL,
+            SymbolBinding::Global => STB_GLOBAL,
+            SymbolBinding::Weak => STB_WEAK,
+            SymbolBinding::GnuUnique => STB_GNU_UNIQUE,
+            SymbolBinding::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for SymbolBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolBinding::Local => write!(f, "LOCAL"),
+            SymbolBinding::Global => write!(f, "GLOBAL"),
+            SymbolBinding::Weak => write!(f, "WEAK"),
+            SymbolBinding::GnuUnique => write!(f, "UNIQUE"),
+            SymbolBinding::Unknown(value) => write!(f, "<unknown>: {}", value),
+        }
+    }
+}
+
+const STT_NOTYPE: u8 = 0;
+const STT_OBJECT: u8 = 1;
+const STT_FUNC: u8 = 2;
+const STT_SECTION: u8 = 3;
+const STT_FILE: u8 = 4;
+const STT_COMMON: u8 = 5;
+const STT_TLS: u8 = 6;
+const STT_GNU_IFUNC: u8 = 10;
+
+/// Lower 4 bits of `st_info`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolType {
+    NoType,
+    Object,
+    Func,
+    Section,
+    File,
+    Common,
+    Tls,
+    GnuIfunc,
+    Unknown(u8),
+}
+
+impl From<u8> for SymbolType {
+    fn from(value: u8) -> SymbolType {
+        match value {
+            STT_NOTYPE => SymbolType::NoType,
+            STT_OBJECT => SymbolType::Object,
+            STT_FUNC => SymbolType::Func,
+            STT_SECTION => SymbolType::Section,
+            STT_FILE => SymbolType::File,
+            STT_COMMON => SymbolType::Common,
+            STT_TLS => SymbolType::Tls,
+            STT_GNU_IFUNC => SymbolType::GnuIfunc,
+            value => SymbolType::Unknown(value),
+        }
+    }
+}
+
+impl From<SymbolType> for u8 {
+    fn from(value: SymbolType) -> u8 {
+        match value {
+            SymbolType::NoType => STT_NOTYPE,
+            SymbolType::Object => STT_OBJECT,
+            SymbolType::Func => STT_FUNC,
+            SymbolType::Section => STT_SECTION,
+            SymbolType::File => STT_FILE,
+            SymbolType::Common => STT_COMMON,
+            SymbolType::Tls => STT_TLS,
+            SymbolType::GnuIfunc => STT_GNU_IFUNC,
+            SymbolType::Unknown(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for SymbolType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolType::NoType => write!(f, "NOTYPE"),
+            SymbolType::Object => write!(f, "OBJECT"),
+            SymbolType::Func => write!(f, "FUNC"),
+            SymbolType::Section => write!(f, "SECTION"),
+            SymbolType::File => write!(f, "FILE"),
+            SymbolType::Common => write!(f, "COMMON"),
+            SymbolType::Tls => write!(f, "TLS"),
+            SymbolType::GnuIfunc => write!(f, "IFUNC"),
+            SymbolType::Unknown(value) => write!(f, "<unknown>: {}", value),
+        }
+    }
+}
+
+const STV_DEFAULT: u8 = 0;
+const STV_INTERNAL: u8 = 1;
+const STV_HIDDEN: u8 = 2;
+const STV_PROTECTED: u8 = 3;
+
+/// Lower 2 bits of `st_other`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolVisibility {
+    Default,
+    Internal,
+    Hidden,
+    Protected,
+}
+
+impl From<u8> for SymbolVisibility {
+    fn from(value: u8) -> SymbolVisibility {
+        match value & 0x3 {
+            STV_DEFAULT => SymbolVisibility::Default,
+            STV_INTERNAL => SymbolVisibility::Internal,
+            STV_HIDDEN => SymbolVisibility::Hidden,
+            _ => SymbolVisibility::Protected,
+        }
+    }
+}
+
+impl From<SymbolVisibility> for u8 {
+    fn from(value: SymbolVisibility) -> u8 {
+        match value {
+            SymbolVisibility::Default => STV_DEFAULT,
+            SymbolVisibility::Internal => STV_INTERNAL,
+            SymbolVisibility::Hidden => STV_HIDDEN,
+            SymbolVisibility::Protected => STV_PROTECTED,
+        }
+    }
+}
+
+impl fmt::Display for SymbolVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SymbolVisibility::Default => write!(f, "DEFAULT"),
+            SymbolVisibility::Internal => write!(f, "INTERNAL"),
+            SymbolVisibility::Hidden => write!(f, "HIDDEN"),
+            SymbolVisibility::Protected => write!(f, "PROTECTED"),
+        }
+    }
+}
+
+pub const SHN_UNDEF: u16 = 0;
+pub const SHN_LORESERVE: u16 = 0xff00;
+pub const SHN_ABS: u16 = 0xfff1;
+pub const SHN_COMMON: u16 = 0xfff2;
+pub const SHN_XINDEX: u16 = 0xffff;
+
+/// Meaning of `st_shndx`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionIndex {
+    Undefined,
+    Absolute,
+    Common,
+    /// The real index is stored in the `SHT_SYMTAB_SHNDX` section.
+    ExtendedIndex,
+    /// Other index in the reserved range `SHN_LORESERVE..=SHN_HIRESERVE`.
+    Reserved(u16),
+    Index(u32),
+}
+
+impl From<u16> for SectionIndex {
+    fn from(value: u16) -> SectionIndex {
+        match value {
+            SHN_UNDEF => SectionIndex::Undefined,
+            SHN_ABS => SectionIndex::Absolute,
+            SHN_COMMON => SectionIndex::Common,
+            SHN_XINDEX => SectionIndex::ExtendedIndex,
+            value if value >= SHN_LORESERVE => SectionIndex::Reserved(value),
+            value => SectionIndex::Index(value as u32),
+        }
+    }
+}
+
+impl fmt::Display for SectionIndex {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SectionIndex::Undefined => write!(f, "UND"),
+            SectionIndex::Absolute => write!(f, "ABS"),
+            SectionIndex::Common => write!(f, "COM"),
+            SectionIndex::ExtendedIndex => write!(f, "XINDEX"),
+            SectionIndex::Reserved(value) => write!(f, "RSV[{:#x}]", value),
+            SectionIndex::Index(index) => write!(f, "{}", index),
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Files for the tests, written with `ElfWriter` so they do not need a toolchain.
+
+use crate::header::{Class, ElfType, Encoding, Endian, Machine};
+use crate::program::{ElfProgramHeader, ProgramType};
+use crate::section::{ElfSectionHeader, SectionFlags, SectionType};
+use crate::writer::ElfWriter;
+
+pub(crate) const ELF64_LE: Encoding = Encoding {
+    class: Class::Elf64,
+    endian: Endian::Little,
+};
+
+/// Header of a section of `sh_type` with every other field zero. The writer fills in
+/// `sh_name`, `sh_offset` and `sh_size`.
+pub(crate) fn section_header(sh_type: SectionType) -> ElfSectionHeader {
+    ElfSectionHeader {
+        sh_name: 0,
+        sh_type: u32::from(sh_type),
+        sh_flags: 0,
+        sh_addr: 0,
+        sh_offset: 0,
+        sh_size: 0,
+        sh_link: 0,
+        sh_info: 0,
+        sh_addralign: 1,
+        sh_entsize: 0,
+    }
+}
+
+/// A writable `PT_LOAD` of `memsz` bytes at `address`.
+pub(crate) fn load_segment(address: u64, memsz: u64) -> ElfProgramHeader {
+    ElfProgramHeader {
+        p_type: u32::from(ProgramType::Load),
+        p_flags: 6,
+        p_offset: 0,
+        p_vaddr: address,
+        p_paddr: address,
+        p_filesz: 0,
+        p_memsz: memsz,
+        p_align: 0x1000,
+    }
+}
+
+/// A file of `elf_type` whose only segment holds `contents` at `address`, then zeros up to
+/// `memsz` bytes.
+pub(crate) fn file_with_segment(
+    elf_type: ElfType,
+    address: u64,
+    contents: &[u8],
+    memsz: u64,
+) -> Vec<u8> {
+    let mut writer = ElfWriter::new(ELF64_LE, elf_type, Machine::X86_64);
+    writer.segments.push(load_segment(address, memsz));
+    writer.add_section(
+        ".data",
+        ElfSectionHeader {
+            sh_flags: SectionFlags::ALLOC.0 | SectionFlags::WRITE.0,
+            sh_addr: address,
+            ..section_header(SectionType::Progbits)
+        },
+        contents.to_vec(),
+    );
+    writer.to_bytes().unwrap()
+}
+
+/// A note of `name` and `desc`, both padded to `align`.
+pub(crate) fn note(name: &str, n_type: u32, desc: &[u8], align: usize) -> Vec<u8> {
+    let mut binary = Vec::new();
+    for value in [name.len() as u32 + 1, desc.len() as u32, n_type].iter() {
+        binary.extend_from_slice(&value.to_le_bytes());
+    }
+    binary.extend_from_slice(name.as_bytes());
+    binary.push(0);
+    binary.resize(binary.len().next_multiple_of(align), 0);
+    binary.extend_from_slice(desc);
+    binary.resize(binary.len().next_multiple_of(align), 0);
+    binary
+}
@@ -0,0 +1,585 @@
+use crate::error::ElfError;
+use crate::file::ElfFile;
+use crate::header::ELF_IDENTIFICATION_SIZE;
+use crate::loader::ElfLoader;
+use crate::program::{ElfProgramHeader, ProgramType};
+use crate::section::{ElfSectionHeader, SectionType};
+use std::fmt;
+
+/// One problem found by `ElfFile::validate`.
+#[derive(Debug)]
+pub enum ValidationIssue {
+    /// Part of the file could not be read, so the checks depending on it were skipped.
+    Error(ElfError),
+    /// `e_ehsize`, `e_phentsize` or `e_shentsize` disagrees with the size of the structure.
+    HeaderSizeMismatch {
+        field: &'static str,
+        value: u16,
+        expected: usize,
+    },
+    SegmentPastEnd {
+        index: usize,
+        offset: u64,
+        size: u64,
+        file_size: u64,
+    },
+    /// `p_align` is neither 0, 1 nor a power of two.
+    SegmentBadAlignment { index: usize, align: u64 },
+    /// `p_offset` and `p_vaddr` of a `PT_LOAD` are not congruent modulo `p_align`.
+    SegmentMisaligned {
+        index: usize,
+        offset: u64,
+        vaddr: u64,
+        align: u64,
+    },
+    /// A `PT_LOAD` has more bytes in the file than in memory.
+    SegmentFileSizeTooLarge {
+        index: usize,
+        file_size: u64,
+        memory_size: u64,
+    },
+    /// Two `PT_LOAD` segments share virtual addresses.
+    OverlappingSegments { first: usize, second: usize },
+    SectionPastEnd {
+        index: usize,
+        offset: u64,
+        size: u64,
+        file_size: u64,
+    },
+    /// `sh_addralign` is neither 0, 1 nor a power of two.
+    SectionBadAlignment { index: usize, align: u64 },
+    /// `sh_entsize` differs from the entry size of the section type.
+    EntrySizeMismatch {
+        index: usize,
+        entry_size: u64,
+        expected: usize,
+    },
+    /// `sh_size` is not a multiple of `sh_entsize`.
+    PartialEntry {
+        index: usize,
+        size: u64,
+        entry_size: u64,
+    },
+    /// `sh_link` points outside of the section header table.
+    BadSectionLink {
+        index: usize,
+        link: u32,
+        count: usize,
+    },
+    /// A `SHT_STRTAB` that does not start and end with NUL.
+    BadStringTable { index: usize },
+    /// `e_shstrndx` points outside of the section header table.
+    BadSectionNameTableIndex { index: usize, count: usize },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ValidationIssue::Error(error) => write!(f, "{}", error),
+            ValidationIssue::HeaderSizeMismatch {
+                field,
+                value,
+                expected,
+            } => write!(f, "{} is {} but should be {}", field, value, expected),
+            ValidationIssue::SegmentPastEnd {
+                index,
+                offset,
+                size,
+                file_size,
+            } => write!(
+                f,
+                "segment {} ({:#x} bytes at {:#x}) ends past the end of the file ({:#x} bytes)",
+                index, size, offset, file_size
+            ),
+            ValidationIssue::SegmentBadAlignment { index, align } => {
+                write!(
+                    f,
+                    "segment {} alignment {:#x} is not a power of two",
+                    index, align
+                )
+            }
+            ValidationIssue::SegmentMisaligned {
+                index,
+                offset,
+                vaddr,
+                align,
+            } => write!(
+                f,
+                "segment {} offset {:#x} and address {:#x} differ modulo alignment {:#x}",
+                index, offset, vaddr, align
+            ),
+            ValidationIssue::SegmentFileSizeTooLarge {
+                index,
+                file_size,
+                memory_size,
+            } => write!(
+                f,
+                "segment {} file size {:#x} is larger than its memory size {:#x}",
+                index, file_size, memory_size
+            ),
+            ValidationIssue::OverlappingSegments { first, second } => {
+                write!(f, "segments {} and {} overlap in memory", first, second)
+            }
+            ValidationIssue::SectionPastEnd {
+                index,
+                offset,
+                size,
+                file_size,
+            } => write!(
+                f,
+                "section {} ({:#x} bytes at {:#x}) ends past the end of the file ({:#x} bytes)",
+                index, size, offset, file_size
+            ),
+            ValidationIssue::SectionBadAlignment { index, align } => {
+                write!(
+                    f,
+                    "section {} alignment {:#x} is not a power of two",
+                    index, align
+                )
+            }
+            ValidationIssue::EntrySizeMismatch {
+                index,
+                entry_size,
+                expected,
+            } => write!(
+                f,
+                "section {} entry size is {} but should be {}",
+                index, entry_size, expected
+            ),
+            ValidationIssue::PartialEntry {
+                index,
+                size,
+                entry_size,
+            } => write!(
+                f,
+                "section {} size {:#x} is not a multiple of its entry size {}",
+                index, size, entry_size
+            ),
+            ValidationIssue::BadSectionLink { index, link, count } => write!(
+                f,
+                "section {} links to section {} but there are {} sections",
+                index, link, count
+            ),
+            ValidationIssue::BadStringTable { index } => {
+                write!(f, "string table in section {} is not NUL terminated", index)
+            }
+            ValidationIssue::BadSectionNameTableIndex { index, count } => write!(
+                f,
+                "section name table index {} is out of {} sections",
+                index, count
+            ),
+        }
+    }
+}
+
+impl<'a> ElfFile<'a> {
+    /// Checks the file against the specification and returns every problem found.
+    ///
+    /// Reading never stops at the first problem; parts that cannot be read are reported
+    /// as `ValidationIssue::Error` and the checks that need them are skipped.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::<ValidationIssue>::new();
+        self.validate_header(&mut issues);
+        match self.get_program_headers() {
+            Ok(headers) => self.validate_segments(&headers, &mut issues),
+            Err(error) => issues.push(ValidationIssue::Error(error)),
+        }
+        match self.get_section_headers() {
+            Ok(headers) => self.validate_sections(headers, &mut issues),
+            Err(error) => issues.push(ValidationIssue::Error(error)),
+        }
+        issues
+    }
+
+    fn validate_header(&self, issues: &mut Vec<ValidationIssue>) {
+        let header = self.get_elf_header();
+        let encoding = self.get_encoding();
+        let mut check = |field: &'static str, value: u16, expected: usize| {
+            if value as usize != expected {
+                issues.push(ValidationIssue::HeaderSizeMismatch {
+                    field,
+                    value,
+                    expected,
+                });
+            }
+        };
+        check(
+            "e_ehsize",
+            header.e_ehsize,
+            ELF_IDENTIFICATION_SIZE + encoding.header_size(),
+        );
+        if header.e_phnum != 0 {
+            check(
+                "e_phentsize",
+                header.e_phentsize,
+                encoding.program_header_size(),
+            );
+        }
+        if header.e_shoff != 0 {
+            check(
+                "e_shentsize",
+                header.e_shentsize,
+                encoding.section_header_size(),
+            );
+        }
+    }
+
+    fn validate_segments(&self, headers: &[ElfProgramHeader], issues: &mut Vec<ValidationIssue>) {
+        let file_size = self.data().len() as u64;
+        for (index, header) in headers.iter().enumerate() {
+            if header
+                .p_offset
+                .checked_add(header.p_filesz)
+                .is_none_or(|end| end > file_size)
+            {
+                issues.push(ValidationIssue::SegmentPastEnd {
+                    index,
+                    offset: header.p_offset,
+                    size: header.p_filesz,
+                    file_size,
+                });
+            }
+            if header.p_align > 1 && !header.p_align.is_power_of_two() {
+                issues.push(ValidationIssue::SegmentBadAlignment {
+                    index,
+                    align: header.p_align,
+                });
+                continue;
+            }
+            if ProgramType::from(header.p_type) != ProgramType::Load {
+                continue;
+            }
+            if header.p_align > 1
+                && header.p_offset % header.p_align != header.p_vaddr % header.p_align
+            {
+                issues.push(ValidationIssue::SegmentMisaligned {
+                    index,
+                    offset: header.p_offset,
+                    vaddr: header.p_vaddr,
+                    align: header.p_align,
+                });
+            }
+            if header.p_filesz > header.p_memsz {
+                issues.push(ValidationIssue::SegmentFileSizeTooLarge {
+                    index,
+                    file_size: header.p_filesz,
+                    memory_size: header.p_memsz,
+                });
+            }
+        }
+
+        let loads = headers
+            .iter()
+            .enumerate()
+            .filter(|(_, header)| {
+                ProgramType::from(header.p_type) == ProgramType::Load && header.p_memsz != 0
+            })
+            .collect::<Vec<_>>();
+        for (i, (first, a)) in loads.iter().enumerate() {
+            for (second, b) in loads[i + 1..].iter() {
+                let a_end = a.p_vaddr.saturating_add(a.p_memsz);
+                let b_end = b.p_vaddr.saturating_add(b.p_memsz);
+                if a.p_vaddr < b_end && b.p_vaddr < a_end {
+                    issues.push(ValidationIssue::OverlappingSegments {
+                        first: *first,
+                        second: *second,
+                    });
+                }
+            }
+        }
+    }
+
+    fn validate_sections(&self, headers: &[ElfSectionHeader], issues: &mut Vec<ValidationIssue>) {
+        let file_size = self.data().len() as u64;
+        let count = headers.len();
+        if let Ok(index) = self.get_section_name_table_index() {
+            if index != 0 && index >= count {
+                issues.push(ValidationIssue::BadSectionNameTableIndex { index, count });
+            }
+        }
+        for (index, header) in headers.iter().enumerate().skip(1) {
+            let section_type = SectionType::from(header.sh_type);
+            let in_file = section_type != SectionType::Nobits;
+            if in_file
+                && header
+                    .sh_offset
+                    .checked_add(header.sh_size)
+                    .is_none_or(|end| end > file_size)
+            {
+                issues.push(ValidationIssue::SectionPastEnd {
+                    index,
+                    offset: header.sh_offset,
+                    size: header.sh_size,
+                    file_size,
+                });
+                continue;
+            }
+            if header.sh_addralign > 1 && !header.sh_addralign.is_power_of_two() {
+                issues.push(ValidationIssue::SectionBadAlignment {
+                    index,
+                    align: header.sh_addralign,
+                });
+            }
+            if let Some(expected) = self.get_entry_size(section_type) {
+                if header.sh_entsize != expected as u64 {
+                    issues.push(ValidationIssue::EntrySizeMismatch {
+                        index,
+                        entry_size: header.sh_entsize,
+                        expected,
+                    });
+                }
+            }
+            if header.sh_entsize != 0 && header.sh_size % header.sh_entsize != 0 {
+                issues.push(ValidationIssue::PartialEntry {
+                    index,
+                    size: header.sh_size,
+                    entry_size: header.sh_entsize,
+                });
+            }
+            if has_link(section_type) && header.sh_link as usize >= count {
+                issues.push(ValidationIssue::BadSectionLink {
+                    index,
+                    link: header.sh_link,
+                    count,
+                });
+            }
+            if section_type == SectionType::Strtab && header.sh_size != 0 {
+                if let Ok(data) = self.get_section_data(header) {
+                    if data.first() != Some(&0) || data.last() != Some(&0) {
+                        issues.push(ValidationIssue::BadStringTable { index });
+                    }
+                }
+            }
+        }
+    }
+
+    /// Entry size required for sections of `section_type`, `None` when it is not fixed.
+    fn get_entry_size(&self, section_type: SectionType) -> Option<usize> {
+        let encoding = self.get_encoding();
+        match section_type {
+            SectionType::Symtab | SectionType::Dynsym => Some(encoding.symbol_entry_size()),
+            SectionType::Rel => Some(encoding.rel_entry_size()),
+            SectionType::Rela => Some(encoding.rela_entry_size()),
+            SectionType::Dynamic => Some(encoding.dynamic_entry_size()),
+            SectionType::SymtabShndx => Some(4),
+            SectionType::GnuVersym => Some(2),
+            _ => None,
+        }
+    }
+}
+
+/// Section types whose `sh_link` holds a section index.
+fn has_link(section_type: SectionType) -> bool {
+    matches!(
+        section_type,
+        SectionType::Symtab
+            | SectionType::Dynsym
+            | SectionType::Rel
+            | SectionType::Rela
+            | SectionType::Dynamic
+            | SectionType::Hash
+            | SectionType::GnuHash
+            | SectionType::SymtabShndx
+            | SectionType::GnuVersym
+            | SectionType::GnuVerneed
+            | SectionType::GnuVerdef
+    )
+}
+
+impl ElfLoader {
+    /// Problems found by `ElfFile::validate`, or the error that kept the file from being parsed.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        match self.elf_file() {
+            Ok(file) => file.validate(),
+            Err(error) => vec![ValidationIssue::Error(error)],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::{ElfType, Machine};
+    use crate::section::SectionFlags;
+    use crate::testing::{load_segment, section_header, ELF64_LE};
+    use crate::writer::ElfWriter;
+
+    /// An executable with a code and a data segment, a relocation and a string table, that
+    /// `validate` finds nothing wrong with.
+    fn executable() -> Vec<u8> {
+        let mut writer = ElfWriter::new(ELF64_LE, ElfType::Exec, Machine::X86_64);
+        writer.segments.push(load_segment(0x40_0000, 0x10));
+        writer.segments.push(load_segment(0x60_0000, 0x10));
+        for (name, flags, address) in [
+            (".text", SectionFlags::EXECINSTR, 0x40_0000),
+            (".data", SectionFlags::WRITE, 0x60_0000),
+        ] {
+            writer.add_section(
+                name,
+                ElfSectionHeader {
+                    sh_flags: SectionFlags::ALLOC.0 | flags.0,
+                    sh_addr: address,
+                    ..section_header(SectionType::Progbits)
+                },
+                vec![0x90; 4],
+            );
+        }
+        writer.add_section(
+            ".rela.dyn",
+            ElfSectionHeader {
+                sh_addralign: 8,
+                sh_entsize: ELF64_LE.rela_entry_size() as u64,
+                ..section_header(SectionType::Rela)
+            },
+            vec![0; ELF64_LE.rela_entry_size()],
+        );
+        writer.add_section(
+            ".strings",
+            section_header(SectionType::Strtab),
+            b"\0a\0".to_vec(),
+        );
+        writer.to_bytes().unwrap()
+    }
+
+    /// Issues of `executable` once `edit` changed its headers in place.
+    fn issues(edit: impl FnOnce(&mut ElfWriter)) -> Vec<ValidationIssue> {
+        let binary = executable();
+        let file = ElfFile::new(&binary).unwrap();
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        edit(&mut writer);
+        let binary = writer.to_bytes().unwrap();
+        ElfFile::new(&binary).unwrap().validate()
+    }
+
+    fn section<'w, 'a>(writer: &'w mut ElfWriter<'a>, name: &str) -> &'w mut ElfSectionHeader {
+        let index = writer.find_section(name).unwrap();
+        &mut writer.sections[index].header
+    }
+
+    #[test]
+    fn accepts_a_well_formed_file() {
+        let issues = issues(|_| {});
+        assert!(issues.is_empty(), "{:?}", issues);
+    }
+
+    #[test]
+    fn reports_header_issues() {
+        assert!(matches!(
+            issues(|writer| writer.header.e_ehsize = 52)[..],
+            [ValidationIssue::HeaderSizeMismatch {
+                field: "e_ehsize",
+                value: 52,
+                expected: 64,
+            }]
+        ));
+        assert!(matches!(
+            issues(|writer| writer.header.e_shstrndx = 99)[..],
+            [ValidationIssue::BadSectionNameTableIndex {
+                index: 99,
+                count: 6,
+            }]
+        ));
+
+        // A program header table past the end of the file cannot be checked at all.
+        let mut binary = executable();
+        let file_size = binary.len() as u64;
+        binary[32..40].copy_from_slice(&file_size.to_le_bytes());
+        assert!(matches!(
+            ElfFile::new(&binary).unwrap().validate()[..],
+            [ValidationIssue::Error(ElfError::OffsetOutOfRange { .. })]
+        ));
+    }
+
+    #[test]
+    fn reports_segment_issues() {
+        assert!(matches!(
+            issues(|writer| {
+                writer.segments[1].p_filesz = 0x10_0000;
+                writer.segments[1].p_memsz = 0x10_0000;
+            })[..],
+            [ValidationIssue::SegmentPastEnd {
+                index: 1,
+                size: 0x10_0000,
+                ..
+            }]
+        ));
+        assert!(matches!(
+            issues(|writer| writer.segments[1].p_align = 0x1800)[..],
+            [ValidationIssue::SegmentBadAlignment {
+                index: 1,
+                align: 0x1800,
+            }]
+        ));
+        assert!(matches!(
+            issues(|writer| writer.segments[1].p_vaddr += 8)[..],
+            [ValidationIssue::SegmentMisaligned {
+                index: 1,
+                vaddr: 0x60_0008,
+                align: 0x1000,
+                ..
+            }]
+        ));
+        assert!(matches!(
+            issues(|writer| writer.segments[1].p_memsz = 2)[..],
+            [ValidationIssue::SegmentFileSizeTooLarge {
+                index: 1,
+                memory_size: 2,
+                ..
+            }]
+        ));
+        assert!(matches!(
+            issues(|writer| writer.segments[0].p_memsz = 0x20_0001)[..],
+            [ValidationIssue::OverlappingSegments {
+                first: 0,
+                second: 1,
+            }]
+        ));
+    }
+
+    #[test]
+    fn reports_section_issues() {
+        assert!(matches!(
+            issues(|writer| section(writer, ".data").sh_size = 0x10_0000)[..],
+            [ValidationIssue::SectionPastEnd {
+                index: 2,
+                size: 0x10_0000,
+                ..
+            }]
+        ));
+        assert!(matches!(
+            issues(|writer| section(writer, ".data").sh_addralign = 6)[..],
+            [ValidationIssue::SectionBadAlignment { index: 2, align: 6 }]
+        ));
+        assert!(matches!(
+            issues(|writer| section(writer, ".rela.dyn").sh_entsize = 12)[..],
+            [ValidationIssue::EntrySizeMismatch {
+                index: 3,
+                entry_size: 12,
+                expected: 24,
+            }]
+        ));
+        assert!(matches!(
+            issues(|writer| section(writer, ".data").sh_entsize = 3)[..],
+            [ValidationIssue::PartialEntry {
+                index: 2,
+                size: 4,
+                entry_size: 3,
+            }]
+        ));
+        assert!(matches!(
+            issues(|writer| section(writer, ".rela.dyn").sh_link = 6)[..],
+            [ValidationIssue::BadSectionLink {
+                index: 3,
+                link: 6,
+                count: 6,
+            }]
+        ));
+        assert!(matches!(
+            issues(|writer| {
+                let index = writer.find_section(".strings").unwrap();
+                writer.sections[index].data = b"abc"[..].into();
+            })[..],
+            [ValidationIssue::BadStringTable { index: 4 }]
+        ));
+    }
+}
@@ -0,0 +1,479 @@
+use crate::error::ElfError;
+use crate::file::ElfFile;
+use crate::reader::Reader;
+use crate::section::{ElfSectionHeader, SectionType};
+use std::collections::HashMap;
+use std::fmt;
+
+/// `.gnu.version` index of local symbols.
+pub const VER_NDX_LOCAL: u16 = 0;
+/// `.gnu.version` index of global symbols without a version.
+pub const VER_NDX_GLOBAL: u16 = 1;
+const VERSYM_HIDDEN: u16 = 0x8000;
+
+/// `vd_flags` of the definition that names the file itself.
+pub const VER_FLG_BASE: u16 = 0x1;
+pub const VER_FLG_WEAK: u16 = 0x2;
+
+const VERNEED_SIZE: usize = 16;
+const VERNAUX_SIZE: usize = 16;
+const VERDEF_SIZE: usize = 20;
+const VERDAUX_SIZE: usize = 8;
+
+/// Version attached to a dynamic symbol through `.gnu.version`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolVersion<'a> {
+    pub name: &'a str,
+    /// Library the version is required from, `None` for versions defined by this file.
+    pub file: Option<&'a str>,
+    /// The symbol is not the default version and is only bound as `name@version`.
+    pub hidden: bool,
+}
+
+impl<'a> fmt::Display for SymbolVersion<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.hidden {
+            write!(f, " (hidden)")?;
+        }
+        if let Some(file) = self.file {
+            write!(f, " from {}", file)?;
+        }
+        Ok(())
+    }
+}
+
+/// One `Elf_Verneed` of `.gnu.version_r`: versions required from a library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionNeed<'a> {
+    pub version: u16,
+    pub file: &'a str,
+    pub entries: Vec<VersionNeedEntry<'a>>,
+}
+
+/// One `Elf_Vernaux`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VersionNeedEntry<'a> {
+    pub hash: u32,
+    pub flags: u16,
+    /// Index used in `.gnu.version`.
+    pub index: u16,
+    pub name: &'a str,
+}
+
+impl<'a> fmt::Display for VersionNeed<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "---VersionNeed---\nFile      = {}", self.file)?;
+        for entry in self.entries.iter() {
+            write!(
+                f,
+                "\n{:#06x}    = {} (Flags: {:#x}, Hash: {:#010x})",
+                entry.index, entry.name, entry.flags, entry.hash
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// One `Elf_Verdef` of `.gnu.version_d`: a version defined by this file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionDefinition<'a> {
+    pub version: u16,
+    pub flags: u16,
+    /// Index used in `.gnu.version`.
+    pub index: u16,
+    pub hash: u32,
+    /// The version name followed by the names of the versions it inherits from.
+    pub names: Vec<&'a str>,
+}
+
+impl<'a> VersionDefinition<'a> {
+    pub fn name(&self) -> Option<&'a str> {
+        self.names.first().copied()
+    }
+}
+
+impl<'a> fmt::Display for VersionDefinition<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "---VersionDefinition---
+Index     = {}
+Flags     = {:#x}
+Hash      = {:#010x}
+Name      = {}",
+            self.index,
+            self.flags,
+            self.hash,
+            self.name().unwrap_or(""),
+        )?;
+        if self.names.len() > 1 {
+            write!(f, "\nParents   = {}", self.names[1..].join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a> ElfFile<'a> {
+    /// Entries of `.gnu.version_r`.
+    pub fn get_version_needs(&self) -> Result<Vec<VersionNeed<'a>>, ElfError> {
+        let header = self.get_section_header(self.find_section_index(SectionType::GnuVerneed)?)?;
+        let data = self.get_section_data(header)?;
+        let strings = self.get_string_table(self.get_section_header(header.sh_link as usize)?)?;
+        let mut needs = Vec::<VersionNeed<'a>>::new();
+        let mut offset = 0;
+        for _ in 0..header.sh_info {
+            let mut reader = Reader::new(
+                self.get_version_bytes(header, data, offset, VERNEED_SIZE)?,
+                self.get_encoding(),
+            );
+            let version = reader.read_u16();
+            let count = reader.read_u16();
+            let file = strings.get_str(reader.read_u32() as u64)?;
+            let aux = reader.read_u32() as usize;
+            let next = reader.read_u32() as usize;
+
+            let mut entries = Vec::<VersionNeedEntry<'a>>::new();
+            let mut aux_offset = offset + aux;
+            for _ in 0..count {
+                let mut reader = Reader::new(
+                    self.get_version_bytes(header, data, aux_offset, VERNAUX_SIZE)?,
+                    self.get_encoding(),
+                );
+                let hash = reader.read_u32();
+                let flags = reader.read_u16();
+                let index = reader.read_u16();
+                let name = strings.get_str(reader.read_u32() as u64)?;
+                let aux_next = reader.read_u32() as usize;
+                entries.push(VersionNeedEntry {
+                    hash,
+                    flags,
+                    index,
+                    name,
+                });
+                if aux_next == 0 {
+                    break;
+                }
+                aux_offset += aux_next;
+            }
+            needs.push(VersionNeed {
+                version,
+                file,
+                entries,
+            });
+            if next == 0 {
+                break;
+            }
+            offset += next;
+        }
+        Ok(needs)
+    }
+
+    /// Entries of `.gnu.version_d`.
+    pub fn get_version_definitions(&self) -> Result<Vec<VersionDefinition<'a>>, ElfError> {
+        let header = self.get_section_header(self.find_section_index(SectionType::GnuVerdef)?)?;
+        let data = self.get_section_data(header)?;
+        let strings = self.get_string_table(self.get_section_header(header.sh_link as usize)?)?;
+        let mut definitions = Vec::<VersionDefinition<'a>>::new();
+        let mut offset = 0;
+        for _ in 0..header.sh_info {
+            let mut reader = Reader::new(
+                self.get_version_bytes(header, data, offset, VERDEF_SIZE)?,
+                self.get_encoding(),
+            );
+            let version = reader.read_u16();
+            let flags = reader.read_u16();
+            let index = reader.read_u16();
+            let count = reader.read_u16();
+            let hash = reader.read_u32();
+            let aux = reader.read_u32() as usize;
+            let next = reader.read_u32() as usize;
+
+            let mut names = Vec::<&'a str>::new();
+            let mut aux_offset = offset + aux;
+            for _ in 0..count {
+                let mut reader = Reader::new(
+                    self.get_version_bytes(header, data, aux_offset, VERDAUX_SIZE)?,
+                    self.get_encoding(),
+                );
+                names.push(strings.get_str(reader.read_u32() as u64)?);
+                let aux_next = reader.read_u32() as usize;
+                if aux_next == 0 {
+                    break;
+                }
+                aux_offset += aux_next;
+            }
+            definitions.push(VersionDefinition {
+                version,
+                flags,
+                index,
+                hash,
+                names,
+            });
+            if next == 0 {
+                break;
+            }
+            offset += next;
+        }
+        Ok(definitions)
+    }
+
+    /// Versions of the symbols in the symbol table at `index`, in symbol order.
+    ///
+    /// Empty when no `.gnu.version` refers to the table. Symbols that are local,
+    /// unversioned or use an unknown index have no version.
+    pub fn get_symbol_versions(
+        &self,
+        index: usize,
+    ) -> Result<Vec<Option<SymbolVersion<'a>>>, ElfError> {
+        let versym = match self.get_section_headers()?.iter().find(|header| {
+            header.sh_type == u32::from(SectionType::GnuVersym) && header.sh_link as usize == index
+        }) {
+            Some(versym) => versym,
+            None => return Ok(Vec::new()),
+        };
+
+        let mut names = HashMap::<u16, (&'a str, Option<&'a str>)>::new();
+        for need in self.get_version_needs().or_else(ignore_missing)?.iter() {
+            for entry in need.entries.iter() {
+                names.insert(entry.index, (entry.name, Some(need.file)));
+            }
+        }
+        for definition in self
+            .get_version_definitions()
+            .or_else(ignore_missing)?
+            .iter()
+        {
+            if let Some(name) = definition.name() {
+                names.insert(definition.index, (name, None));
+            }
+        }
+
+        let data = self.get_section_data(versym)?;
+        Ok(data
+            .chunks_exact(2)
+            .map(|chunk| {
+                let value = Reader::new(chunk, self.get_encoding()).read_u16();
+                let version_index = value & !VERSYM_HIDDEN;
+                if version_index == VER_NDX_LOCAL || version_index == VER_NDX_GLOBAL {
+                    return None;
+                }
+                names.get(&version_index).map(|(name, file)| SymbolVersion {
+                    name,
+                    file: *file,
+                    hidden: value & VERSYM_HIDDEN != 0,
+                })
+            })
+            .collect())
+    }
+
+    fn get_version_bytes(
+        &self,
+        header: &ElfSectionHeader,
+        data: &'a [u8],
+        offset: usize,
+        size: usize,
+    ) -> Result<&'a [u8], ElfError> {
+        data.get(offset..offset + size)
+            .ok_or(ElfError::OffsetOutOfRange {
+                offset: header.sh_offset + offset as u64,
+                size: size as u64,
+                file_size: self.data().len() as u64,
+            })
+    }
+}
+
+fn ignore_missing<T>(error: ElfError) -> Result<Vec<T>, ElfError> {
+    match error {
+        ElfError::MissingSection { .. } => Ok(Vec::new()),
+        error => Err(error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::sysv_hash;
+    use crate::header::{ElfType, Machine};
+    use crate::symbol::{ElfSymbolEntry, SHN_UNDEF};
+    use crate::testing::{section_header, ELF64_LE};
+    use crate::writer::ElfWriter;
+
+    /// Little-endian fields, each `u16` or `u32` as `size` says.
+    fn fields(values: &[(u32, usize)]) -> Vec<u8> {
+        let mut binary = Vec::new();
+        for (value, size) in values.iter() {
+            binary.extend_from_slice(&value.to_le_bytes()[..*size]);
+        }
+        binary
+    }
+
+    struct Strings(Vec<u8>);
+
+    impl Strings {
+        fn add(&mut self, name: &str) -> u32 {
+            let index = self.0.len() as u32;
+            self.0.extend_from_slice(name.as_bytes());
+            self.0.push(0);
+            index
+        }
+    }
+
+    fn verdef(flags: u16, index: u16, names: &[u32], hash: u32, next: u32) -> Vec<u8> {
+        let count = names.len() as u32;
+        let mut binary = fields(&[
+            (1, 2),
+            (flags as u32, 2),
+            (index as u32, 2),
+            (count, 2),
+            (hash, 4),
+            (VERDEF_SIZE as u32, 4),
+            (next, 4),
+        ]);
+        for (i, name) in names.iter().enumerate() {
+            let last = i == names.len() - 1;
+            let next = if last { 0 } else { VERDAUX_SIZE as u32 };
+            binary.extend_from_slice(&fields(&[(*name, 4), (next, 4)]));
+        }
+        binary
+    }
+
+    /// A shared object defining `foo@@LIBFOO_1.0` and `foo_old@LIBFOO_0.9`, the first inheriting
+    /// from the second, and requiring `printf@GLIBC_2.2.5` from `libc.so.6`.
+    fn versioned_library() -> Vec<u8> {
+        let mut strings = Strings(vec![0]);
+        let symbol_names = ["printf", "foo", "foo_old", "bar"].map(|name| strings.add(name));
+        let libfoo = strings.add("libfoo.so.1");
+        let new = strings.add("LIBFOO_1.0");
+        let old = strings.add("LIBFOO_0.9");
+        let libc = strings.add("libc.so.6");
+        let glibc = strings.add("GLIBC_2.2.5");
+        let glibc_new = strings.add("GLIBC_2.34");
+
+        let mut symbols = vec![0u8; ELF64_LE.symbol_entry_size()];
+        for (i, name) in symbol_names.iter().enumerate() {
+            let entry = ElfSymbolEntry {
+                st_name: *name,
+                st_info: 0x12,
+                st_other: 0,
+                st_shndx: if i == 0 { SHN_UNDEF } else { 1 },
+                st_value: 0,
+                st_size: 0,
+            };
+            symbols.extend_from_slice(&entry.to_bytes(ELF64_LE));
+        }
+        let versions = fields(&[(0, 2), (4, 2), (2, 2), (0x8003, 2), (1, 2)]);
+
+        let hash = |name: &str| sysv_hash(name.as_bytes());
+        let mut definitions = verdef(VER_FLG_BASE, 1, &[libfoo], hash("libfoo.so.1"), 28);
+        definitions.extend(verdef(0, 2, &[new, old], hash("LIBFOO_1.0"), 36));
+        definitions.extend(verdef(0, 3, &[old], hash("LIBFOO_0.9"), 0));
+
+        let mut needs = fields(&[(1, 2), (2, 2), (libc, 4), (16, 4), (0, 4)]);
+        needs.extend(fields(&[
+            (hash("GLIBC_2.2.5"), 4),
+            (0, 2),
+            (4, 2),
+            (glibc, 4),
+            (16, 4),
+        ]));
+        needs.extend(fields(&[
+            (hash("GLIBC_2.34"), 4),
+            (0, 2),
+            (5, 2),
+            (glibc_new, 4),
+            (0, 4),
+        ]));
+
+        let mut writer = ElfWriter::new(ELF64_LE, ElfType::Dyn, Machine::X86_64);
+        let linked = |sh_type, sh_link, sh_info| ElfSectionHeader {
+            sh_link,
+            sh_info,
+            ..section_header(sh_type)
+        };
+        writer.add_section(".dynstr", section_header(SectionType::Strtab), strings.0);
+        writer.add_section(".dynsym", linked(SectionType::Dynsym, 1, 1), symbols);
+        writer.add_section(
+            ".gnu.version",
+            linked(SectionType::GnuVersym, 2, 0),
+            versions,
+        );
+        writer.add_section(
+            ".gnu.version_d",
+            linked(SectionType::GnuVerdef, 1, 3),
+            definitions,
+        );
+        writer.add_section(
+            ".gnu.version_r",
+            linked(SectionType::GnuVerneed, 1, 1),
+            needs,
+        );
+        writer.to_bytes().unwrap()
+    }
+
+    #[test]
+    fn follows_definition_and_need_chains() {
+        let binary = versioned_library();
+        let file = ElfFile::new(&binary).unwrap();
+        let definitions = file.get_version_definitions().unwrap();
+        assert_eq!(definitions.len(), 3);
+        assert_eq!(definitions[0].flags, VER_FLG_BASE);
+        assert_eq!(definitions[0].name(), Some("libfoo.so.1"));
+        assert_eq!(definitions[1].index, 2);
+        assert_eq!(definitions[1].names, ["LIBFOO_1.0", "LIBFOO_0.9"]);
+        assert_eq!(definitions[1].hash, sysv_hash(b"LIBFOO_1.0"));
+        assert_eq!(definitions[2].names, ["LIBFOO_0.9"]);
+
+        let needs = file.get_version_needs().unwrap();
+        assert_eq!(needs.len(), 1);
+        assert_eq!(needs[0].file, "libc.so.6");
+        let entries = needs[0]
+            .entries
+            .iter()
+            .map(|entry| (entry.index, entry.name))
+            .collect::<Vec<_>>();
+        assert_eq!(entries, [(4, "GLIBC_2.2.5"), (5, "GLIBC_2.34")]);
+    }
+
+    #[test]
+    fn attaches_versions_to_dynamic_symbols() {
+        let binary = versioned_library();
+        let file = ElfFile::new(&binary).unwrap();
+        let versions = file
+            .get_dynamic_symbol_table()
+            .unwrap()
+            .iter()
+            .map(|symbol| (symbol.name, symbol.version.map(|v| v.to_string())))
+            .collect::<Vec<_>>();
+        let version = |name: &str| Some(name.to_string());
+        assert_eq!(
+            versions,
+            [
+                ("", None),
+                ("printf", version("GLIBC_2.2.5 from libc.so.6")),
+                ("foo", version("LIBFOO_1.0")),
+                ("foo_old", version("LIBFOO_0.9 (hidden)")),
+                ("bar", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_chains_past_the_section() {
+        let binary = versioned_library();
+        let file = ElfFile::new(&binary).unwrap();
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        // One more definition than the chain holds makes the last vd_next point past the end.
+        writer.sections[4].header.sh_info = 4;
+        let mut definitions = writer.sections[4].data.to_vec();
+        definitions[80..84].copy_from_slice(&28u32.to_le_bytes());
+        writer.sections[4].data = definitions.into();
+        writer.keep_layout = false;
+        let binary = writer.to_bytes().unwrap();
+        let file = ElfFile::new(&binary).unwrap();
+        assert!(matches!(
+            file.get_version_definitions(),
+            Err(ElfError::OffsetOutOfRange { .. })
+        ));
+    }
+}
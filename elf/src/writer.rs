@@ -0,0 +1,929 @@
+use crate::error::ElfError;
+use crate::file::ElfFile;
+use crate::header::{
+    Class, ElfHeader, ElfIdentification, ElfType, Encoding, Endian, Machine,
+    ELF_IDENTIFICATION_SIZE,
+};
+use crate::program::{ElfProgramHeader, ProgramType};
+use crate::section::{ElfSectionHeader, SectionFlags, SectionType};
+use crate::symbol::{ElfSymbolEntry, SymbolBinding, SHN_LORESERVE, SHN_XINDEX};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+/// `e_phnum` of a file whose program header count is in `sh_info` of the null section.
+const PN_XNUM: u16 = 0xffff;
+
+/// Appends fixed-size fields one after another, the counterpart of `Reader`.
+pub(crate) struct Writer {
+    binary: Vec<u8>,
+    encoding: Encoding,
+}
+
+impl Writer {
+    pub(crate) fn new(encoding: Encoding) -> Writer {
+        Writer {
+            binary: Vec::new(),
+            encoding,
+        }
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.binary
+    }
+
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) {
+        self.binary.extend_from_slice(bytes);
+    }
+
+    pub(crate) fn write_u8(&mut self, value: u8) {
+        self.binary.push(value);
+    }
+
+    pub(crate) fn write_u16(&mut self, value: u16) {
+        let bytes = match self.encoding.endian {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        };
+        self.bytes(&bytes);
+    }
+
+    pub(crate) fn write_u32(&mut self, value: u32) {
+        let bytes = match self.encoding.endian {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        };
+        self.bytes(&bytes);
+    }
+
+    pub(crate) fn write_u64(&mut self, value: u64) {
+        let bytes = match self.encoding.endian {
+            Endian::Little => value.to_le_bytes(),
+            Endian::Big => value.to_be_bytes(),
+        };
+        self.bytes(&bytes);
+    }
+
+    /// Writes an address, offset or size, truncated to 4 bytes in ELF32.
+    pub(crate) fn write_word(&mut self, value: u64) {
+        match self.encoding.class {
+            Class::Elf32 => self.write_u32(value as u32),
+            Class::Elf64 => self.write_u64(value),
+        }
+    }
+
+    /// Writes a signed word such as `r_addend`.
+    pub(crate) fn write_sword(&mut self, value: i64) {
+        self.write_word(value as u64);
+    }
+}
+
+/// A section written by `ElfWriter`.
+#[derive(Debug, Clone)]
+pub struct WriterSection<'a> {
+    pub name: Cow<'a, str>,
+    /// When the writer lays the file out, `sh_name`, `sh_offset` and `sh_size` are filled in
+    /// from `name`, the layout and `data`. The other fields are written as they are.
+    pub header: ElfSectionHeader,
+    /// Contents, empty for `SHT_NOBITS`.
+    pub data: Cow<'a, [u8]>,
+}
+
+/// A symbol of the `.symtab` generated by `ElfWriter`. `st_name` is filled in from `name`.
+#[derive(Debug, Clone)]
+pub struct WriterSymbol<'a> {
+    pub name: Cow<'a, str>,
+    pub entry: ElfSymbolEntry,
+}
+
+/// Serializes an elf file in the class and byte order of its identification.
+///
+/// `from_file` keeps every offset of the file it was made from, so an unmodified file is
+/// written back byte for byte. A writer made with `new`, or with `keep_layout` cleared,
+/// is laid out again when written: the string tables are rebuilt, sections are placed one
+/// after another, and the `PT_LOAD` segments are fitted to the sections they cover.
+#[derive(Debug, Clone)]
+pub struct ElfWriter<'a> {
+    pub identification: ElfIdentification,
+    /// The fields describing the layout are overwritten when the file is laid out.
+    pub header: ElfHeader,
+    /// Program headers. On layout `p_offset` and `p_filesz` are computed; `p_vaddr`, `p_memsz`
+    /// and `p_align` decide which sections each segment covers.
+    pub segments: Vec<ElfProgramHeader>,
+    /// Sections in section header table order, starting with the null section.
+    pub sections: Vec<WriterSection<'a>>,
+    /// Symbols of a `.symtab` to generate on layout, together with its `.strtab`.
+    /// Empty to leave the symbol tables in `sections` as they are.
+    pub symbols: Vec<WriterSymbol<'a>>,
+    /// Write at the offsets already in the headers instead of laying the file out again.
+    pub keep_layout: bool,
+    /// Contents of the file `from_file` read, which fill the bytes no header or section covers.
+    original: Option<&'a [u8]>,
+}
+
+impl<'a> ElfWriter<'a> {
+    /// Empty file with only the null section.
+    pub fn new(encoding: Encoding, elf_type: ElfType, machine: Machine) -> ElfWriter<'a> {
+        ElfWriter {
+            identification: ElfIdentification::for_encoding(encoding),
+            header: ElfHeader {
+                e_type: u16::from(elf_type),
+                e_machine: u16::from(machine),
+                e_version: 1,
+                e_entry: 0,
+                e_phoff: 0,
+                e_shoff: 0,
+                e_flags: 0,
+                e_ehsize: 0,
+                e_phentsize: 0,
+                e_phnum: 0,
+                e_shentsize: 0,
+                e_shnum: 0,
+                e_shstrndx: 0,
+            },
+            segments: Vec::new(),
+            sections: vec![null_section()],
+            symbols: Vec::new(),
+            keep_layout: false,
+            original: None,
+        }
+    }
+
+    /// Writer holding every header and section of `file`, borrowing their contents.
+    pub fn from_file(file: &ElfFile<'a>) -> Result<ElfWriter<'a>, ElfError> {
+        let names = file.get_section_names()?;
+        let mut sections = Vec::<WriterSection<'a>>::new();
+        for (header, name) in file.get_section_headers()?.iter().zip(names) {
+            sections.push(WriterSection {
+                name: Cow::Borrowed(name),
+                header: *header,
+                data: Cow::Borrowed(file.get_section_data(header)?),
+            });
+        }
+        Ok(ElfWriter {
+            identification: file.get_identification().clone(),
+            header: *file.get_elf_header(),
+            segments: file.get_program_headers()?,
+            sections,
+            symbols: Vec::new(),
+            keep_layout: true,
+            original: Some(file.data()),
+        })
+    }
+
+    pub fn encoding(&self) -> Result<Encoding, ElfError> {
+        self.identification.encoding()
+    }
+
+    /// Appends a section and returns its index.
+    pub fn add_section(
+        &mut self,
+        name: impl Into<Cow<'a, str>>,
+        header: ElfSectionHeader,
+        data: impl Into<Cow<'a, [u8]>>,
+    ) -> usize {
+        self.sections.push(WriterSection {
+            name: name.into(),
+            header,
+            data: data.into(),
+        });
+        self.sections.len() - 1
+    }
+
+    /// Index of the first section called `name`.
+    pub fn find_section(&self, name: &str) -> Option<usize> {
+        self.sections
+            .iter()
+            .position(|section| section.name == name)
+    }
+
+    /// Serialized file, laid out first unless `keep_layout` is set.
+    pub fn to_bytes(&mut self) -> Result<Vec<u8>, ElfError> {
+        if !self.keep_layout {
+            self.layout()?;
+        }
+        let encoding = self.encoding()?;
+        let header = &self.header;
+        let program_header_size = encoding.program_header_size() as u64;
+        let section_header_size = encoding.section_header_size() as u64;
+        let segments = table_offsets(
+            header.e_phoff,
+            self.segments.len(),
+            header.e_phentsize,
+            program_header_size,
+        )?;
+        let sections = table_offsets(
+            header.e_shoff,
+            self.sections.len(),
+            header.e_shentsize,
+            section_header_size,
+        )?;
+        let mut size = (ELF_IDENTIFICATION_SIZE + encoding.header_size()) as u64;
+        if let Some(last) = segments.last() {
+            size = size.max(last + program_header_size);
+        }
+        if let Some(last) = sections.last() {
+            size = size.max(last + section_header_size);
+        }
+        for section in self.sections.iter() {
+            if section.header.sh_type != u32::from(SectionType::Nobits) && !section.data.is_empty()
+            {
+                size = size.max(range_end(
+                    section.header.sh_offset,
+                    section.data.len() as u64,
+                )?);
+            }
+        }
+        if self.keep_layout {
+            let limit = self.size_limit(encoding);
+            if size > limit {
+                return Err(ElfError::FileTooLarge { size, limit });
+            }
+        }
+        let mut binary = vec![0u8; size as usize];
+        if let Some(original) = self.original {
+            let length = original.len().min(binary.len());
+            binary[..length].copy_from_slice(&original[..length]);
+            binary.extend_from_slice(&original[length..]);
+        }
+
+        // Every non-empty range written below ends within `size`.
+        let mut put = |offset: u64, bytes: &[u8]| {
+            if bytes.is_empty() {
+                return;
+            }
+            let offset = offset as usize;
+            binary[offset..offset + bytes.len()].copy_from_slice(bytes);
+        };
+        put(0, &self.identification.to_bytes());
+        put(ELF_IDENTIFICATION_SIZE as u64, &header.to_bytes(encoding));
+        for (offset, segment) in segments.into_iter().zip(self.segments.iter()) {
+            put(offset, &segment.to_bytes(encoding));
+        }
+        for section in self.sections.iter() {
+            if section.header.sh_type != u32::from(SectionType::Nobits) {
+                put(section.header.sh_offset, &section.data);
+            }
+        }
+        for (offset, section) in sections.into_iter().zip(self.sections.iter()) {
+            put(offset, &section.header.to_bytes(encoding));
+        }
+        Ok(binary)
+    }
+
+    /// Size of the largest file `to_bytes` needs in the layout of the headers: the original
+    /// contents, followed by the headers and by every section at its alignment.
+    fn size_limit(&self, encoding: Encoding) -> u64 {
+        let header = &self.header;
+        let tables = [
+            (ELF_IDENTIFICATION_SIZE + encoding.header_size()) as u64,
+            (self.segments.len() as u64).saturating_mul(header.e_phentsize as u64),
+            (self.sections.len() as u64).saturating_mul(header.e_shentsize as u64),
+            encoding.word_size() as u64,
+        ];
+        let sections = self
+            .sections
+            .iter()
+            .map(|section| (section.data.len() as u64).saturating_add(section.header.sh_addralign));
+        let original = self.original.map_or(0, |original| original.len() as u64);
+        tables
+            .iter()
+            .copied()
+            .chain(sections)
+            .fold(original, u64::saturating_add)
+    }
+
+    /// Rebuilds the string and symbol tables and assigns every offset in the headers.
+    ///
+    /// Sections are placed in order after the program headers. An allocated section inside a
+    /// `PT_LOAD` gets an offset congruent with its address, at the same distance from the
+    /// other sections of the segment as in memory. The first `PT_LOAD` also maps the file
+    /// headers when there is room for them below its first section.
+    pub fn layout(&mut self) -> Result<(), ElfError> {
+        let encoding = self.encoding()?;
+        self.original = None;
+        let has_sections = !self.sections.is_empty() || !self.symbols.is_empty();
+        if has_sections {
+            if self.sections.is_empty() {
+                self.sections.push(null_section());
+            }
+            self.build_symbol_table(encoding);
+            self.build_section_name_table();
+        }
+
+        let header_size = (ELF_IDENTIFICATION_SIZE + encoding.header_size()) as u64;
+        let program_header_size = encoding.program_header_size() as u64;
+        let headers_end = header_size + self.segments.len() as u64 * program_header_size;
+        let header = &mut self.header;
+        header.e_ehsize = header_size as u16;
+        if self.segments.is_empty() {
+            header.e_phoff = 0;
+            header.e_phentsize = 0;
+        } else {
+            header.e_phoff = header_size;
+            header.e_phentsize = program_header_size as u16;
+        }
+        // `PN_XNUM` would move the count to the null section, which readers here do not follow.
+        header.e_phnum = match u16::try_from(self.segments.len()) {
+            Ok(count) if count != PN_XNUM => count,
+            _ => {
+                return Err(ElfError::TooManySegments {
+                    count: self.segments.len() as u64,
+                })
+            }
+        };
+
+        // Distance between the addresses and the offsets of each segment, once known.
+        let mut deltas = vec![None; self.segments.len()];
+        if let Some(first) = self.segments.iter().position(is_load) {
+            let segment = &self.segments[first];
+            let lowest = self
+                .sections
+                .iter()
+                .filter(|section| segment_covers(segment, &section.header))
+                .map(|section| section.header.sh_addr)
+                .min();
+            if lowest.is_none_or(|address| address - segment.p_vaddr >= headers_end) {
+                deltas[first] = Some(segment.p_vaddr);
+            }
+        }
+
+        let mut offset = headers_end;
+        for index in 1..self.sections.len() {
+            let section = &mut self.sections[index];
+            let header = &mut section.header;
+            let nobits = header.sh_type == u32::from(SectionType::Nobits);
+            if !nobits {
+                header.sh_size = section.data.len() as u64;
+            }
+            let align = header.sh_addralign.max(1);
+            let segment = self
+                .segments
+                .iter()
+                .position(|segment| is_load(segment) && segment_covers(segment, header));
+            header.sh_offset = match segment {
+                Some(segment) if !nobits => {
+                    let align = align.max(self.segments[segment].p_align);
+                    let delta = match deltas[segment] {
+                        Some(delta) => delta,
+                        None => {
+                            let position = congruent_offset(offset, header.sh_addr, align)?;
+                            *deltas[segment].insert(header.sh_addr.wrapping_sub(position))
+                        }
+                    };
+                    let position = header.sh_addr.wrapping_sub(delta);
+                    if position < offset {
+                        return Err(ElfError::SectionOutOfOrder {
+                            index: index as u64,
+                            address: header.sh_addr,
+                        });
+                    }
+                    position
+                }
+                _ => align_up(offset, align)?,
+            };
+            if !nobits {
+                offset = range_end(header.sh_offset, header.sh_size)?;
+            }
+        }
+
+        for (i, segment) in self.segments.iter_mut().enumerate() {
+            if segment.p_type == u32::from(ProgramType::Phdr) {
+                segment.p_offset = self.header.e_phoff;
+                segment.p_filesz = headers_end - header_size;
+                continue;
+            }
+            let covered = self
+                .sections
+                .iter()
+                .filter(|section| segment_covers(segment, &section.header))
+                .collect::<Vec<_>>();
+            if !is_load(segment) {
+                if let Some(first) = covered.first() {
+                    segment.p_offset = first
+                        .header
+                        .sh_offset
+                        .saturating_sub(first.header.sh_addr - segment.p_vaddr);
+                }
+                continue;
+            }
+            let delta = match deltas[i] {
+                Some(delta) => delta,
+                None => segment.p_vaddr.wrapping_sub(congruent_offset(
+                    offset,
+                    segment.p_vaddr,
+                    segment.p_align,
+                )?),
+            };
+            segment.p_offset = segment.p_vaddr.wrapping_sub(delta);
+            let mut end = if segment.p_offset == 0 {
+                headers_end
+            } else {
+                0
+            };
+            for section in covered.iter() {
+                if section.header.sh_type != u32::from(SectionType::Nobits) {
+                    end = end.max(section.header.sh_offset + section.header.sh_size);
+                }
+            }
+            segment.p_filesz = end.saturating_sub(segment.p_offset);
+        }
+
+        let header = &mut self.header;
+        if !has_sections {
+            header.e_shoff = 0;
+            header.e_shentsize = 0;
+            header.e_shnum = 0;
+            header.e_shstrndx = 0;
+            return Ok(());
+        }
+        header.e_shoff = align_up(offset, encoding.word_size() as u64)?;
+        header.e_shentsize = encoding.section_header_size() as u16;
+        // A count that does not fit in 16 bits moves to the null section.
+        let count = self.sections.len();
+        if count >= SHN_LORESERVE as usize {
+            header.e_shnum = 0;
+            self.sections[0].header.sh_size = count as u64;
+        } else {
+            header.e_shnum = count as u16;
+        }
+        Ok(())
+    }
+
+    /// Fills `.symtab` and `.strtab` from `symbols`, local symbols first as the ABI requires.
+    fn build_symbol_table(&mut self, encoding: Encoding) {
+        if self.symbols.is_empty() {
+            return;
+        }
+        let mut names = StringTableBuilder::new();
+        let mut data = vec![0u8; encoding.symbol_entry_size()];
+        let (local, global): (Vec<_>, Vec<_>) = self
+            .symbols
+            .iter()
+            .partition(|symbol| symbol.entry.binding() == SymbolBinding::Local);
+        let locals = local.len();
+        for symbol in local.into_iter().chain(global) {
+            let entry = ElfSymbolEntry {
+                st_name: names.add(&symbol.name),
+                ..symbol.entry
+            };
+            data.extend_from_slice(&entry.to_bytes(encoding));
+        }
+
+        let symtab = self.find_or_add_section(".symtab", SectionType::Symtab);
+        let strtab = self.find_or_add_section(".strtab", SectionType::Strtab);
+        self.sections[strtab].data = Cow::Owned(names.into_bytes());
+        self.sections[strtab].header.sh_addralign = 1;
+        let section = &mut self.sections[symtab];
+        section.data = Cow::Owned(data);
+        section.header.sh_link = strtab as u32;
+        section.header.sh_info = locals as u32 + 1;
+        section.header.sh_addralign = encoding.word_size() as u64;
+        section.header.sh_entsize = encoding.symbol_entry_size() as u64;
+    }
+
+    /// Fills `.shstrtab` with the names of every section and sets `sh_name` and `e_shstrndx`.
+    fn build_section_name_table(&mut self) {
+        let current = match self.header.e_shstrndx {
+            SHN_XINDEX => self
+                .sections
+                .first()
+                .map(|null| null.header.sh_link as usize),
+            index => Some(index as usize),
+        };
+        let index = match current {
+            Some(index)
+                if index != 0
+                    && self
+                        .sections
+                        .get(index)
+                        .map(|section| section.header.sh_type)
+                        == Some(u32::from(SectionType::Strtab)) =>
+            {
+                index
+            }
+            _ => self.find_or_add_section(".shstrtab", SectionType::Strtab),
+        };
+        let mut names = StringTableBuilder::new();
+        for section in self.sections.iter_mut() {
+            section.header.sh_name = names.add(&section.name);
+        }
+        self.sections[index].data = Cow::Owned(names.into_bytes());
+        self.sections[index].header.sh_addralign = 1;
+        if index >= SHN_LORESERVE as usize {
+            self.header.e_shstrndx = SHN_XINDEX;
+            self.sections[0].header.sh_link = index as u32;
+        } else {
+            self.header.e_shstrndx = index as u16;
+        }
+    }
+
+    fn find_or_add_section(&mut self, name: &'static str, section_type: SectionType) -> usize {
+        let found = self.sections.iter().position(|section| {
+            section.name == name && section.header.sh_type == u32::from(section_type)
+        });
+        match found {
+            Some(index) => index,
+            None => self.add_section(
+                name,
+                ElfSectionHeader {
+                    sh_name: 0,
+                    sh_type: u32::from(section_type),
+                    sh_flags: 0,
+                    sh_addr: 0,
+                    sh_offset: 0,
+                    sh_size: 0,
+                    sh_link: 0,
+                    sh_info: 0,
+                    sh_addralign: 1,
+                    sh_entsize: 0,
+                },
+                Vec::new(),
+            ),
+        }
+    }
+}
+
+fn null_section<'a>() -> WriterSection<'a> {
+    WriterSection {
+        name: Cow::Borrowed(""),
+        header: ElfSectionHeader {
+            sh_name: 0,
+            sh_type: u32::from(SectionType::Null),
+            sh_flags: 0,
+            sh_addr: 0,
+            sh_offset: 0,
+            sh_size: 0,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 0,
+            sh_entsize: 0,
+        },
+        data: Cow::Borrowed(&[]),
+    }
+}
+
+/// String table under construction. Equal strings share one entry.
+struct StringTableBuilder {
+    data: Vec<u8>,
+    indexes: HashMap<String, u32>,
+}
+
+impl StringTableBuilder {
+    fn new() -> StringTableBuilder {
+        StringTableBuilder {
+            data: vec![0],
+            indexes: HashMap::new(),
+        }
+    }
+
+    fn add(&mut self, name: &str) -> u32 {
+        if name.is_empty() {
+            return 0;
+        }
+        if let Some(index) = self.indexes.get(name) {
+            return *index;
+        }
+        let index = self.data.len() as u32;
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.push(0);
+        self.indexes.insert(name.to_string(), index);
+        index
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+fn is_load(segment: &ElfProgramHeader) -> bool {
+    segment.p_type == u32::from(ProgramType::Load)
+}
+
+/// Whether `section` is allocated and starts inside the memory of `segment`.
+fn segment_covers(segment: &ElfProgramHeader, section: &ElfSectionHeader) -> bool {
+    section.flags().contains(SectionFlags::ALLOC)
+        && section.sh_addr >= segment.p_vaddr
+        && section.sh_addr - segment.p_vaddr < segment.p_memsz.max(1)
+}
+
+/// Smallest offset from `offset` that is congruent with `address` modulo `align`.
+fn congruent_offset(offset: u64, address: u64, align: u64) -> Result<u64, ElfError> {
+    if align <= 1 {
+        return Ok(offset);
+    }
+    let wanted = address % align;
+    let current = offset % align;
+    let distance = if current <= wanted {
+        wanted - current
+    } else {
+        align - current + wanted
+    };
+    range_end(offset, distance)
+}
+
+fn align_up(value: u64, align: u64) -> Result<u64, ElfError> {
+    if align <= 1 {
+        return Ok(value);
+    }
+    value
+        .div_ceil(align)
+        .checked_mul(align)
+        .ok_or(ElfError::OffsetOverflow {
+            offset: value,
+            size: align,
+        })
+}
+
+/// End of the `size` bytes at `offset`.
+fn range_end(offset: u64, size: u64) -> Result<u64, ElfError> {
+    offset
+        .checked_add(size)
+        .ok_or(ElfError::OffsetOverflow { offset, size })
+}
+
+/// Offsets of the `count` entries of a header table at `offset`, each `entry_size` bytes
+/// apart and with room for the `size` bytes of a header.
+fn table_offsets(
+    offset: u64,
+    count: usize,
+    entry_size: u16,
+    size: u64,
+) -> Result<Vec<u64>, ElfError> {
+    if count == 0 {
+        return Ok(Vec::new());
+    }
+    let entry_size = entry_size as u64;
+    if entry_size < size {
+        return Err(ElfError::BadEntrySize {
+            offset,
+            entry_size,
+            size,
+        });
+    }
+    let mut offsets = Vec::with_capacity(count);
+    for index in 0..count as u64 {
+        let start = index
+            .checked_mul(entry_size)
+            .and_then(|distance| offset.checked_add(distance))
+            .ok_or(ElfError::OffsetOverflow {
+                offset,
+                size: entry_size.saturating_mul(count as u64),
+            })?;
+        range_end(start, size)?;
+        offsets.push(start);
+    }
+    Ok(offsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{load_segment, section_header, ELF64_LE};
+    use std::fs;
+
+    /// The test executable itself, a real elf file of the host.
+    fn host_binary() -> Vec<u8> {
+        fs::read(std::env::current_exe().unwrap()).unwrap()
+    }
+
+    fn note_header() -> ElfSectionHeader {
+        ElfSectionHeader {
+            sh_name: 0,
+            sh_type: u32::from(SectionType::Note),
+            sh_flags: 0,
+            sh_addr: 0,
+            sh_offset: 0,
+            sh_size: 0,
+            sh_link: 0,
+            sh_info: 0,
+            sh_addralign: 4,
+            sh_entsize: 0,
+        }
+    }
+
+    #[test]
+    fn writes_an_unmodified_file_back_byte_for_byte() {
+        let binary = host_binary();
+        let file = ElfFile::new(&binary).unwrap();
+        let output = ElfWriter::from_file(&file).unwrap().to_bytes().unwrap();
+        assert!(output == binary, "the written file differs from the input");
+    }
+
+    #[test]
+    fn appends_a_section() {
+        let binary = host_binary();
+        let file = ElfFile::new(&binary).unwrap();
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        let contents = b"appended by the writer test\0\0\0\0\0";
+        let index = writer.add_section(".note.test", note_header(), &contents[..]);
+        writer.keep_layout = false;
+        let output = writer.to_bytes().unwrap();
+
+        let written = ElfFile::new(&output).unwrap();
+        let names = written.get_section_names().unwrap();
+        assert_eq!(names[index], ".note.test");
+        let headers = written.get_section_headers().unwrap();
+        assert_eq!(headers.len(), file.get_section_headers().unwrap().len() + 1);
+        assert_eq!(
+            written.get_section_data(&headers[index]).unwrap(),
+            &contents[..]
+        );
+        // Allocated sections keep their contents and addresses.
+        for (before, after) in file
+            .get_section_headers()
+            .unwrap()
+            .iter()
+            .zip(headers.iter())
+            .filter(|(header, _)| header.flags().contains(SectionFlags::ALLOC))
+        {
+            assert_eq!(before.sh_addr, after.sh_addr);
+            assert_eq!(
+                file.get_section_data(before).unwrap(),
+                written.get_section_data(after).unwrap()
+            );
+        }
+        assert_eq!(
+            written.get_program_headers().unwrap().len(),
+            file.get_program_headers().unwrap().len()
+        );
+    }
+
+    #[test]
+    fn rejects_entries_smaller_than_a_header() {
+        let binary = host_binary();
+        let file = ElfFile::new(&binary).unwrap();
+        for (program, section) in [(1, 64), (0, 64), (56, 39), (56, 0)].iter() {
+            let mut writer = ElfWriter::from_file(&file).unwrap();
+            writer.header.e_phentsize = *program;
+            writer.header.e_shentsize = *section;
+            match writer.to_bytes() {
+                Err(ElfError::BadEntrySize { .. }) => {}
+                other => panic!("{:?}", other.map(|_| ())),
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_tables_past_the_largest_offset() {
+        let binary = host_binary();
+        let file = ElfFile::new(&binary).unwrap();
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        writer.header.e_shoff = u64::MAX - 100;
+        assert!(matches!(
+            writer.to_bytes(),
+            Err(ElfError::OffsetOverflow { .. })
+        ));
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        writer.header.e_phoff = u64::MAX - 10;
+        assert!(matches!(
+            writer.to_bytes(),
+            Err(ElfError::OffsetOverflow { .. })
+        ));
+    }
+
+    #[test]
+    fn ignores_the_offset_of_an_empty_table() {
+        let binary = host_binary();
+        let file = ElfFile::new(&binary).unwrap();
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        writer.segments.clear();
+        writer.header.e_phoff = u64::MAX;
+        writer.header.e_phentsize = 0;
+        assert_eq!(writer.to_bytes().unwrap().len(), binary.len());
+    }
+
+    #[test]
+    fn aligns_without_wrapping() {
+        assert_eq!(align_up(0x11, 8).unwrap(), 0x18);
+        assert!(matches!(
+            align_up(u64::MAX - 2, 8),
+            Err(ElfError::OffsetOverflow { .. })
+        ));
+        assert_eq!(congruent_offset(0x10, 0x401003, 0x1000).unwrap(), 0x1003);
+        assert!(congruent_offset(u64::MAX - 1, 0x1000, 0x1000).is_err());
+    }
+
+    #[test]
+    fn round_trips_every_class_and_byte_order() {
+        for class in [Class::Elf32, Class::Elf64].iter() {
+            for endian in [Endian::Little, Endian::Big].iter() {
+                let encoding = Encoding {
+                    class: *class,
+                    endian: *endian,
+                };
+                let mut writer = ElfWriter::new(encoding, ElfType::Exec, Machine::Arm);
+                writer.header.e_entry = 0x1_0004;
+                writer.segments.push(load_segment(0x1_0000, 0x20));
+                writer.add_section(
+                    ".data",
+                    ElfSectionHeader {
+                        sh_flags: SectionFlags::ALLOC.0 | SectionFlags::WRITE.0,
+                        sh_addr: 0x1_0000,
+                        sh_addralign: 4,
+                        ..section_header(SectionType::Progbits)
+                    },
+                    &b"0123456789abcdef"[..],
+                );
+                writer.symbols.push(WriterSymbol {
+                    name: "start".into(),
+                    entry: ElfSymbolEntry {
+                        st_name: 0,
+                        st_info: 0x12,
+                        st_other: 0,
+                        st_shndx: 1,
+                        st_value: 0x1_0004,
+                        st_size: 12,
+                    },
+                });
+                let binary = writer.to_bytes().unwrap();
+
+                let file = ElfFile::new(&binary).unwrap();
+                assert_eq!(file.get_encoding(), encoding);
+                assert_eq!(file.get_elf_header().e_entry, 0x1_0004);
+                assert_eq!(file.get_elf_header().e_machine, u16::from(Machine::Arm));
+                let segments = file.get_program_headers().unwrap();
+                assert_eq!(segments.len(), 1);
+                assert_eq!(
+                    (
+                        segments[0].p_vaddr,
+                        segments[0].p_memsz,
+                        segments[0].p_align
+                    ),
+                    (0x1_0000, 0x20, 0x1000)
+                );
+                assert_eq!(segments[0].p_offset % 0x1000, 0);
+                let data = writer.find_section(".data").unwrap();
+                let headers = file.get_section_headers().unwrap();
+                assert_eq!(
+                    file.get_section_data(&headers[data]).unwrap(),
+                    b"0123456789abcdef"
+                );
+                let symbols = file.get_symbol_table().unwrap();
+                let start = symbols.iter().find(|symbol| symbol.name == "start");
+                assert_eq!(start.unwrap().entry.st_value, 0x1_0004);
+
+                let output = ElfWriter::from_file(&file).unwrap().to_bytes().unwrap();
+                assert!(output == binary, "{:?} differs once written back", encoding);
+            }
+        }
+    }
+
+    #[test]
+    fn skips_empty_sections_past_the_end() {
+        let binary = host_binary();
+        let file = ElfFile::new(&binary).unwrap();
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        let index = writer.add_section(".empty", note_header(), &[][..]);
+        writer.sections[index].header.sh_offset = 1 << 40;
+        writer.header.e_shnum += 1;
+        let output = writer.to_bytes().unwrap();
+        let written = ElfFile::new(&output).unwrap();
+        assert_eq!(written.get_section_names().unwrap()[index], "");
+        assert_eq!(
+            written.get_section_headers().unwrap()[index].sh_offset,
+            1 << 40
+        );
+    }
+
+    #[test]
+    fn rejects_offsets_far_past_the_contents() {
+        let binary = host_binary();
+        let file = ElfFile::new(&binary).unwrap();
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        writer.header.e_shoff = 1 << 40;
+        assert!(matches!(
+            writer.to_bytes(),
+            Err(ElfError::FileTooLarge { size, .. }) if size > 1 << 40
+        ));
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        let index = writer
+            .sections
+            .iter()
+            .position(|section| !section.data.is_empty())
+            .unwrap();
+        writer.sections[index].header.sh_offset = 1 << 40;
+        assert!(matches!(
+            writer.to_bytes(),
+            Err(ElfError::FileTooLarge { .. })
+        ));
+        // Moving a section just past the end of the file is still fine.
+        let mut writer = ElfWriter::from_file(&file).unwrap();
+        writer.sections[index].header.sh_offset = binary.len() as u64;
+        let size = writer.sections[index].data.len();
+        assert_eq!(writer.to_bytes().unwrap().len(), binary.len() + size);
+    }
+
+    #[test]
+    fn rejects_more_segments_than_e_phnum_counts() {
+        let mut writer = ElfWriter::new(ELF64_LE, ElfType::Exec, Machine::X86_64);
+        writer.segments = vec![load_segment(0x1000, 0); PN_XNUM as usize - 1];
+        writer.to_bytes().unwrap();
+        assert_eq!(writer.header.e_phnum, PN_XNUM - 1);
+        writer.segments.push(load_segment(0x1000, 0));
+        assert!(matches!(
+            writer.to_bytes(),
+            Err(ElfError::TooManySegments { count: 0xffff })
+        ));
+    }
+}